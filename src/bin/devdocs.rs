@@ -1,11 +1,15 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
-use devdocs_mcp::tools::DocRouter;
+use axum::serve::Listener;
+use devdocs_mcp::config::{Config, HttpConfig};
+use devdocs_mcp::tools::DocService;
+use devdocs_mcp::transport::http_sse_server::App;
+use devdocs_mcp::transport::jsonrpc_server::JsonRpcServer;
+use devdocs_mcp::transport::listener::ListenAddress;
 use mcp_core::Content;
-use mcp_server::router::RouterService;
-use mcp_server::{ByteTransport, Router, Server};
+use mcp_server::Router;
 use serde_json::json;
-use std::net::SocketAddr;
+use std::path::PathBuf;
 use tokio::io::{stdin, stdout};
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
 use tracing_subscriber::{self, EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
@@ -15,6 +19,18 @@ use tracing_subscriber::{self, EnvFilter, layer::SubscriberExt, util::Subscriber
 #[command(propagate_version = true)]
 #[command(disable_version_flag = true)]
 struct Cli {
+    /// Path to a TOML config file (defaults to $DEVDOCS_CONFIG or ~/.config/devdocs-mcp/config.toml)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Directory for the persistent documentation cache (overrides the config file)
+    #[arg(long, global = true)]
+    cache_dir: Option<PathBuf>,
+
+    /// Project whose lockfiles pin the version of lookups that omit one (overrides the config file)
+    #[arg(long, global = true)]
+    project_root: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -29,7 +45,7 @@ enum Commands {
     },
     /// Run the server with HTTP/SSE interface
     Http {
-        /// Address to bind the HTTP server to
+        /// Address to bind the HTTP server to, or `unix:<path>` for a Unix domain socket
         #[arg(short, long, default_value = "127.0.0.1:8080")]
         address: String,
         
@@ -81,9 +97,18 @@ enum Commands {
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    let mut config = Config::load(cli.config.as_deref())?;
+    if let Some(dir) = cli.cache_dir {
+        config.cache.persistent = true;
+        config.cache.dir = Some(dir);
+    }
+    if let Some(root) = cli.project_root {
+        config.project.root = Some(root);
+    }
+
     match cli.command {
-        Commands::Stdio { debug } => run_stdio_server(debug).await,
-        Commands::Http { address, debug } => run_http_server(address, debug).await,
+        Commands::Stdio { debug } => run_stdio_server(config, debug).await,
+        Commands::Http { address, debug } => run_http_server(config, address, debug).await,
         Commands::Test { 
             tool, 
             crate_name, 
@@ -94,7 +119,7 @@ async fn main() -> Result<()> {
             format,
             output,
             debug 
-        } => run_test_tool(TestToolConfig {
+        } => run_test_tool(config, TestToolConfig {
             tool,
             crate_name,
             item_path,
@@ -108,7 +133,7 @@ async fn main() -> Result<()> {
     }
 }
 
-async fn run_stdio_server(debug: bool) -> Result<()> {
+async fn run_stdio_server(config: Config, debug: bool) -> Result<()> {
     // Set up file appender for logging
     let file_appender = RollingFileAppender::new(Rotation::DAILY, "logs", "stdio-server.log");
 
@@ -127,17 +152,19 @@ async fn run_stdio_server(debug: bool) -> Result<()> {
     tracing::info!("Starting MCP documentation server in STDIN/STDOUT mode");
 
     // Create an instance of our documentation router
-    let router = RouterService(DocRouter::new());
+    let router = DocService::new(config.build_router());
 
     // Create and run the server
-    let server = Server::new(router);
-    let transport = ByteTransport::new(stdin(), stdout());
+    let server = JsonRpcServer::new(router);
 
     tracing::info!("Documentation server initialized and ready to handle requests");
-    Ok(server.run(transport).await?)
+    Ok(server.run(stdin(), stdout()).await?)
 }
 
-async fn run_http_server(address: String, debug: bool) -> Result<()> {
+/// Serve MCP over HTTP on `address` until interrupted
+///
+/// A local server only answers requests addressed to it, which stops DNS rebinding.
+async fn run_http_server(config: Config, address: String, debug: bool) -> Result<()> {
     // Setup tracing
     let level = if debug { "debug" } else { "info" };
     
@@ -149,20 +176,103 @@ async fn run_http_server(address: String, debug: bool) -> Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    // Parse socket address
-    let addr: SocketAddr = address.parse()?;
-    let listener = tokio::net::TcpListener::bind(addr).await?;
+    let address: ListenAddress = address.parse()?;
+    let scheme = if config.http.tls.paths()?.is_some() { "https" } else { "http" };
+    let auth = config.http.auth();
+    let authenticated = auth.is_enabled();
+
+    let cors = match &address {
+        ListenAddress::Tcp(addr) if addr.ip().is_loopback() => config.http.cors().loopback_only(),
+        _ => config.http.cors(),
+    };
 
-    tracing::debug!("Rust Documentation Server listening on {}", listener.local_addr()?);
-    tracing::info!("Access the Rust Documentation Server at http://{}/sse", addr);
-    
     // Create app and run server
-    let app = devdocs_mcp::transport::http_sse_server::App::new();
-    axum::serve(listener, app.router()).await?;
-    
+    let app = App::with_router(config.build_router())
+        .with_auth(auth)
+        .with_cors(cors)
+        .with_limits(config.http.limits());
+
+    match address {
+        ListenAddress::Tcp(addr) => {
+            let listener = tokio::net::TcpListener::bind(addr).await?;
+            tracing::debug!("Rust Documentation Server listening on {}", listener.local_addr()?);
+            tracing::info!("Access the Rust Documentation Server at {scheme}://{addr}/mcp (legacy SSE: {scheme}://{addr}/sse)");
+            if !authenticated && !addr.ip().is_loopback() {
+                tracing::warn!("Listening on {} without authentication; set [http.auth] tokens or DEVDOCS_HTTP_TOKENS", addr);
+            }
+            serve(app, listener, &config.http).await
+        }
+        #[cfg(unix)]
+        ListenAddress::Unix(path) => {
+            let listener = devdocs_mcp::transport::listener::bind_unix(&path, config.http.socket_mode)?;
+            tracing::info!("Access the Rust Documentation Server at {scheme}://localhost/mcp through the socket {}", path.display());
+            let result = serve(app, listener, &config.http).await;
+            let _ = std::fs::remove_file(&path);
+            result
+        }
+        #[cfg(not(unix))]
+        ListenAddress::Unix(_) => anyhow::bail!("Unix domain sockets are not supported on this platform"),
+    }
+}
+
+/// Serve `app` on `listener` until a shutdown signal, terminating TLS first if configured
+async fn serve<L>(app: App, listener: L, config: &HttpConfig) -> Result<()>
+where
+    L: Listener,
+    L::Addr: Clone + std::fmt::Debug + 'static,
+{
+    let grace = std::time::Duration::from_secs(config.shutdown_timeout_secs);
+
+    #[cfg(feature = "tls")]
+    if let Some((cert, key)) = config.tls.paths()? {
+        use devdocs_mcp::transport::tls::{TlsCertificate, TlsListener};
+
+        let mut certificate = TlsCertificate::load(cert, key)?;
+        if let Some(interval) = config.tls.reload_interval() {
+            certificate = certificate.reload_every(interval);
+        }
+        app.serve(TlsListener::new(listener, certificate), shutdown_signal(), grace).await?;
+        return Ok(());
+    }
+    #[cfg(not(feature = "tls"))]
+    if config.tls.paths()?.is_some() {
+        anyhow::bail!("[http.tls] is set, but devdocs was built without the `tls` feature");
+    }
+
+    app.serve(listener, shutdown_signal(), grace).await?;
     Ok(())
 }
 
+/// Resolve on Ctrl-C or, on Unix, SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!(?e, "failed to listen for Ctrl-C");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!(?e, "failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
 /// Configuration for the test tool
 struct TestToolConfig {
     tool: String,
@@ -177,7 +287,7 @@ struct TestToolConfig {
 }
 
 /// Run a direct test of a documentation tool from the CLI
-async fn run_test_tool(config: TestToolConfig) -> Result<()> {
+async fn run_test_tool(app_config: Config, config: TestToolConfig) -> Result<()> {
     let TestToolConfig {
         tool,
         crate_name,
@@ -223,7 +333,7 @@ async fn run_test_tool(config: TestToolConfig) -> Result<()> {
         .init();
 
     // Create router instance
-    let router = DocRouter::new();
+    let router = app_config.build_router();
     
     tracing::info!("Testing tool: {}", tool);
     
@@ -0,0 +1,268 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::tools::docs::auth::{Credentials, CredentialsConfig};
+use crate::tools::docs::cache::CachePolicy;
+use crate::tools::docs::registry::RegistryUrls;
+use crate::tools::{DocCache, DocRouter};
+use crate::transport::cors::{Cors, CorsConfig};
+use crate::transport::http_auth::{HttpAuth, HttpAuthConfig};
+use crate::transport::http_sse_server::SessionLimits;
+
+/// Server configuration, loaded from a TOML file
+///
+/// Every section is optional; missing values fall back to their defaults.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub cache: CacheConfig,
+    pub registries: RegistryUrls,
+    pub credentials: CredentialsConfig,
+    pub rustdoc: RustdocConfig,
+    pub project: ProjectConfig,
+    pub http: HttpConfig,
+}
+
+const MB: u64 = 1024 * 1024;
+
+/// `[cache]` section: where documentation lookups are persisted and for how long
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    /// Persist cache entries to disk so they survive restarts
+    pub persistent: bool,
+    /// Cache directory (defaults to `$XDG_CACHE_HOME/devdocs-mcp`)
+    pub dir: Option<PathBuf>,
+    /// Maximum number of entries kept in memory before the least recently used are evicted
+    pub max_entries: usize,
+    /// Megabytes of documentation kept in memory (0 = unbounded)
+    pub max_memory_mb: u64,
+    /// Megabytes the cache directory may take up before the least recently used files are removed (0 = unbounded)
+    pub max_disk_mb: u64,
+    /// Seconds before unversioned lookups and search results expire (0 = never)
+    pub latest_ttl_secs: u64,
+    /// Seconds before lookups of an explicit version expire (0 = never)
+    pub pinned_ttl_secs: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        let policy = CachePolicy::default();
+        Self {
+            persistent: true,
+            dir: None,
+            max_entries: policy.max_entries,
+            max_memory_mb: policy.max_memory_bytes.map_or(0, |bytes| bytes as u64 / MB),
+            max_disk_mb: policy.max_disk_bytes.map_or(0, |bytes| bytes / MB),
+            latest_ttl_secs: policy.latest_ttl.map_or(0, |ttl| ttl.as_secs()),
+            pinned_ttl_secs: policy.pinned_ttl.map_or(0, |ttl| ttl.as_secs()),
+        }
+    }
+}
+
+impl CacheConfig {
+    /// Expiry and size limits described by this section
+    pub fn policy(&self) -> CachePolicy {
+        let ttl = |secs: u64| (secs > 0).then(|| Duration::from_secs(secs));
+        CachePolicy {
+            latest_ttl: ttl(self.latest_ttl_secs),
+            pinned_ttl: ttl(self.pinned_ttl_secs),
+            max_entries: self.max_entries,
+            max_memory_bytes: (self.max_memory_mb > 0).then(|| (self.max_memory_mb * MB) as usize),
+            max_disk_bytes: (self.max_disk_mb > 0).then(|| self.max_disk_mb * MB),
+        }
+    }
+
+    /// Build the documentation cache described by this section
+    pub fn build(&self) -> DocCache {
+        let cache = if !self.persistent {
+            DocCache::new()
+        } else {
+            match self.dir.clone().or_else(DocCache::default_dir) {
+                Some(dir) => DocCache::persistent(dir),
+                None => {
+                    tracing::warn!("No cache directory could be determined, using an in-memory cache");
+                    DocCache::new()
+                }
+            }
+        };
+        cache.with_policy(self.policy())
+    }
+}
+
+/// `[rustdoc]` section: where structured rustdoc JSON is read from
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RustdocConfig {
+    /// Download rustdoc JSON from docs.rs when it has been built for the crate
+    pub remote: bool,
+    /// Directories holding `cargo doc --output-format json` output (for example `target/doc`)
+    pub local_dirs: Vec<PathBuf>,
+}
+
+impl Default for RustdocConfig {
+    fn default() -> Self {
+        Self {
+            remote: true,
+            local_dirs: Vec::new(),
+        }
+    }
+}
+
+/// `[project]` section: the local project whose lockfiles pin default versions
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    /// Directory holding the project's manifests and lockfiles
+    pub root: Option<PathBuf>,
+}
+
+/// `[http]` section: settings of `devdocs http`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpConfig {
+    /// Sessions open at once before new ones are refused (0 = unlimited)
+    pub max_sessions: usize,
+    /// Seconds without a request before a session is closed (0 = never)
+    pub idle_timeout_secs: u64,
+    /// Seconds between keep-alive comments on SSE streams (0 = none)
+    pub keep_alive_secs: u64,
+    /// Seconds running requests get to answer once shutdown begins
+    pub shutdown_timeout_secs: u64,
+    /// Permissions of the socket file when listening on a Unix socket, e.g. `0o660`
+    pub socket_mode: Option<u32>,
+    pub auth: HttpAuthConfig,
+    pub cors: CorsConfig,
+    pub tls: TlsConfig,
+}
+
+/// `[http.tls]` section: certificate and key to serve HTTPS with
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TlsConfig {
+    /// PEM file holding the certificate chain
+    pub cert: Option<PathBuf>,
+    /// PEM file holding the private key
+    pub key: Option<PathBuf>,
+    /// Seconds between checks for a renewed certificate (0 = never reload)
+    pub reload_secs: u64,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        Self {
+            cert: None,
+            key: None,
+            reload_secs: 60,
+        }
+    }
+}
+
+impl TlsConfig {
+    /// Certificate and key paths, or `None` to serve plain HTTP
+    pub fn paths(&self) -> Result<Option<(&Path, &Path)>> {
+        match (&self.cert, &self.key) {
+            (Some(cert), Some(key)) => Ok(Some((cert, key))),
+            (None, None) => Ok(None),
+            _ => anyhow::bail!("[http.tls] needs both cert and key"),
+        }
+    }
+
+    /// How often to check for a renewed certificate
+    pub fn reload_interval(&self) -> Option<Duration> {
+        (self.reload_secs > 0).then(|| Duration::from_secs(self.reload_secs))
+    }
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        let limits = SessionLimits::default();
+        Self {
+            max_sessions: limits.max_sessions.unwrap_or(0),
+            idle_timeout_secs: limits.idle_timeout.map_or(0, |timeout| timeout.as_secs()),
+            keep_alive_secs: limits.keep_alive.map_or(0, |interval| interval.as_secs()),
+            shutdown_timeout_secs: 30,
+            socket_mode: None,
+            auth: HttpAuthConfig::default(),
+            cors: CorsConfig::default(),
+            tls: TlsConfig::default(),
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Session limits described by this section
+    pub fn limits(&self) -> SessionLimits {
+        let secs = |secs: u64| (secs > 0).then(|| Duration::from_secs(secs));
+        SessionLimits {
+            max_sessions: (self.max_sessions > 0).then_some(self.max_sessions),
+            idle_timeout: secs(self.idle_timeout_secs),
+            keep_alive: secs(self.keep_alive_secs),
+        }
+    }
+
+    /// Authentication required from HTTP callers, including `DEVDOCS_HTTP_*` environment variables
+    pub fn auth(&self) -> HttpAuth {
+        HttpAuth::from_config(&self.auth)
+    }
+
+    /// Cross-origin policy for browser clients
+    pub fn cors(&self) -> Cors {
+        Cors::from_config(&self.cors)
+    }
+}
+
+impl Config {
+    /// Load the configuration from `path`, `$DEVDOCS_CONFIG` or the default location
+    ///
+    /// An explicitly requested file must exist; the default location is optional.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let explicit = path
+            .map(Path::to_path_buf)
+            .or_else(|| std::env::var_os("DEVDOCS_CONFIG").map(PathBuf::from));
+
+        let path = match explicit {
+            Some(path) => path,
+            None => match Self::default_path().filter(|p| p.exists()) {
+                Some(path) => path,
+                None => return Ok(Self::default()),
+            },
+        };
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        Self::parse(&content).with_context(|| format!("Invalid config file {}", path.display()))
+    }
+
+    /// Parse a configuration from TOML text
+    pub fn parse(content: &str) -> Result<Self> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Default config file: `$XDG_CONFIG_HOME/devdocs-mcp/config.toml`, falling back to `~/.config`
+    pub fn default_path() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(base.join("devdocs-mcp").join("config.toml"))
+    }
+
+    /// Build a documentation router from this configuration
+    pub fn build_router(&self) -> DocRouter {
+        let router = DocRouter::with_cache(self.cache.build())
+            .with_registries(self.registries.clone())
+            .with_credentials(Credentials::discover(&self.credentials, &self.registries))
+            .with_rustdoc_sources(self.rustdoc.local_dirs.clone(), self.rustdoc.remote);
+        match &self.project.root {
+            Some(root) => router.with_project_root(root.clone()),
+            None => router,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
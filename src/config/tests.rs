@@ -0,0 +1,68 @@
+use std::path::Path;
+use std::time::Duration;
+
+use crate::config::Config;
+
+#[test]
+fn test_cache_config() {
+    let config = Config::parse("").unwrap();
+    assert!(config.cache.persistent);
+    assert!(config.cache.dir.is_none());
+
+    let config = Config::parse(
+        r#"
+        [cache]
+        persistent = false
+        dir = "/tmp/devdocs"
+        max_entries = 10
+        max_disk_mb = 0
+        latest_ttl_secs = 60
+        pinned_ttl_secs = 0
+        "#,
+    )
+    .unwrap();
+    assert!(!config.cache.persistent);
+    assert_eq!(config.cache.dir.as_deref(), Some(Path::new("/tmp/devdocs")));
+
+    let policy = config.cache.policy();
+    assert_eq!(policy.max_entries, 10);
+    assert_eq!(policy.latest_ttl, Some(Duration::from_secs(60)));
+    assert_eq!(policy.pinned_ttl, None);
+    assert_eq!(policy.max_memory_bytes, Some(64 * 1024 * 1024));
+    assert_eq!(policy.max_disk_bytes, None);
+}
+
+#[test]
+fn test_registry_and_project_config() {
+    let config = Config::parse("[registries]\nnpm = \"http://localhost:4873\"").unwrap();
+    assert_eq!(config.registries.npm, "http://localhost:4873");
+    assert_eq!(config.registries.docs_rs, "https://docs.rs");
+    assert_eq!(config.project.root, None);
+
+    let config = Config::parse("[project]\nroot = \"/src/app\"").unwrap();
+    assert_eq!(config.project.root.as_deref(), Some(Path::new("/src/app")));
+}
+
+#[test]
+fn test_http_session_limits_config() {
+    let limits = Config::parse("[http]\nmax_sessions = 0\nidle_timeout_secs = 60").unwrap().http.limits();
+    assert_eq!(limits.max_sessions, None);
+    assert_eq!(limits.idle_timeout, Some(Duration::from_secs(60)));
+    assert_eq!(limits.keep_alive, Some(Duration::from_secs(15)));
+}
+
+#[test]
+fn test_http_listener_config() {
+    let http = Config::parse("[http]\nsocket_mode = 0o660\n[http.tls]\ncert = \"cert.pem\"\nkey = \"key.pem\"\nreload_secs = 0")
+        .unwrap()
+        .http;
+    assert_eq!(http.socket_mode, Some(0o660));
+    assert!(http.tls.paths().unwrap().is_some());
+    assert_eq!(http.tls.reload_interval(), None);
+    assert!(Config::parse("[http.tls]\ncert = \"cert.pem\"").unwrap().http.tls.paths().is_err());
+}
+
+#[test]
+fn test_unknown_keys_rejected() {
+    assert!(Config::parse("[cache]\nunknown = 1").is_err());
+}
@@ -1,3 +1,6 @@
+pub mod config;
 pub mod tools;
 pub mod transport;
 
+#[cfg(test)]
+mod test_support;
@@ -0,0 +1,57 @@
+//! Fixtures shared by the unit tests
+
+use std::net::SocketAddr;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+use crate::transport::http_sse_server::App;
+
+/// A fresh directory under the system temp dir, removed with its contents when dropped
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Create `devdocs-{label}-{random}` under the system temp dir
+    pub fn new(label: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("devdocs-{}-{:016x}", label, rand::random::<u64>()));
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Serve `app` on a loopback port and return its address
+pub async fn listen(app: App) -> SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let router = app.router();
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    addr
+}
+
+/// Serve `app` on a loopback port and return its base URL
+pub async fn serve(app: App) -> String {
+    format!("http://{}", listen(app).await)
+}
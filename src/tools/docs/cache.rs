@@ -0,0 +1,574 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, Notify};
+
+use super::registry::base_url;
+
+/// Cache for documentation lookups to avoid repeated requests.
+///
+/// Entries are always kept in memory, bounded by [`CachePolicy::max_entries`] and
+/// [`CachePolicy::max_memory_bytes`] with least-recently-used eviction. When the cache is
+/// created with [`DocCache::persistent`], every entry is also written to a directory on
+/// disk so that it survives restarts and is shared by every process pointed at the same
+/// directory (for example `devdocs stdio` and `devdocs http`). The directory is swept of
+/// expired entries when first used and kept under [`CachePolicy::max_disk_bytes`] by
+/// removing the least recently used files.
+#[derive(Clone)]
+pub struct DocCache {
+    inner: Arc<Mutex<Entries>>,
+    counters: Arc<Counters>,
+    writes: Arc<PendingWrites>,
+    disk: Arc<DiskUsage>,
+    policy: CachePolicy,
+    dir: Option<Arc<PathBuf>>,
+    /// Base URL of the registry whose responses this view holds, unless it is the default one
+    scope: Option<Arc<str>>,
+}
+
+/// Expiry and size limits of a [`DocCache`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachePolicy {
+    /// Time-to-live of unversioned ("latest") lookups and search results
+    pub latest_ttl: Option<Duration>,
+    /// Time-to-live of lookups pinned to an explicit version (`None` never expires)
+    pub pinned_ttl: Option<Duration>,
+    /// Maximum number of entries kept in memory
+    pub max_entries: usize,
+    /// Bytes of keys and values kept in memory (`None` is unbounded)
+    pub max_memory_bytes: Option<usize>,
+    /// Bytes the cache directory may take up (`None` is unbounded)
+    pub max_disk_bytes: Option<u64>,
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        Self {
+            latest_ttl: Some(Duration::from_secs(60 * 60)),
+            pinned_ttl: None,
+            max_entries: 512,
+            max_memory_bytes: Some(64 * 1024 * 1024),
+            max_disk_bytes: Some(1024 * 1024 * 1024),
+        }
+    }
+}
+
+/// How long a cached value stays valid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    /// Unversioned lookups and searches whose upstream result can change
+    Latest,
+    /// Lookups pinned to an exact version, which never change upstream
+    Pinned,
+}
+
+impl Freshness {
+    /// `Pinned` when an exact version such as `1.2.3` or Go's `v1.2.3` was requested
+    ///
+    /// Tags like `next`, ranges like `^1.2` and partial versions like `1.0` resolve to
+    /// whatever is newest upstream, so they are `Latest`.
+    pub fn for_version(version: &Option<String>) -> Self {
+        match version.as_deref() {
+            Some(v) if semver::Version::parse(v.strip_prefix('v').unwrap_or(v)).is_ok() => Freshness::Pinned,
+            _ => Freshness::Latest,
+        }
+    }
+}
+
+/// Snapshot of the hit, miss and eviction counters of a [`DocCache`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub entries: usize,
+}
+
+#[derive(Default)]
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+/// Disk writes in progress, so shutdown can wait for them
+#[derive(Default)]
+struct PendingWrites {
+    count: AtomicU64,
+    done: Notify,
+}
+
+/// Counts one disk write as pending until dropped, even if the write is cancelled
+struct PendingWrite(Arc<PendingWrites>);
+
+impl PendingWrite {
+    fn start(writes: &Arc<PendingWrites>) -> Self {
+        writes.count.fetch_add(1, Ordering::SeqCst);
+        Self(writes.clone())
+    }
+}
+
+/// Bytes used by the cache directory, as of the last sweep plus the writes since
+#[derive(Default)]
+struct DiskUsage {
+    bytes: AtomicU64,
+    swept: AtomicBool,
+    sweeping: Arc<Mutex<()>>,
+}
+
+impl Drop for PendingWrite {
+    fn drop(&mut self) {
+        if self.0.count.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.done.notify_waiters();
+        }
+    }
+}
+
+struct Entry {
+    value: String,
+    expires_at: Option<SystemTime>,
+    last_used: u64,
+}
+
+/// In-memory entries plus their recency order (`last_used` tick -> key)
+#[derive(Default)]
+struct Entries {
+    map: HashMap<String, Entry>,
+    lru: BTreeMap<u64, String>,
+    tick: u64,
+    /// Bytes of every key and value held
+    bytes: usize,
+}
+
+impl Entries {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn get(&mut self, key: &str, now: SystemTime) -> Option<String> {
+        let tick = self.next_tick();
+        let entry = self.map.get_mut(key)?;
+        if entry.expires_at.is_some_and(|at| at <= now) {
+            self.remove(key);
+            return None;
+        }
+        self.lru.remove(&entry.last_used);
+        entry.last_used = tick;
+        self.lru.insert(tick, key.to_string());
+        Some(entry.value.clone())
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.map.remove(key) {
+            self.lru.remove(&entry.last_used);
+            self.bytes -= key.len() + entry.value.len();
+        }
+    }
+
+    /// Insert an entry and return how many entries were evicted to make room
+    ///
+    /// A value larger than the whole byte budget is not kept at all.
+    fn insert(&mut self, key: String, value: String, expires_at: Option<SystemTime>, policy: &CachePolicy) -> u64 {
+        self.remove(&key);
+        let max_bytes = policy.max_memory_bytes.unwrap_or(usize::MAX);
+        if key.len() + value.len() > max_bytes {
+            return 0;
+        }
+        let tick = self.next_tick();
+        self.bytes += key.len() + value.len();
+        self.map.insert(key.clone(), Entry { value, expires_at, last_used: tick });
+        self.lru.insert(tick, key);
+
+        let mut evicted = 0;
+        while self.map.len() > policy.max_entries.max(1) || self.bytes > max_bytes {
+            let Some(oldest) = self.lru.values().next().cloned() else {
+                break;
+            };
+            self.remove(&oldest);
+            evicted += 1;
+        }
+        evicted
+    }
+}
+
+/// On-disk representation of a single cache entry
+#[derive(Serialize, Deserialize)]
+struct DiskEntry {
+    key: String,
+    value: String,
+    stored_at: u64,
+    #[serde(default)]
+    expires_at: Option<u64>,
+}
+
+impl Default for DocCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DocCache {
+    /// Create an in-memory cache
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Entries::default())),
+            counters: Arc::new(Counters::default()),
+            writes: Arc::new(PendingWrites::default()),
+            disk: Arc::new(DiskUsage::default()),
+            policy: CachePolicy::default(),
+            dir: None,
+            scope: None,
+        }
+    }
+
+    /// Create a cache that also persists its entries under `dir`
+    pub fn persistent(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: Some(Arc::new(dir.into())),
+            ..Self::new()
+        }
+    }
+
+    /// Replace the expiry and size limits of this cache
+    pub fn with_policy(mut self, policy: CachePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// A view of this cache for documentation fetched from the registry at `url`
+    ///
+    /// Entries from a registry other than the public `default` one are kept apart, so after
+    /// `[registries]` is pointed at a mirror, or back, the other registry's documentation
+    /// is never served from the cache.
+    pub fn for_registry(&self, url: &str, default: &str) -> Self {
+        let url = base_url(url);
+        Self {
+            scope: (url != base_url(default)).then(|| Arc::from(url)),
+            ..self.clone()
+        }
+    }
+
+    fn scoped_key(&self, key: &str) -> String {
+        match &self.scope {
+            Some(scope) => format!("{} {}", scope, key),
+            None => key.to_string(),
+        }
+    }
+
+    /// Default cache directory: `$XDG_CACHE_HOME/devdocs-mcp`, falling back to `~/.cache/devdocs-mcp`
+    pub fn default_dir() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CACHE_HOME")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+        Some(base.join("devdocs-mcp"))
+    }
+
+    /// Directory backing this cache, if it is persistent
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref().map(PathBuf::as_path)
+    }
+
+    /// Expiry and size limits of this cache
+    pub fn policy(&self) -> &CachePolicy {
+        &self.policy
+    }
+
+    /// Current hit, miss and eviction counters
+    pub async fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+            evictions: self.counters.evictions.load(Ordering::Relaxed),
+            entries: self.inner.lock().await.map.len(),
+        }
+    }
+
+    /// Wait until the entries being written to disk are persisted, e.g. before the process exits
+    pub async fn flush(&self) {
+        loop {
+            let done = self.writes.done.notified();
+            if self.writes.count.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            done.await;
+        }
+    }
+
+    pub async fn get(&self, key: &str) -> Option<String> {
+        let key = &self.scoped_key(key);
+        let now = SystemTime::now();
+        if let Some(value) = self.inner.lock().await.get(key, now) {
+            self.counters.hits.fetch_add(1, Ordering::Relaxed);
+            return Some(value);
+        }
+
+        let Some((value, expires_at)) = self.read_from_disk(key, now).await else {
+            self.counters.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        };
+        self.counters.hits.fetch_add(1, Ordering::Relaxed);
+        self.insert_memory(key.to_string(), value.clone(), expires_at).await;
+        Some(value)
+    }
+
+    /// Store an unversioned lookup or search result
+    pub async fn set(&self, key: String, value: String) {
+        self.set_with(key, value, Freshness::Latest).await;
+    }
+
+    /// Store a value with the time-to-live matching `freshness`
+    pub async fn set_with(&self, key: String, value: String, freshness: Freshness) {
+        let ttl = match freshness {
+            Freshness::Latest => self.policy.latest_ttl,
+            Freshness::Pinned => self.policy.pinned_ttl,
+        };
+        let expires_at = ttl.map(|ttl| SystemTime::now() + ttl);
+
+        let key = self.scoped_key(&key);
+        self.write_to_disk(&key, &value, expires_at).await;
+        self.insert_memory(key, value, expires_at).await;
+    }
+
+    async fn insert_memory(&self, key: String, value: String, expires_at: Option<SystemTime>) {
+        let evicted = self
+            .inner
+            .lock()
+            .await
+            .insert(key, value, expires_at, &self.policy);
+        if evicted > 0 {
+            self.counters.evictions.fetch_add(evicted, Ordering::Relaxed);
+        }
+    }
+
+    fn entry_path(&self, key: &str) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{:016x}.json", fnv1a(key.as_bytes()))))
+    }
+
+    /// The entry stored for `key`, if it has not expired
+    ///
+    /// A read touches the file, since modification times order files for eviction.
+    async fn read_from_disk(&self, key: &str, now: SystemTime) -> Option<(String, Option<SystemTime>)> {
+        let path = self.entry_path(key)?;
+        self.sweep_if_needed();
+        let bytes = tokio::fs::read(&path).await.ok()?;
+        let entry = match serde_json::from_slice::<DiskEntry>(&bytes) {
+            Ok(entry) if entry.key == key => entry,
+            Ok(_) => return None,
+            Err(e) => {
+                tracing::warn!(path = %path.display(), "Ignoring corrupt cache entry: {}", e);
+                return None;
+            }
+        };
+
+        let expires_at = entry.expires_at.map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+        if expires_at.is_some_and(|at| at <= now) {
+            if tokio::fs::remove_file(&path).await.is_ok() {
+                self.disk.release(bytes.len() as u64);
+            }
+            return None;
+        }
+
+        tokio::task::spawn_blocking(move || {
+            std::fs::File::options()
+                .append(true)
+                .open(&path)
+                .and_then(|file| file.set_modified(SystemTime::now()))
+        });
+        Some((entry.value, expires_at))
+    }
+
+    /// Store `value` under `key`, to expire at `expires_at`
+    ///
+    /// The entry is written to a temporary file and renamed into place, so concurrent readers
+    /// never see a partial one.
+    async fn write_to_disk(&self, key: &str, value: &str, expires_at: Option<SystemTime>) {
+        let (Some(dir), Some(path)) = (self.dir.as_ref(), self.entry_path(key)) else {
+            return;
+        };
+        let _pending = PendingWrite::start(&self.writes);
+
+        let entry = DiskEntry {
+            key: key.to_string(),
+            value: value.to_string(),
+            stored_at: unix_secs(SystemTime::now()),
+            expires_at: expires_at.map(unix_secs),
+        };
+        let bytes = match serde_json::to_vec(&entry) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                tracing::warn!("Failed to serialize cache entry: {}", e);
+                return;
+            }
+        };
+
+        let tmp = path.with_extension(format!("{:08x}.tmp", rand::random::<u32>()));
+        let replaced = tokio::fs::metadata(&path).await.map_or(0, |m| m.len());
+        let result = async {
+            tokio::fs::create_dir_all(dir.as_path()).await?;
+            tokio::fs::write(&tmp, &bytes).await?;
+            tokio::fs::rename(&tmp, &path).await
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                self.disk.bytes.fetch_add(bytes.len() as u64, Ordering::SeqCst);
+                self.disk.release(replaced);
+                self.sweep_if_needed();
+            }
+            Err(e) => {
+                tracing::warn!(path = %path.display(), "Failed to persist cache entry: {}", e);
+                let _ = tokio::fs::remove_file(&tmp).await;
+            }
+        }
+    }
+
+    /// Sweep the cache directory when it is first used or has grown past its budget
+    ///
+    /// The sweep runs in the background and counts as a pending write for [`DocCache::flush`].
+    fn sweep_if_needed(&self) {
+        let Some(dir) = self.dir.clone() else {
+            return;
+        };
+        let over_budget = self
+            .policy
+            .max_disk_bytes
+            .is_some_and(|max| self.disk.bytes.load(Ordering::SeqCst) > max);
+        let first = !self.disk.swept.swap(true, Ordering::SeqCst);
+        if !first && !over_budget {
+            return;
+        }
+        let Ok(sweeping) = self.disk.sweeping.clone().try_lock_owned() else {
+            return;
+        };
+
+        let pending = PendingWrite::start(&self.writes);
+        let disk = self.disk.clone();
+        let max_bytes = self.policy.max_disk_bytes;
+        tokio::spawn(async move {
+            let swept = tokio::task::spawn_blocking(move || sweep(&dir, max_bytes, SystemTime::now())).await;
+            match swept {
+                Ok(Ok(bytes)) => disk.bytes.store(bytes, Ordering::SeqCst),
+                Ok(Err(e)) => tracing::warn!("Failed to sweep the cache directory: {}", e),
+                Err(e) => tracing::warn!("Cache directory sweep panicked: {}", e),
+            }
+            drop((sweeping, pending));
+        });
+    }
+}
+
+impl DiskUsage {
+    fn release(&self, bytes: u64) {
+        let _ = self
+            .bytes
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used| Some(used.saturating_sub(bytes)));
+    }
+}
+
+/// Only the expiry of a [`DiskEntry`], so a sweep need not copy the values
+#[derive(Deserialize)]
+struct DiskExpiry {
+    #[serde(default)]
+    expires_at: Option<u64>,
+}
+
+/// Kind of a file this cache writes, judged by its name alone
+#[derive(PartialEq)]
+enum CacheFile {
+    /// `{hash:016x}.json`
+    Entry,
+    /// `{hash:016x}.{random:08x}.tmp`, renamed into place once written
+    Temporary,
+}
+
+impl CacheFile {
+    fn from_name(name: &str) -> Option<Self> {
+        let hex = |s: &str, len: usize| s.len() == len && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'));
+        match name.split('.').collect::<Vec<_>>()[..] {
+            [hash, "json"] if hex(hash, 16) => Some(CacheFile::Entry),
+            [hash, random, "tmp"] if hex(hash, 16) && hex(random, 8) => Some(CacheFile::Temporary),
+            _ => None,
+        }
+    }
+}
+
+/// Remove expired, corrupt and abandoned files from `dir`, then the least recently used
+/// ones until at most 90% of `max_bytes` is used. Returns the bytes still used.
+///
+/// Only files named like the ones this cache writes are touched, so pointing the cache at
+/// a directory holding other files never removes them.
+fn sweep(dir: &Path, max_bytes: Option<u64>, now: SystemTime) -> std::io::Result<u64> {
+    /// Age after which a temporary file belongs to a write that never finished
+    const ABANDONED: Duration = Duration::from_secs(60 * 60);
+
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let Some(kind) = entry.file_name().to_str().and_then(CacheFile::from_name) else {
+            continue;
+        };
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        let modified = metadata.modified().unwrap_or(now);
+        if kind == CacheFile::Temporary {
+            if now.duration_since(modified).is_ok_and(|age| age > ABANDONED) {
+                let _ = std::fs::remove_file(&path);
+            }
+            continue;
+        }
+
+        let entry = std::fs::File::open(&path)
+            .map_err(serde_json::Error::io)
+            .and_then(|file| serde_json::from_reader::<_, DiskExpiry>(std::io::BufReader::new(file)));
+        let expired = match entry {
+            Ok(entry) => entry.expires_at.is_some_and(|secs| secs <= unix_secs(now)),
+            Err(_) => true,
+        };
+        if expired {
+            let _ = std::fs::remove_file(&path);
+            continue;
+        }
+        files.push((modified, metadata.len(), path));
+    }
+
+    let mut used: u64 = files.iter().map(|(_, len, _)| len).sum();
+    if let Some(max) = max_bytes.filter(|max| used > *max) {
+        let target = max / 10 * 9;
+        files.sort_by_key(|(modified, _, _)| *modified);
+        for (_, len, path) in files {
+            if used <= target {
+                break;
+            }
+            if std::fs::remove_file(&path).is_ok() {
+                used -= len;
+            }
+        }
+    }
+    Ok(used)
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Stable 64-bit FNV-1a hash used to derive cache file names
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x100000001b3)
+    })
+}
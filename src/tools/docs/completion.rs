@@ -0,0 +1,97 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::project::Ecosystem;
+use super::resources::SCHEME;
+
+/// Most values a completion response may carry
+pub const MAX_VALUES: usize = 100;
+
+/// What a `completion/complete` request completes an argument of
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompletionRef {
+    /// `ref/prompt`: a built-in prompt, by name
+    Prompt(String),
+    /// `ref/resource`: a resource URI or URI template
+    Resource(String),
+}
+
+impl CompletionRef {
+    pub fn parse(reference: &Value) -> Option<Self> {
+        match reference.get("type")?.as_str()? {
+            "ref/prompt" => Some(Self::Prompt(reference.get("name")?.as_str()?.to_string())),
+            "ref/resource" => Some(Self::Resource(reference.get("uri")?.as_str()?.to_string())),
+            _ => None,
+        }
+    }
+
+    /// Ecosystem a resource template belongs to, e.g. npm for `docs://npm/{package}@{version}`
+    pub fn ecosystem(&self) -> Option<Ecosystem> {
+        let Self::Resource(uri) = self else {
+            return None;
+        };
+        let (scheme, _) = uri.strip_prefix(SCHEME)?.split_once('/')?;
+        Ecosystem::from_scheme(scheme)
+    }
+}
+
+/// Result of a `completion/complete` request
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Completion {
+    pub values: Vec<String>,
+    pub total: usize,
+    pub has_more: bool,
+}
+
+impl Completion {
+    /// Candidates matching `value`, those starting with it first, without duplicates
+    pub fn matching(candidates: impl IntoIterator<Item = String>, value: &str) -> Self {
+        let needle = value.to_lowercase();
+        let mut prefixed = Vec::new();
+        let mut containing = Vec::new();
+        let mut seen = HashSet::new();
+        for candidate in candidates {
+            let lower = candidate.to_lowercase();
+            if !lower.contains(&needle) || !seen.insert(candidate.clone()) {
+                continue;
+            }
+            if lower.starts_with(&needle) {
+                prefixed.push(candidate);
+            } else {
+                containing.push(candidate);
+            }
+        }
+        prefixed.extend(containing);
+
+        let total = prefixed.len();
+        prefixed.truncate(MAX_VALUES);
+        Self {
+            has_more: total > prefixed.len(),
+            values: prefixed,
+            total,
+        }
+    }
+
+    /// Prefix every value with `head`, for completing one entry of a list
+    pub fn prefixed(mut self, head: &str) -> Self {
+        for value in &mut self.values {
+            value.insert_str(0, head);
+        }
+        self
+    }
+}
+
+/// Split a comma-separated list into the finished entries (with their separator) and the one being typed
+pub fn last_entry(value: &str) -> (&str, &str) {
+    match value.rfind(',') {
+        Some(comma) => {
+            let rest = &value[comma + 1..];
+            let typed = rest.trim_start();
+            (&value[..value.len() - typed.len()], typed)
+        }
+        None => ("", value),
+    }
+}
@@ -0,0 +1,321 @@
+use serde::{Deserialize, Serialize};
+
+/// Score of a candidate whose name matches the query exactly
+const EXACT_NAME: u32 = 80;
+
+/// One public item of a crate, as listed on its docs.rs `all.html` page
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CrateItem {
+    /// Path relative to the crate root, e.g. `sync::mpsc::Sender`
+    pub path: String,
+    /// rustdoc item kind as used in page names: `struct`, `enum`, `trait`, `fn`, `macro`, ...
+    pub kind: String,
+}
+
+/// Outcome of matching a path that could not be found against a crate's items
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Suggestion {
+    /// A single item is clearly meant; holds its path
+    Unique(String),
+    /// Several plausible items, best first
+    Candidates(Vec<CrateItem>),
+    /// Nothing resembles the query
+    None,
+}
+
+/// Extract the item list from a rustdoc `all.html` page
+pub fn parse_all_html(html: &str) -> Vec<CrateItem> {
+    let mut items = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find("<a href=\"") {
+        rest = &rest[start + 9..];
+        let Some(href_end) = rest.find('"') else {
+            break;
+        };
+        let href = &rest[..href_end];
+        let Some(text) = rest[href_end..]
+            .find('>')
+            .map(|i| &rest[href_end + i + 1..])
+            .and_then(|text| text.find("</a>").map(|end| &text[..end]))
+        else {
+            break;
+        };
+
+        let file = href.rsplit('/').next().unwrap_or_default();
+        let mut parts = file.split('.');
+        if let (Some(kind), Some(_), Some("html"), None) = (parts.next(), parts.next(), parts.next(), parts.next()) {
+            let path = strip_tags(text);
+            if !path.is_empty() && !path.contains(' ') {
+                items.push(CrateItem {
+                    path,
+                    kind: kind.to_string(),
+                });
+            }
+        }
+    }
+    items.sort();
+    items.dedup();
+    items
+}
+
+fn strip_tags(text: &str) -> String {
+    let mut out = String::new();
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out.trim().to_string()
+}
+
+/// Items resembling `query`, best match first
+pub fn rank<'a>(items: &'a [CrateItem], query: &str) -> Vec<(u32, &'a CrateItem)> {
+    let query: Vec<&str> = query.split("::").filter(|s| !s.is_empty()).collect();
+    let mut ranked: Vec<(u32, &CrateItem)> = items
+        .iter()
+        .map(|item| (score(item, &query), item))
+        .filter(|(score, _)| *score > 0)
+        .collect();
+    ranked.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .cmp(a_score)
+            .then(a.path.len().cmp(&b.path.len()))
+            .then(a.path.cmp(&b.path))
+    });
+    ranked
+}
+
+/// Pick the item meant by `query`, or up to `limit` candidates if it is ambiguous
+///
+/// An exact name match wins outright unless another path ties with it; the same path in
+/// several namespaces, like a trait and its derive macro, counts as one match.
+pub fn suggest(items: &[CrateItem], query: &str, limit: usize) -> Suggestion {
+    let ranked = rank(items, query);
+    let Some(&(best, best_item)) = ranked.first() else {
+        return Suggestion::None;
+    };
+
+    let unique = best >= EXACT_NAME
+        && ranked
+            .iter()
+            .take_while(|(score, _)| *score == best)
+            .all(|(_, item)| item.path == best_item.path);
+    if unique {
+        return Suggestion::Unique(best_item.path.clone());
+    }
+
+    let mut candidates: Vec<CrateItem> = Vec::new();
+    for (_, item) in ranked {
+        if candidates.len() >= limit {
+            break;
+        }
+        if !candidates.contains(item) {
+            candidates.push(item.clone());
+        }
+    }
+    Suggestion::Candidates(candidates)
+}
+
+fn score(item: &CrateItem, query: &[&str]) -> u32 {
+    let Some((wanted, modules)) = query.split_last() else {
+        return 0;
+    };
+    let segments: Vec<&str> = item.path.split("::").collect();
+    let Some((name, item_modules)) = segments.split_last() else {
+        return 0;
+    };
+
+    let name_score = if name == wanted {
+        EXACT_NAME
+    } else if name.eq_ignore_ascii_case(wanted) {
+        70
+    } else {
+        let (name, wanted) = (name.to_lowercase(), wanted.to_lowercase());
+        let distance = edit_distance(&name, &wanted);
+        if distance <= (wanted.len() / 4).max(1) {
+            60 - 10 * distance as u32
+        } else if wanted.len() >= 3 && (name.contains(&wanted) || wanted.contains(&name)) {
+            30
+        } else {
+            return 0;
+        }
+    };
+
+    let shared = modules
+        .iter()
+        .filter(|m| item_modules.iter().any(|im| im.eq_ignore_ascii_case(m)))
+        .count() as u32;
+    name_score + 5 * shared
+}
+
+/// Edit distance counting insertions, deletions, substitutions and adjacent transpositions
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1).min(rows[i][j - 1] + 1).min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// Section titles of item kinds, in the order rustdoc lists them
+const KIND_TITLES: &[(&str, &str)] = &[
+    ("macro", "Macros"),
+    ("struct", "Structs"),
+    ("enum", "Enums"),
+    ("union", "Unions"),
+    ("primitive", "Primitive Types"),
+    ("trait", "Traits"),
+    ("traitalias", "Trait Aliases"),
+    ("fn", "Functions"),
+    ("type", "Type Aliases"),
+    ("constant", "Constants"),
+    ("static", "Statics"),
+    ("attr", "Attribute Macros"),
+    ("derive", "Derive Macros"),
+];
+
+/// Map a user-supplied kind (`function`, `structs`, `type_alias`, ...) to a rustdoc page kind
+pub fn normalize_kind(kind: &str) -> String {
+    let kind = kind.trim().to_lowercase();
+    known_kind(&kind)
+        .or_else(|| kind.strip_suffix('s').and_then(known_kind))
+        .map(String::from)
+        .unwrap_or(kind)
+}
+
+fn known_kind(kind: &str) -> Option<&'static str> {
+    Some(match kind {
+        "fn" | "function" | "method" => "fn",
+        "mod" | "module" => "mod",
+        "type" | "type_alias" | "typealias" | "typedef" => "type",
+        "const" | "constant" => "constant",
+        "derive" | "derive_macro" => "derive",
+        "attr" | "attribute" | "attribute_macro" => "attr",
+        "traitalias" | "trait_alias" => "traitalias",
+        "struct" => "struct",
+        "enum" => "enum",
+        "union" => "union",
+        "trait" => "trait",
+        "macro" => "macro",
+        "static" => "static",
+        "primitive" => "primitive",
+        _ => return None,
+    })
+}
+
+/// Render the module tree and items of a crate, optionally restricted to one module and kind
+///
+/// Modules only implied by item paths are listed too, since `all.html` does not list
+/// modules.
+pub fn render_inventory(
+    crate_name: &str,
+    items: &[CrateItem],
+    module: Option<&str>,
+    kind: Option<&str>,
+    limit: usize,
+) -> String {
+    let crate_prefix = format!("{}::", crate_name.replace('-', "_"));
+    let module = module
+        .map(|m| m.trim().trim_end_matches("::"))
+        .map(|m| m.strip_prefix(&crate_prefix).unwrap_or(m))
+        .filter(|m| !m.is_empty() && *m != crate_name.replace('-', "_"));
+    let kind = kind.map(normalize_kind);
+    let in_scope = |path: &str| match module {
+        Some(module) => path.strip_prefix(module).is_some_and(|rest| rest.starts_with("::")),
+        None => true,
+    };
+
+    let mut out = format!("# Items in `{}`", crate_name);
+    if let Some(module) = module {
+        out.push_str(&format!(" under `{}`", module));
+    }
+    out.push_str("\n\n");
+
+    let mut modules: Vec<&str> = items
+        .iter()
+        .flat_map(|item| {
+            let ancestors = item.path.match_indices("::").map(|(i, _)| &item.path[..i]);
+            let own = (item.kind == "mod").then_some(item.path.as_str());
+            ancestors.chain(own)
+        })
+        .filter(|path| in_scope(path))
+        .collect();
+    modules.sort_unstable();
+    modules.dedup();
+
+    let mut shown = 0;
+    let mut hidden = 0;
+    if kind.as_deref().map_or(true, |k| k == "mod") && !modules.is_empty() {
+        out.push_str("## Modules\n\n");
+        let base_depth = module.map_or(0, |m| m.matches("::").count() + 1);
+        for path in &modules {
+            if shown >= limit {
+                hidden += 1;
+                continue;
+            }
+            let depth = path.matches("::").count() - base_depth;
+            let name = path.rsplit("::").next().unwrap_or(path);
+            out.push_str(&format!("{}- `{}`\n", "  ".repeat(depth), name));
+            shown += 1;
+        }
+        out.push('\n');
+    }
+
+    let mut sections: Vec<(String, Vec<&CrateItem>)> = Vec::new();
+    for item in items.iter().filter(|item| item.kind != "mod" && in_scope(&item.path)) {
+        if kind.as_deref().is_some_and(|k| k != item.kind) {
+            continue;
+        }
+        match sections.iter_mut().find(|(k, _)| *k == item.kind) {
+            Some((_, list)) => list.push(item),
+            None => sections.push((item.kind.clone(), vec![item])),
+        }
+    }
+    let order = |k: &str| KIND_TITLES.iter().position(|(known, _)| *known == k).unwrap_or(KIND_TITLES.len());
+    sections.sort_by(|(a, _), (b, _)| order(a).cmp(&order(b)).then(a.cmp(b)));
+
+    for (section_kind, list) in &sections {
+        let title = KIND_TITLES
+            .iter()
+            .find(|(k, _)| k == section_kind)
+            .map_or(section_kind.as_str(), |(_, title)| title);
+        out.push_str(&format!("## {} ({})\n\n", title, list.len()));
+        for item in list {
+            if shown >= limit {
+                hidden += 1;
+                continue;
+            }
+            out.push_str(&format!("- `{}`\n", item.path));
+            shown += 1;
+        }
+        out.push('\n');
+    }
+
+    if shown == 0 && hidden == 0 {
+        out.push_str("No matching items found.\n");
+    } else if hidden > 0 {
+        out.push_str(&format!(
+            "_{} more entries not shown; narrow the listing with `module` or `kind`._\n",
+            hidden
+        ));
+    }
+    out.trim_end().to_string() + "\n"
+}
@@ -1,15 +1,18 @@
 use reqwest::Client;
 use html2md::parse_html;
 use anyhow::Result;
-use std::sync::Arc;
-use tokio::sync::Mutex;
 use mcp_core::ToolError;
 
+use super::cache::DocCache;
+use super::metrics::TimedSend;
+use super::registry::{base_url, RegistryUrls};
+
 /// DevDocs.io Client for fetching documentation from various languages and frameworks
 #[derive(Clone)]
 pub struct DevDocsClient {
     client: Client,
-    cache: Arc<Mutex<std::collections::HashMap<String, String>>>,
+    cache: DocCache,
+    base_url: String,
 }
 
 impl Default for DevDocsClient {
@@ -20,22 +23,33 @@ impl Default for DevDocsClient {
 
 impl DevDocsClient {
     pub fn new() -> Self {
+        Self::with_cache(DocCache::new())
+    }
+
+    /// Create a client that stores its lookups in `cache`
+    pub fn with_cache(cache: DocCache) -> Self {
         Self {
             client: Client::new(),
-            cache: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            cache,
+            base_url: RegistryUrls::default().devdocs,
         }
     }
 
+    /// Use `url` as the DevDocs base URL instead of the public one
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        self.base_url = base_url(url);
+        self.cache = self.cache.for_registry(url, &RegistryUrls::default().devdocs);
+        self
+    }
+
     /// Get cache entry
     pub async fn get_cache(&self, key: &str) -> Option<String> {
-        let cache = self.cache.lock().await;
-        cache.get(key).cloned()
+        self.cache.get(key).await
     }
 
     /// Set cache entry
     pub async fn set_cache(&self, key: String, value: String) {
-        let mut cache = self.cache.lock().await;
-        cache.insert(key, value);
+        self.cache.set(key, value).await;
     }
 
     /// List available documentations on DevDocs.io
@@ -47,11 +61,11 @@ impl DevDocsClient {
         }
 
         // Fetch the list from DevDocs.io
-        let url = "https://devdocs.io/docs.json";
+        let url = format!("{}/docs.json", self.base_url);
         let response = self.client
-            .get(url)
+            .get(&url)
             .header("User-Agent", "CodeNav-MCP/0.1.0")
-            .send()
+            .send_timed()
             .await
             .map_err(|e| ToolError::ExecutionError(format!("Failed to fetch DevDocs list: {}", e)))?;
 
@@ -71,6 +85,18 @@ impl DevDocsClient {
         Ok(json_response)
     }
 
+    /// Slugs of every documentation set, e.g. `rust` or `javascript`
+    pub async fn slugs(&self) -> Result<Vec<String>, ToolError> {
+        let list = self.list_documentations().await?;
+        let docs: Vec<serde_json::Value> = serde_json::from_str(&list)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to parse DevDocs list: {}", e)))?;
+        Ok(docs
+            .iter()
+            .filter_map(|doc| doc.get("slug").and_then(|v| v.as_str()))
+            .map(String::from)
+            .collect())
+    }
+
     /// Get documentation for a specific documentation slug and entry
     pub async fn get_documentation(&self, slug: String, entry: Option<String>) -> Result<String, ToolError> {
         // Construct cache key
@@ -87,16 +113,16 @@ impl DevDocsClient {
 
         // Determine URL based on whether an entry was specified
         let url = if let Some(entry_path) = entry {
-            format!("https://devdocs.io/{}/{}", slug, entry_path)
+            format!("{}/{}/{}", self.base_url, slug, entry_path)
         } else {
-            format!("https://devdocs.io/{}", slug)
+            format!("{}/{}", self.base_url, slug)
         };
 
         // Fetch the documentation
         let response = self.client
             .get(&url)
             .header("User-Agent", "CodeNav-MCP/0.1.0")
-            .send()
+            .send_timed()
             .await
             .map_err(|e| ToolError::ExecutionError(format!("Failed to fetch DevDocs documentation: {}", e)))?;
 
@@ -131,13 +157,13 @@ impl DevDocsClient {
 
         // DevDocs doesn't have a public API for search, so we'll use their website search
         // This is not ideal, but it works for now
-        let url = format!("https://devdocs.io/{}/?q={}", slug, query);
+        let url = format!("{}/{}/?q={}", self.base_url, slug, query);
 
         // Fetch the search results page
         let response = self.client
             .get(&url)
             .header("User-Agent", "CodeNav-MCP/0.1.0")
-            .send()
+            .send_timed()
             .await
             .map_err(|e| ToolError::ExecutionError(format!("Failed to search DevDocs: {}", e)))?;
 
@@ -1,4 +1,4 @@
-use std::{future::Future, pin::Pin, sync::Arc};
+use std::{future::Future, path::PathBuf, pin::Pin};
 
 use mcp_core::{
     handler::{PromptError, ResourceError},
@@ -8,53 +8,71 @@ use mcp_core::{
 };
 use mcp_server::router::CapabilitiesBuilder;
 use reqwest::Client;
-use serde_json::{json, Value};
-use tokio::sync::Mutex;
+use serde_json::{json, Map, Value};
 use html2md::parse_html;
 
+use super::auth::{Credentials, RegistryAuth, RequestAuthExt};
+pub use super::cache::DocCache;
+use super::cache::Freshness;
+use super::completion::{last_entry, Completion, CompletionRef};
+use super::crate_items::{parse_all_html, render_inventory, suggest, CrateItem, Suggestion};
+use super::features::{index_path, parse_index, select_version, validate_crate_name, IndexEntry};
+use super::metrics::{metrics, TimedSend};
+use super::registry::{base_url, RegistryUrls};
+use super::resources::{templates, DocUri, ResourceTemplate};
 use super::devdocs::DevDocsClient;
 use super::npm::NpmClient;
+use super::project::{Ecosystem, Project};
+use super::progress::report;
+use super::prompts::{self, argument, parse_ecosystem, truncate, PromptSpec, MAX_COMPARED, MAX_DOC_CHARS, PROMPTS};
 use super::pypi::PyPIClient;
 use super::golang::GoClient;
+use super::rustdoc::RustdocClient;
 
-// Cache for documentation lookups to avoid repeated requests
-#[derive(Clone)]
-pub struct DocCache {
-    cache: Arc<Mutex<std::collections::HashMap<String, String>>>,
-}
-
-impl Default for DocCache {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl DocCache {
-    pub fn new() -> Self {
-        Self {
-            cache: Arc::new(Mutex::new(std::collections::HashMap::new())),
-        }
-    }
-
-    pub async fn get(&self, key: &str) -> Option<String> {
-        let cache = self.cache.lock().await;
-        cache.get(key).cloned()
-    }
-
-    pub async fn set(&self, key: String, value: String) {
-        let mut cache = self.cache.lock().await;
-        cache.insert(key, value);
-    }
-}
+/// Names of the tools in `list_tools`, the only tool names recorded as metrics labels
+///
+/// Client-supplied names of tools that don't exist would make the label set unbounded.
+pub const TOOL_NAMES: [&str; 16] = [
+    "lookup_crate",
+    "search_crates",
+    "lookup_item",
+    "list_crate_items",
+    "lookup_crate_features",
+    "list_devdocs_documentations",
+    "get_devdocs_documentation",
+    "search_devdocs_documentation",
+    "lookup_npm_package",
+    "search_npm_packages",
+    "lookup_pypi_package",
+    "search_pypi_packages",
+    "lookup_go_package",
+    "search_go_packages",
+    "lookup_go_symbol",
+    "lookup_go_item",
+];
 
 #[derive(Clone)]
 pub struct DocRouter {
     pub client: Client,
     pub cache: DocCache,
+    /// `cache` as seen by lookups against docs.rs
+    docs_rs_cache: DocCache,
+    /// `cache` as seen by lookups against the crate index
+    index_cache: DocCache,
     pub devdocs_client: DevDocsClient,
     pub npm_client: NpmClient,
     pub pypi_client: PyPIClient,
     pub go_client: GoClient,
+    pub rustdoc_client: RustdocClient,
+    pub registries: RegistryUrls,
+    /// Credentials for docs.rs requests
+    pub docs_rs_auth: Option<RegistryAuth>,
+    /// Credentials for crates.io API requests
+    pub crates_io_auth: Option<RegistryAuth>,
+    /// Credentials for crate index requests
+    pub crates_index_auth: Option<RegistryAuth>,
+    /// Local project whose locked dependency versions are used when a lookup omits the version
+    pub project: Option<Project>,
 }
 
 impl Default for DocRouter {
@@ -65,40 +83,328 @@ impl Default for DocRouter {
 
 impl DocRouter {
     pub fn new() -> Self {
+        Self::with_cache(DocCache::new())
+    }
+
+    /// Create a router whose documentation sources all share `cache`
+    pub fn with_cache(cache: DocCache) -> Self {
         Self {
             client: Client::new(),
-            cache: DocCache::new(),
-            devdocs_client: DevDocsClient::new(),
-            npm_client: NpmClient::new(),
-            pypi_client: PyPIClient::new(),
-            go_client: GoClient::new(),
+            devdocs_client: DevDocsClient::with_cache(cache.clone()),
+            npm_client: NpmClient::with_cache(cache.clone()),
+            pypi_client: PyPIClient::with_cache(cache.clone()),
+            go_client: GoClient::with_cache(cache.clone()),
+            rustdoc_client: RustdocClient::new().with_latest_ttl(cache.policy().latest_ttl),
+            docs_rs_cache: cache.clone(),
+            index_cache: cache.clone(),
+            cache,
+            registries: RegistryUrls::default(),
+            docs_rs_auth: None,
+            crates_io_auth: None,
+            crates_index_auth: None,
+            project: None,
+        }
+    }
+
+    /// Point every documentation source at the given base URLs
+    pub fn with_registries(mut self, registries: RegistryUrls) -> Self {
+        self.devdocs_client = self.devdocs_client.with_base_url(&registries.devdocs);
+        self.npm_client = self.npm_client.with_base_url(&registries.npm);
+        self.pypi_client = self.pypi_client.with_base_url(&registries.pypi);
+        self.go_client = self.go_client.with_base_url(&registries.go);
+        self.rustdoc_client = self.rustdoc_client.with_base_url(&registries.docs_rs);
+        let defaults = RegistryUrls::default();
+        self.docs_rs_cache = self.cache.for_registry(&registries.docs_rs, &defaults.docs_rs);
+        self.index_cache = self.cache.for_registry(&registries.crates_index, &defaults.crates_index);
+        self.registries = RegistryUrls {
+            docs_rs: base_url(&registries.docs_rs),
+            crates_io: base_url(&registries.crates_io),
+            crates_index: base_url(&registries.crates_index),
+            ..registries
+        };
+        self
+    }
+
+    /// Authenticate against private registries with the given credentials
+    pub fn with_credentials(mut self, credentials: Credentials) -> Self {
+        self.npm_client = self.npm_client.with_auth(credentials.npm);
+        self.pypi_client = self.pypi_client.with_auth(credentials.pypi);
+        self.go_client = self.go_client.with_auth(credentials.go, credentials.go_private);
+        self.rustdoc_client = self.rustdoc_client.with_auth(credentials.docs_rs.clone());
+        self.docs_rs_auth = credentials.docs_rs;
+        self.crates_io_auth = credentials.crates_io;
+        self.crates_index_auth = credentials.crates_index;
+        self
+    }
+
+    /// Read rustdoc JSON from `local_dirs` and, if `remote` is set, download it from docs.rs
+    pub fn with_rustdoc_sources(mut self, local_dirs: Vec<PathBuf>, remote: bool) -> Self {
+        self.rustdoc_client = self.rustdoc_client.with_local_dirs(local_dirs).with_remote(remote);
+        self
+    }
+
+    /// Default unversioned lookups to the dependency versions locked by the project at `root`
+    pub fn with_project_root(mut self, root: PathBuf) -> Self {
+        self.project = Some(Project::new(root));
+        self
+    }
+
+    /// URI templates of every documentation page readable with `read_resource`
+    pub fn list_resource_templates(&self) -> Vec<ResourceTemplate> {
+        templates()
+    }
+
+    /// Default an omitted version to the one locked by the project, with a note saying so
+    async fn pin_version(&self, ecosystem: Ecosystem, package: &str, version: Option<String>) -> (Option<String>, Option<String>) {
+        if version.as_deref().is_some_and(|v| !v.is_empty()) {
+            return (version, None);
+        }
+        let Some(project) = &self.project else {
+            return (version, None);
+        };
+        match project.current_versions().await.get(ecosystem, package).cloned() {
+            Some(locked) => {
+                let note = locked.note(package);
+                (Some(locked.version), Some(note))
+            }
+            None => (version, None),
+        }
+    }
+
+    /// Fetch the documentation page a resource URI points at, defaulting to the locked version
+    async fn read_doc(&self, uri: DocUri) -> Result<String, ToolError> {
+        let (version, pinned) = self.pin_version(uri.ecosystem, &uri.package, uri.version).await;
+        let package = uri.package;
+
+        let doc = match (uri.ecosystem, uri.item) {
+            (Ecosystem::Rust, Some(item_path)) => self.lookup_item(package, item_path, version).await,
+            (Ecosystem::Rust, None) => self.lookup_crate(package, version).await,
+            (Ecosystem::Npm, _) => self.lookup_npm_package(package, version).await,
+            (Ecosystem::PyPI, _) => self.lookup_pypi_package(package, version).await,
+            (Ecosystem::Go, Some(symbol)) => self.lookup_go_symbol(package, symbol, version).await,
+            (Ecosystem::Go, None) => self.lookup_go_package(package, version).await,
+        }?;
+        Ok(with_note(pinned, doc))
+    }
+
+    /// Messages of a built-in prompt: its instructions followed by the documentation they refer to
+    ///
+    /// Returns the prompt's description and the text of its single user message.
+    ///
+    /// Rust crates also get their item list, so the answer can name exact paths.
+    pub async fn render_prompt(&self, name: &str, arguments: &Map<String, Value>) -> Result<(String, String), PromptError> {
+        let spec = prompts::find(name).ok_or_else(|| PromptError::NotFound(format!("Prompt {} not found", name)))?;
+        let mut text = spec.instructions(arguments).map_err(PromptError::InvalidParameters)?;
+        let ecosystem = parse_ecosystem(argument(arguments, "ecosystem")).map_err(PromptError::InvalidParameters)?;
+        let version = argument(arguments, "version").map(String::from);
+        let doc_uri = |package: &str, version: Option<String>| DocUri {
+            ecosystem,
+            package: package.to_string(),
+            version,
+            item: None,
+        };
+
+        let mut sections: Vec<(String, Result<String, ToolError>)> = Vec::new();
+        match spec.name {
+            "explain_crate" => {
+                let crate_name = argument(arguments, "crate_name").unwrap_or_default();
+                let uri = DocUri { ecosystem: Ecosystem::Rust, ..doc_uri(crate_name, version.clone()) };
+                let (locked, _) = self.pin_version(Ecosystem::Rust, crate_name, version).await;
+                sections.push((format!("Documentation of `{}`", crate_name), self.read_doc(uri).await));
+                sections.push((
+                    "Cargo features".to_string(),
+                    self.lookup_crate_features(crate_name.to_string(), locked).await,
+                ));
+            }
+            "migrate_versions" => {
+                let package = argument(arguments, "package").unwrap_or_default();
+                for key in ["from_version", "to_version"] {
+                    let version = argument(arguments, key).map(String::from);
+                    let title = format!("`{}` {}", package, version.as_deref().unwrap_or_default());
+                    sections.push((title, self.read_doc(doc_uri(package, version)).await));
+                }
+            }
+            "compare_packages" => {
+                let packages = argument(arguments, "packages").unwrap_or_default();
+                for package in packages.split(',').map(str::trim).filter(|p| !p.is_empty()).take(MAX_COMPARED) {
+                    sections.push((format!("`{}`", package), self.read_doc(doc_uri(package, None)).await));
+                }
+            }
+            "find_api" => {
+                let package = argument(arguments, "package").unwrap_or_default();
+                let (locked, _) = self.pin_version(ecosystem, package, version.clone()).await;
+                sections.push((format!("Documentation of `{}`", package), self.read_doc(doc_uri(package, version)).await));
+                if ecosystem == Ecosystem::Rust {
+                    let items = self
+                        .crate_items(package, locked.as_deref())
+                        .await
+                        .map(|items| render_inventory(package, &items, None, None, 300));
+                    sections.push(("Public items".to_string(), items));
+                }
+            }
+            _ => {}
+        }
+
+        for (title, doc) in sections {
+            let doc = match doc {
+                Ok(doc) => truncate(&doc, MAX_DOC_CHARS),
+                Err(e) => format!("_Documentation could not be fetched: {}_", e),
+            };
+            text.push_str(&format!("\n\n## {}\n\n{}", title, doc.trim_end()));
+        }
+        Ok((spec.description.to_string(), text))
+    }
+
+    /// Values for an argument of a prompt or resource template, best first
+    ///
+    /// Package names come from the project's dependencies and then a registry search,
+    /// item paths from the crate named by another argument in `context`.
+    pub async fn complete(&self, reference: &CompletionRef, name: &str, value: &str, context: &Map<String, Value>) -> Completion {
+        let ecosystem = reference
+            .ecosystem()
+            .or_else(|| parse_ecosystem(argument(context, "ecosystem")).ok())
+            .unwrap_or(Ecosystem::Rust);
+
+        match name {
+            "ecosystem" => Completion::matching(["rust", "npm", "pypi", "go"].map(String::from), value),
+            "crate" | "crate_name" => self.complete_package(Ecosystem::Rust, value).await,
+            "package" => self.complete_package(ecosystem, value).await,
+            "module" => self.complete_package(Ecosystem::Go, value).await,
+            "packages" => {
+                let (head, typed) = last_entry(value);
+                self.complete_package(ecosystem, typed).await.prefixed(head)
+            }
+            "item_path" => {
+                let Some(crate_name) = ["crate", "crate_name", "package"].iter().find_map(|key| argument(context, key)) else {
+                    return Completion::default();
+                };
+                let version = argument(context, "version").map(String::from);
+                let (version, _) = self.pin_version(Ecosystem::Rust, crate_name, version).await;
+                match self.crate_items(crate_name, version.as_deref()).await {
+                    Ok(items) => Completion::matching(items.into_iter().map(|item| item.path), value),
+                    Err(_) => Completion::default(),
+                }
+            }
+            "slug" => match self.devdocs_client.slugs().await {
+                Ok(slugs) => Completion::matching(slugs, value),
+                Err(_) => Completion::default(),
+            },
+            _ => Completion::default(),
+        }
+    }
+
+    /// Project dependencies first, then registry search results
+    async fn complete_package(&self, ecosystem: Ecosystem, prefix: &str) -> Completion {
+        let dependencies = match &self.project {
+            Some(project) => project.current_versions().await.dependencies(),
+            None => Vec::new(),
+        };
+        let mut names: Vec<String> = dependencies
+            .into_iter()
+            .filter(|dependency| dependency.ecosystem == ecosystem)
+            .map(|dependency| dependency.name)
+            .collect();
+        if !prefix.is_empty() {
+            names.extend(self.package_names(ecosystem, prefix).await);
+        }
+        Completion::matching(names, prefix)
+    }
+
+    /// Registry names matching `prefix`, cached like a search; failures only leave the project's names
+    async fn package_names(&self, ecosystem: Ecosystem, prefix: &str) -> Vec<String> {
+        let cache_key = format!("complete:{}:{}", ecosystem.scheme(), prefix.to_lowercase());
+        let defaults = RegistryUrls::default();
+        let cache = match ecosystem {
+            Ecosystem::Rust => self.cache.for_registry(&self.registries.crates_io, &defaults.crates_io),
+            Ecosystem::Npm => self.cache.for_registry(&self.registries.npm, &defaults.npm),
+            Ecosystem::PyPI => self.cache.for_registry(&self.registries.pypi, &defaults.pypi),
+            Ecosystem::Go => self.cache.for_registry(&self.registries.go, &defaults.go),
+        };
+        if let Some(names) = cache.get(&cache_key).await.and_then(|v| serde_json::from_str(&v).ok()) {
+            return names;
+        }
+
+        let names = match ecosystem {
+            Ecosystem::Rust => self.crate_names(prefix).await,
+            Ecosystem::Npm => self.npm_client.package_names(prefix).await,
+            Ecosystem::PyPI => self.pypi_client.package_names(prefix).await,
+            Ecosystem::Go => self.go_client.package_names(prefix).await,
+        };
+        match names {
+            Ok(names) => {
+                if let Ok(serialized) = serde_json::to_string(&names) {
+                    cache.set_with(cache_key, serialized, Freshness::Latest).await;
+                }
+                names
+            }
+            Err(e) => {
+                tracing::debug!(prefix, "Completion lookup failed: {}", e);
+                Vec::new()
+            }
         }
     }
 
+    /// Names of crates matching `prefix` from the crates.io search API
+    async fn crate_names(&self, prefix: &str) -> Result<Vec<String>, ToolError> {
+        let url = format!("{}/api/v1/crates", self.registries.crates_io);
+        let response = self.client.get(&url)
+            .query(&[("q", prefix), ("per_page", "50")])
+            .header("User-Agent", "CodeNav/0.1.0 (https://github.com/HikaruEgashira/codenav-mcp)")
+            .registry_auth(self.crates_io_auth.as_ref())
+            .send_timed()
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to search crates.io: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(ToolError::ExecutionError(format!(
+                "Failed to search crates.io. Status: {}",
+                response.status()
+            )));
+        }
+
+        let body: Value = response.json().await
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to parse crates.io response: {}", e)))?;
+        Ok(body
+            .get("crates")
+            .and_then(|v| v.as_array())
+            .map(|crates| {
+                crates
+                    .iter()
+                    .filter_map(|krate| krate.get("name").and_then(|v| v.as_str()))
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
     // Fetch crate documentation from docs.rs
     async fn lookup_crate(&self, crate_name: String, version: Option<String>) -> Result<String, ToolError> {
         // Check cache first
         let cache_key = if let Some(ver) = &version {
-            format!("{}:{}", crate_name, ver)
+            format!("rust:crate:{}:{}", crate_name, ver)
         } else {
-            crate_name.clone()
+            format!("rust:crate:{}", crate_name)
         };
 
-        if let Some(doc) = self.cache.get(&cache_key).await {
+        if let Some(doc) = self.docs_rs_cache.get(&cache_key).await {
             return Ok(doc);
         }
 
+        let freshness = Freshness::for_version(&version);
+
         // Construct the docs.rs URL for the crate
         let url = if let Some(ver) = version {
-            format!("https://docs.rs/crate/{}/{}/", crate_name, ver)
+            format!("{}/crate/{}/{}/", self.registries.docs_rs, crate_name, ver)
         } else {
-            format!("https://docs.rs/crate/{}/", crate_name)
+            format!("{}/crate/{}/", self.registries.docs_rs, crate_name)
         };
 
         // Fetch the documentation page
+        report(format!("Fetching `{}` documentation from docs.rs", crate_name));
         let response = self.client.get(&url)
             .header("User-Agent", "CodeNav/0.1.0 (https://github.com/HikaruEgashira/codenav-mcp)")
-            .send()
+            .registry_auth(self.docs_rs_auth.as_ref())
+            .send_timed()
             .await
             .map_err(|e| {
                 ToolError::ExecutionError(format!("Failed to fetch documentation: {}", e))
@@ -116,9 +422,10 @@ impl DocRouter {
         })?;
         
         // Convert HTML to markdown
+        report("Converting HTML to Markdown");
         let markdown_body = parse_html(&html_body);
         // Cache the markdown result
-        self.cache.set(cache_key, markdown_body.clone()).await;
+        self.docs_rs_cache.set_with(cache_key, markdown_body.clone(), freshness).await;
         
         Ok(markdown_body)
     }
@@ -127,11 +434,12 @@ impl DocRouter {
     async fn search_crates(&self, query: String, limit: Option<u32>) -> Result<String, ToolError> {
         let limit = limit.unwrap_or(10).min(100); // Cap at 100 results
         
-        let url = format!("https://crates.io/api/v1/crates?q={}&per_page={}", query, limit);
+        let url = format!("{}/api/v1/crates?q={}&per_page={}", self.registries.crates_io, query, limit);
         
         let response = self.client.get(&url)
             .header("User-Agent", "CodeNav/0.1.0 (https://github.com/HikaruEgashira/codenav-mcp)")
-            .send()
+            .registry_auth(self.crates_io_auth.as_ref())
+            .send_timed()
             .await
             .map_err(|e| {
                 ToolError::ExecutionError(format!("Failed to search crates.io: {}", e))
@@ -158,7 +466,10 @@ impl DocRouter {
         }
     }
 
-    // Get documentation for a specific item in a crate
+    /// Get documentation for a specific item in a crate, suggesting similar items if it does not exist
+    ///
+    /// The whole path is tried first, then its last segment as a member of a misplaced
+    /// type.
     async fn lookup_item(&self, crate_name: String, mut item_path: String, version: Option<String>) -> Result<String, ToolError> {
         // Strip crate name prefix from the item path if it exists
         let crate_prefix = format!("{}::", crate_name);
@@ -166,14 +477,73 @@ impl DocRouter {
             item_path = item_path[crate_prefix.len()..].to_string();
         }
 
-        // Check cache first
-        let cache_key = if let Some(ver) = &version {
-            format!("{}:{}:{}", crate_name, ver, item_path)
-        } else {
-            format!("{}:{}", crate_name, item_path)
+        let not_found = match self.lookup_item_exact(&crate_name, &item_path, &version).await {
+            Err(ToolError::ExecutionError(message)) => message,
+            result => return result,
         };
 
-        if let Some(doc) = self.cache.get(&cache_key).await {
+        report(format!("`{}` not found, looking for similar items in `{}`", item_path, crate_name));
+        let items = match self.crate_items(&crate_name, version.as_deref()).await {
+            Ok(items) => items,
+            Err(_) => return Err(ToolError::ExecutionError(not_found)),
+        };
+
+        let mut attempts = vec![(item_path.as_str(), None)];
+        if let Some((owner, member)) = item_path.rsplit_once("::") {
+            attempts.push((owner, Some(member)));
+        }
+
+        for (query, member) in attempts {
+            let resolved = match suggest(&items, query, 10) {
+                Suggestion::Unique(path) => path,
+                Suggestion::Candidates(candidates) if member.is_none() => {
+                    let list: Vec<String> = candidates
+                        .iter()
+                        .map(|item| format!("- `{}` ({})", item.path, item.kind))
+                        .collect();
+                    return Err(ToolError::ExecutionError(format!(
+                        "{}\n\nDid you mean one of these items in `{}`?\n{}",
+                        not_found,
+                        crate_name,
+                        list.join("\n")
+                    )));
+                }
+                _ => continue,
+            };
+            let resolved = match member {
+                Some(member) => format!("{}::{}", resolved, member),
+                None => resolved,
+            };
+            if resolved == item_path {
+                continue;
+            }
+
+            if let Ok(doc) = self.lookup_item_exact(&crate_name, &resolved, &version).await {
+                let doc = format!("> Resolved `{}` to `{}`\n\n{}", item_path, resolved, doc);
+                let cache_key = item_cache_key(&crate_name, &item_path, &version);
+                self.docs_rs_cache.set_with(cache_key, doc.clone(), Freshness::for_version(&version)).await;
+                return Ok(doc);
+            }
+        }
+
+        Err(ToolError::ExecutionError(not_found))
+    }
+
+    /// Get documentation for an item at exactly the given path
+    ///
+    /// Rustdoc JSON is preferred, as it resolves every kind of item and re-export
+    /// exactly, with the rendered HTML pages as a fallback. The last segment may be a
+    /// method, associated item, variant or field of the item before it.
+    async fn lookup_item_exact(&self, crate_name: &str, item_path: &str, version: &Option<String>) -> Result<String, ToolError> {
+        // Check cache first
+        let cache_key = item_cache_key(crate_name, item_path, version);
+
+        if let Some(doc) = self.docs_rs_cache.get(&cache_key).await {
+            return Ok(doc);
+        }
+
+        if let Some(doc) = self.rustdoc_client.lookup_item(crate_name, item_path, version.as_deref()).await {
+            self.docs_rs_cache.set_with(cache_key, doc.clone(), Freshness::for_version(version)).await;
             return Ok(doc);
         }
 
@@ -188,67 +558,214 @@ impl DocRouter {
             ));
         }
         
-        let item_name = parts.last().unwrap().to_string();
+        // Try different item types (struct, enum, trait, fn)
+        let item_types = ["struct", "enum", "trait", "fn", "macro"];
+        let last_error = match self.fetch_item_page(crate_name, version, &parts, &item_types).await {
+            Ok(html_body) => {
+                // Convert HTML to markdown
+                report("Converting HTML to Markdown");
+                let markdown_body = parse_html(&html_body);
+
+                // Cache the markdown result
+                self.docs_rs_cache.set_with(cache_key, markdown_body.clone(), Freshness::for_version(version)).await;
+
+                return Ok(markdown_body);
+            }
+            Err(e) => e,
+        };
+
+        if let Some((member, owner)) = parts.split_last().filter(|(_, owner)| !owner.is_empty()) {
+            let owner_types = ["struct", "enum", "trait", "union", "type", "primitive"];
+            report(format!("Looking for `{}` on the page of its parent", member));
+            if let Ok(html_body) = self.fetch_item_page(crate_name, version, owner, &owner_types).await {
+                if let Some(section) = member_section(&html_body, member) {
+                    let markdown_body = format!("# {}\n\n{}", item_path, parse_html(&section));
+                    self.docs_rs_cache.set_with(cache_key, markdown_body.clone(), Freshness::for_version(version)).await;
+                    return Ok(markdown_body);
+                }
+            }
+        }
+
+        // If we got here, none of the item types worked
+        Err(ToolError::ExecutionError(format!(
+            "Failed to fetch item documentation. No matching item found. Last error: {}",
+            last_error
+        )))
+    }
+
+    /// List the module tree and items of a crate, optionally filtered by module and kind
+    async fn list_crate_items(
+        &self,
+        crate_name: String,
+        version: Option<String>,
+        module: Option<String>,
+        kind: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<String, ToolError> {
+        let items = self.crate_items(&crate_name, version.as_deref()).await?;
+        let limit = limit.unwrap_or(300) as usize;
+        Ok(render_inventory(&crate_name, &items, module.as_deref(), kind.as_deref(), limit))
+    }
+
+    /// List every public item of a crate, from rustdoc JSON or the docs.rs `all.html` page
+    async fn crate_items(&self, crate_name: &str, version: Option<&str>) -> Result<Vec<CrateItem>, ToolError> {
+        if let Some(krate) = self.rustdoc_client.load(crate_name, version).await {
+            return Ok(krate.inventory());
+        }
+
+        let version = version.filter(|v| !v.is_empty()).unwrap_or("latest");
+        let cache_key = format!("rust:items:{}:{}", crate_name, version);
+        if let Some(items) = self.docs_rs_cache.get(&cache_key).await.and_then(|v| serde_json::from_str(&v).ok()) {
+            return Ok(items);
+        }
+
+        let url = format!(
+            "{}/{}/{}/{}/all.html",
+            self.registries.docs_rs,
+            crate_name,
+            version,
+            crate_name.replace('-', "_")
+        );
+        let response = self.client.get(&url)
+            .header("User-Agent", "CodeNav/0.1.0 (https://github.com/HikaruEgashira/codenav-mcp)")
+            .registry_auth(self.docs_rs_auth.as_ref())
+            .send_timed()
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to fetch item list: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(ToolError::ExecutionError(format!(
+                "Failed to fetch item list. Status: {}",
+                response.status()
+            )));
+        }
+
+        let html_body = response.text().await.map_err(|e| {
+            ToolError::ExecutionError(format!("Failed to read response body: {}", e))
+        })?;
+        let items = parse_all_html(&html_body);
+
+        if let Ok(serialized) = serde_json::to_string(&items) {
+            let freshness = Freshness::for_version(&Some(version.to_string()));
+            self.docs_rs_cache.set_with(cache_key, serialized, freshness).await;
+        }
+        Ok(items)
+    }
+
+    /// Show the feature table of a crate version, with the optional dependencies each feature enables
+    /// and, when its rustdoc JSON is at hand without a download, the items it gates
+    async fn lookup_crate_features(&self, crate_name: String, version: Option<String>) -> Result<String, ToolError> {
+        let entries = self.crate_index(&crate_name).await?;
+        let entry = select_version(&entries, version.as_deref()).ok_or_else(|| {
+            ToolError::ExecutionError(format!(
+                "No version of `{}` matches `{}`",
+                crate_name,
+                version.as_deref().unwrap_or("latest")
+            ))
+        })?;
+
+        let cache_key = format!("rust:features:{}:{}", crate_name.to_lowercase(), entry.vers);
+        if let Some(doc) = self.index_cache.get(&cache_key).await {
+            return Ok(doc);
+        }
+
+        let gates = self
+            .rustdoc_client
+            .load_offline(&crate_name, Some(&entry.vers))
+            .await
+            .map(|krate| krate.feature_gates());
+        let doc = entry.render_features(gates.as_ref());
+        let freshness = if gates.is_some() { Freshness::Pinned } else { Freshness::Latest };
+        self.index_cache.set_with(cache_key, doc.clone(), freshness).await;
+        Ok(doc)
+    }
+
+    /// Fetch every published version of a crate from the sparse registry index
+    ///
+    /// New versions are published to the same file, so it expires like an unversioned
+    /// lookup.
+    async fn crate_index(&self, crate_name: &str) -> Result<Vec<IndexEntry>, ToolError> {
+        validate_crate_name(crate_name).map_err(ToolError::InvalidParameters)?;
+        let cache_key = format!("rust:index:{}", crate_name.to_lowercase());
+        if let Some(body) = self.index_cache.get(&cache_key).await {
+            return Ok(parse_index(&body));
+        }
+
+        let url = format!("{}/{}", self.registries.crates_index, index_path(crate_name));
+        let response = self.client.get(&url)
+            .header("User-Agent", "CodeNav/0.1.0 (https://github.com/HikaruEgashira/codenav-mcp)")
+            .registry_auth(self.crates_index_auth.as_ref())
+            .send_timed()
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to fetch crate index: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(ToolError::ExecutionError(format!(
+                "Failed to fetch crate index. Status: {}",
+                response.status()
+            )));
+        }
+
+        let body = response.text().await.map_err(|e| {
+            ToolError::ExecutionError(format!("Failed to read response body: {}", e))
+        })?;
+        let entries = parse_index(&body);
+        if entries.is_empty() {
+            return Err(ToolError::ExecutionError(format!("Crate `{}` has no published versions", crate_name)));
+        }
+
+        self.index_cache.set_with(cache_key, body, Freshness::Latest).await;
+        Ok(entries)
+    }
+
+    /// Fetch the rendered docs.rs page of an item, trying each possible item type
+    async fn fetch_item_page(&self, crate_name: &str, version: &Option<String>, parts: &[&str], item_types: &[&str]) -> Result<String, String> {
+        let item_name = parts.last().copied().unwrap_or_default();
         let module_path = if parts.len() > 1 {
             parts[..parts.len()-1].join("/")
         } else {
             String::new()
         };
-        
-        // Try different item types (struct, enum, trait, fn)
-        let item_types = ["struct", "enum", "trait", "fn", "macro"];
+
         let mut last_error = None;
-        
+
         for item_type in item_types.iter() {
             // Construct the docs.rs URL for the specific item
             let url = if let Some(ver) = version.clone() {
                 if module_path.is_empty() {
-                    format!("https://docs.rs/{}/{}/{}/{}.{}.html", crate_name, ver, crate_name, item_type, item_name)
+                    format!("{}/{}/{}/{}/{}.{}.html", self.registries.docs_rs, crate_name, ver, crate_name, item_type, item_name)
                 } else {
-                    format!("https://docs.rs/{}/{}/{}/{}/{}.{}.html", crate_name, ver, crate_name, module_path, item_type, item_name)
+                    format!("{}/{}/{}/{}/{}/{}.{}.html", self.registries.docs_rs, crate_name, ver, crate_name, module_path, item_type, item_name)
                 }
             } else {
                 if module_path.is_empty() {
-                    format!("https://docs.rs/{}/latest/{}/{}.{}.html", crate_name, crate_name, item_type, item_name)
+                    format!("{}/{}/latest/{}/{}.{}.html", self.registries.docs_rs, crate_name, crate_name, item_type, item_name)
                 } else {
-                    format!("https://docs.rs/{}/latest/{}/{}/{}.{}.html", crate_name, crate_name, module_path, item_type, item_name)
+                    format!("{}/{}/latest/{}/{}/{}.{}.html", self.registries.docs_rs, crate_name, crate_name, module_path, item_type, item_name)
                 }
             };
-            
+
             // Try to fetch the documentation page
+            report(format!("Trying {} page for `{}`", item_type, item_name));
             let response = match self.client.get(&url)
                 .header("User-Agent", "CodeNav/0.1.0 (https://github.com/HikaruEgashira/codenav-mcp)")
-                .send().await {
+                .registry_auth(self.docs_rs_auth.as_ref())
+                .send_timed().await {
                 Ok(resp) => resp,
                 Err(e) => {
                     last_error = Some(e.to_string());
                     continue;
                 }
             };
-            
-            // If found, process and return
+
             if response.status().is_success() {
-                let html_body = response.text().await.map_err(|e| {
-                    ToolError::ExecutionError(format!("Failed to read response body: {}", e))
-                })?;
-                
-                // Convert HTML to markdown
-                let markdown_body = parse_html(&html_body);
-                
-                // Cache the markdown result
-                self.cache.set(cache_key, markdown_body.clone()).await;
-                
-                return Ok(markdown_body);
+                return response.text().await.map_err(|e| format!("Failed to read response body: {}", e));
             }
-            
+
             last_error = Some(format!("Status code: {}", response.status()));
         }
-        
-        // If we got here, none of the item types worked
-        Err(ToolError::ExecutionError(format!(
-            "Failed to fetch item documentation. No matching item found. Last error: {}",
-            last_error.unwrap_or_else(|| "Unknown error".to_string())
-        )))
+
+        Err(last_error.unwrap_or_else(|| "Unknown error".to_string()))
     }
 
     // DevDocs.io support methods
@@ -314,6 +831,64 @@ impl DocRouter {
     }
 }
 
+/// Human-readable name of an ecosystem's documentation
+fn ecosystem_label(ecosystem: Ecosystem) -> &'static str {
+    match ecosystem {
+        Ecosystem::Rust => "Rust crate",
+        Ecosystem::Npm => "npm package",
+        Ecosystem::PyPI => "PyPI package",
+        Ecosystem::Go => "Go module",
+    }
+}
+
+/// Prefix `doc` with a note explaining how its version was chosen
+fn with_note(note: Option<String>, doc: String) -> String {
+    match note {
+        Some(note) => format!("{}\n\n{}", note, doc),
+        None => doc,
+    }
+}
+
+/// Cache key of an item lookup
+///
+/// Rust keys carry a `rust:<kind>:` prefix like the `npm:`, `pypi:` and `go:` ones, so a
+/// crate named like a key kind (`index`, `items`, ...) cannot collide with another lookup.
+fn item_cache_key(crate_name: &str, item_path: &str, version: &Option<String>) -> String {
+    if let Some(ver) = version {
+        format!("rust:item:{}:{}:{}", crate_name, ver, item_path)
+    } else {
+        format!("rust:item:{}:{}", crate_name, item_path)
+    }
+}
+
+/// Extract the documentation of one method, associated item, variant or field from a rustdoc page
+///
+/// The member ends where the next member, impl block or section begins.
+pub(crate) fn member_section(html: &str, name: &str) -> Option<String> {
+    const ANCHORS: [&str; 6] = ["method", "tymethod", "associatedtype", "associatedconstant", "variant", "structfield"];
+
+    let anchor = ANCHORS
+        .iter()
+        .find_map(|anchor| html.find(&format!("id=\"{}.{}\"", anchor, name)))?;
+    let rest = &html[html[..anchor].rfind('<')?..];
+    let body_start = rest.find('>')? + 1;
+
+    let tail = &rest[body_start..];
+    let next_member = ANCHORS
+        .iter()
+        .map(|anchor| format!("id=\"{}.", anchor))
+        .chain(std::iter::once("id=\"impl-".to_string()))
+        .filter_map(|marker| tail.find(&marker))
+        .min()
+        .and_then(|offset| tail[..offset].rfind("<details").or_else(|| tail[..offset].rfind('<')));
+    let end = next_member
+        .into_iter()
+        .chain(tail.find("<h2"))
+        .min()
+        .map_or(rest.len(), |offset| body_start + offset);
+    Some(rest[..end].to_string())
+}
+
 impl mcp_server::Router for DocRouter {
     fn name(&self) -> String {
         "codenav-docs".to_string()
@@ -377,7 +952,7 @@ impl mcp_server::Router for DocRouter {
             ),
             Tool::new(
                 "lookup_item".to_string(),
-                "Look up documentation for a specific item in a Rust crate (returns markdown)".to_string(),
+                "Look up documentation for a specific item in a Rust crate: signature, docs and implementations (returns markdown)".to_string(),
                 json!({
                     "type": "object",
                     "properties": {
@@ -387,7 +962,7 @@ impl mcp_server::Router for DocRouter {
                         },
                         "item_path": {
                             "type": "string",
-                            "description": "Path to the item (e.g., 'vec::Vec' or 'crate_name::vec::Vec' - crate prefix will be automatically stripped)"
+                            "description": "Path to the item (e.g., 'vec::Vec', 'vec::Vec::push' for a method, associated item, variant or field, or 'crate_name::vec::Vec' - crate prefix will be automatically stripped)"
                         },
                         "version": {
                             "type": "string",
@@ -397,6 +972,54 @@ impl mcp_server::Router for DocRouter {
                     "required": ["crate_name", "item_path"]
                 }),
             ),
+            Tool::new(
+                "list_crate_items".to_string(),
+                "List the module tree and public items of a Rust crate, optionally filtered by module and item kind".to_string(),
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "crate_name": {
+                            "type": "string",
+                            "description": "The name of the crate"
+                        },
+                        "version": {
+                            "type": "string",
+                            "description": "The version of the crate (optional, defaults to latest)"
+                        },
+                        "module": {
+                            "type": "string",
+                            "description": "Only list items inside this module (e.g., 'sync::mpsc')"
+                        },
+                        "kind": {
+                            "type": "string",
+                            "description": "Only list items of this kind: mod, struct, enum, trait, fn, macro, type, constant, static, union, derive, attr"
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of entries to return (default: 300)"
+                        }
+                    },
+                    "required": ["crate_name"]
+                }),
+            ),
+            Tool::new(
+                "lookup_crate_features".to_string(),
+                "Show the Cargo features of a Rust crate version: default features, what each feature enables, optional dependencies and the items gated behind each feature".to_string(),
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "crate_name": {
+                            "type": "string",
+                            "description": "The name of the crate"
+                        },
+                        "version": {
+                            "type": "string",
+                            "description": "The version or version requirement of the crate (optional, defaults to latest)"
+                        }
+                    },
+                    "required": ["crate_name"]
+                }),
+            ),
             
             // DevDocs.io tools
             Tool::new(
@@ -613,267 +1236,366 @@ impl mcp_server::Router for DocRouter {
         let tool_name = tool_name.to_string();
         let arguments = arguments.clone();
         Box::pin(async move {
-            match tool_name.as_str() {
-                // Rust documentation tools
-                "lookup_crate" => {
-                    let crate_name = arguments
-                        .get("crate_name")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| ToolError::InvalidParameters("crate_name is required".to_string()))?
-                        .to_string();
+            let result = async {
+                match tool_name.as_str() {
+                    // Rust documentation tools
+                    "lookup_crate" => {
+                        let crate_name = arguments
+                            .get("crate_name")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| ToolError::InvalidParameters("crate_name is required".to_string()))?
+                            .to_string();
                     
-                    let version = arguments
-                        .get("version")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string());
+                        let version = arguments
+                            .get("version")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string());
                     
-                    let doc = this.lookup_crate(crate_name, version).await?;
-                    Ok(vec![Content::text(doc)])
-                }
-                "search_crates" => {
-                    let query = arguments
-                        .get("query")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| ToolError::InvalidParameters("query is required".to_string()))?
-                        .to_string();
+                        let (version, pinned) = this.pin_version(Ecosystem::Rust, &crate_name, version).await;
+                        let doc = this.lookup_crate(crate_name, version).await?;
+                        Ok(vec![Content::text(with_note(pinned, doc))])
+                    }
+                    "search_crates" => {
+                        let query = arguments
+                            .get("query")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| ToolError::InvalidParameters("query is required".to_string()))?
+                            .to_string();
                     
-                    let limit = arguments
-                        .get("limit")
-                        .and_then(|v| v.as_u64())
-                        .map(|v| v as u32);
+                        let limit = arguments
+                            .get("limit")
+                            .and_then(|v| v.as_u64())
+                            .map(|v| v as u32);
                     
-                    let results = this.search_crates(query, limit).await?;
-                    Ok(vec![Content::text(results)])
-                }
-                "lookup_item" => {
-                    let crate_name = arguments
-                        .get("crate_name")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| ToolError::InvalidParameters("crate_name is required".to_string()))?
-                        .to_string();
+                        let results = this.search_crates(query, limit).await?;
+                        Ok(vec![Content::text(results)])
+                    }
+                    "lookup_item" => {
+                        let crate_name = arguments
+                            .get("crate_name")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| ToolError::InvalidParameters("crate_name is required".to_string()))?
+                            .to_string();
                     
-                    let item_path = arguments
-                        .get("item_path")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| ToolError::InvalidParameters("item_path is required".to_string()))?
-                        .to_string();
+                        let item_path = arguments
+                            .get("item_path")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| ToolError::InvalidParameters("item_path is required".to_string()))?
+                            .to_string();
                     
-                    let version = arguments
-                        .get("version")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string());
+                        let version = arguments
+                            .get("version")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string());
                     
-                    let doc = this.lookup_item(crate_name, item_path, version).await?;
-                    Ok(vec![Content::text(doc)])
-                },
+                        let (version, pinned) = this.pin_version(Ecosystem::Rust, &crate_name, version).await;
+                        let doc = this.lookup_item(crate_name, item_path, version).await?;
+                        Ok(vec![Content::text(with_note(pinned, doc))])
+                    },
+                    "list_crate_items" => {
+                        let crate_name = arguments
+                            .get("crate_name")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| ToolError::InvalidParameters("crate_name is required".to_string()))?
+                            .to_string();
+
+                        let version = arguments
+                            .get("version")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string());
+
+                        let module = arguments
+                            .get("module")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string());
+
+                        let kind = arguments
+                            .get("kind")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string());
+
+                        let limit = arguments
+                            .get("limit")
+                            .and_then(|v| v.as_u64())
+                            .map(|v| v as u32);
+
+                        let (version, pinned) = this.pin_version(Ecosystem::Rust, &crate_name, version).await;
+                        let items = this.list_crate_items(crate_name, version, module, kind, limit).await?;
+                        Ok(vec![Content::text(with_note(pinned, items))])
+                    },
+                    "lookup_crate_features" => {
+                        let crate_name = arguments
+                            .get("crate_name")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| ToolError::InvalidParameters("crate_name is required".to_string()))?
+                            .to_string();
+
+                        let version = arguments
+                            .get("version")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string());
+
+                        let (version, pinned) = this.pin_version(Ecosystem::Rust, &crate_name, version).await;
+                        let features = this.lookup_crate_features(crate_name, version).await?;
+                        Ok(vec![Content::text(with_note(pinned, features))])
+                    },
                 
-                // DevDocs.io tools
-                "list_devdocs_documentations" => {
-                    let docs = this.list_devdocs_documentations().await?;
-                    Ok(vec![Content::text(docs)])
-                },
-                "get_devdocs_documentation" => {
-                    let slug = arguments
-                        .get("slug")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| ToolError::InvalidParameters("slug is required".to_string()))?
-                        .to_string();
+                    // DevDocs.io tools
+                    "list_devdocs_documentations" => {
+                        let docs = this.list_devdocs_documentations().await?;
+                        Ok(vec![Content::text(docs)])
+                    },
+                    "get_devdocs_documentation" => {
+                        let slug = arguments
+                            .get("slug")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| ToolError::InvalidParameters("slug is required".to_string()))?
+                            .to_string();
                     
-                    let entry = arguments
-                        .get("entry")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string());
+                        let entry = arguments
+                            .get("entry")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string());
                     
-                    let doc = this.get_devdocs_documentation(slug, entry).await?;
-                    Ok(vec![Content::text(doc)])
-                },
-                "search_devdocs_documentation" => {
-                    let slug = arguments
-                        .get("slug")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| ToolError::InvalidParameters("slug is required".to_string()))?
-                        .to_string();
+                        let doc = this.get_devdocs_documentation(slug, entry).await?;
+                        Ok(vec![Content::text(doc)])
+                    },
+                    "search_devdocs_documentation" => {
+                        let slug = arguments
+                            .get("slug")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| ToolError::InvalidParameters("slug is required".to_string()))?
+                            .to_string();
                     
-                    let query = arguments
-                        .get("query")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| ToolError::InvalidParameters("query is required".to_string()))?
-                        .to_string();
+                        let query = arguments
+                            .get("query")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| ToolError::InvalidParameters("query is required".to_string()))?
+                            .to_string();
                     
-                    let results = this.search_devdocs_documentation(slug, query).await?;
-                    Ok(vec![Content::text(results)])
-                },
+                        let results = this.search_devdocs_documentation(slug, query).await?;
+                        Ok(vec![Content::text(results)])
+                    },
                 
-                // NPM tools
-                "lookup_npm_package" => {
-                    let package_name = arguments
-                        .get("package_name")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| ToolError::InvalidParameters("package_name is required".to_string()))?
-                        .to_string();
+                    // NPM tools
+                    "lookup_npm_package" => {
+                        let package_name = arguments
+                            .get("package_name")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| ToolError::InvalidParameters("package_name is required".to_string()))?
+                            .to_string();
                     
-                    let version = arguments
-                        .get("version")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string());
+                        let version = arguments
+                            .get("version")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string());
                     
-                    let doc = this.lookup_npm_package(package_name, version).await?;
-                    Ok(vec![Content::text(doc)])
-                },
-                "search_npm_packages" => {
-                    let query = arguments
-                        .get("query")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| ToolError::InvalidParameters("query is required".to_string()))?
-                        .to_string();
+                        let (version, pinned) = this.pin_version(Ecosystem::Npm, &package_name, version).await;
+                        let doc = this.lookup_npm_package(package_name, version).await?;
+                        Ok(vec![Content::text(with_note(pinned, doc))])
+                    },
+                    "search_npm_packages" => {
+                        let query = arguments
+                            .get("query")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| ToolError::InvalidParameters("query is required".to_string()))?
+                            .to_string();
                     
-                    let limit = arguments
-                        .get("limit")
-                        .and_then(|v| v.as_u64())
-                        .map(|v| v as u32);
+                        let limit = arguments
+                            .get("limit")
+                            .and_then(|v| v.as_u64())
+                            .map(|v| v as u32);
                     
-                    let results = this.search_npm_packages(query, limit).await?;
-                    Ok(vec![Content::text(results)])
-                },
+                        let results = this.search_npm_packages(query, limit).await?;
+                        Ok(vec![Content::text(results)])
+                    },
                 
-                // PyPI tools
-                "lookup_pypi_package" => {
-                    let package_name = arguments
-                        .get("package_name")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| ToolError::InvalidParameters("package_name is required".to_string()))?
-                        .to_string();
+                    // PyPI tools
+                    "lookup_pypi_package" => {
+                        let package_name = arguments
+                            .get("package_name")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| ToolError::InvalidParameters("package_name is required".to_string()))?
+                            .to_string();
                     
-                    let version = arguments
-                        .get("version")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string());
+                        let version = arguments
+                            .get("version")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string());
                     
-                    let doc = this.lookup_pypi_package(package_name, version).await?;
-                    Ok(vec![Content::text(doc)])
-                },
-                "search_pypi_packages" => {
-                    let query = arguments
-                        .get("query")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| ToolError::InvalidParameters("query is required".to_string()))?
-                        .to_string();
+                        let (version, pinned) = this.pin_version(Ecosystem::PyPI, &package_name, version).await;
+                        let doc = this.lookup_pypi_package(package_name, version).await?;
+                        Ok(vec![Content::text(with_note(pinned, doc))])
+                    },
+                    "search_pypi_packages" => {
+                        let query = arguments
+                            .get("query")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| ToolError::InvalidParameters("query is required".to_string()))?
+                            .to_string();
                     
-                    let limit = arguments
-                        .get("limit")
-                        .and_then(|v| v.as_u64())
-                        .map(|v| v as u32);
+                        let limit = arguments
+                            .get("limit")
+                            .and_then(|v| v.as_u64())
+                            .map(|v| v as u32);
                     
-                    let results = this.search_pypi_packages(query, limit).await?;
-                    Ok(vec![Content::text(results)])
-                },
+                        let results = this.search_pypi_packages(query, limit).await?;
+                        Ok(vec![Content::text(results)])
+                    },
                 
-                // Go tools
-                "lookup_go_package" => {
-                    let package_name = arguments
-                        .get("package_name")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| ToolError::InvalidParameters("package_name is required".to_string()))?
-                        .to_string();
+                    // Go tools
+                    "lookup_go_package" => {
+                        let package_name = arguments
+                            .get("package_name")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| ToolError::InvalidParameters("package_name is required".to_string()))?
+                            .to_string();
                     
-                    let version = arguments
-                        .get("version")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string());
+                        let version = arguments
+                            .get("version")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string());
                     
-                    let doc = this.lookup_go_package(package_name, version).await?;
-                    Ok(vec![Content::text(doc)])
-                },
-                "search_go_packages" => {
-                    let query = arguments
-                        .get("query")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| ToolError::InvalidParameters("query is required".to_string()))?
-                        .to_string();
+                        let (version, pinned) = this.pin_version(Ecosystem::Go, &package_name, version).await;
+                        let doc = this.lookup_go_package(package_name, version).await?;
+                        Ok(vec![Content::text(with_note(pinned, doc))])
+                    },
+                    "search_go_packages" => {
+                        let query = arguments
+                            .get("query")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| ToolError::InvalidParameters("query is required".to_string()))?
+                            .to_string();
                     
-                    let limit = arguments
-                        .get("limit")
-                        .and_then(|v| v.as_u64())
-                        .map(|v| v as u32);
+                        let limit = arguments
+                            .get("limit")
+                            .and_then(|v| v.as_u64())
+                            .map(|v| v as u32);
                     
-                    let results = this.search_go_packages(query, limit).await?;
-                    Ok(vec![Content::text(results)])
-                },
-                "lookup_go_symbol" => {
-                    let package_name = arguments
-                        .get("package_name")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| ToolError::InvalidParameters("package_name is required".to_string()))?
-                        .to_string();
+                        let results = this.search_go_packages(query, limit).await?;
+                        Ok(vec![Content::text(results)])
+                    },
+                    "lookup_go_symbol" => {
+                        let package_name = arguments
+                            .get("package_name")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| ToolError::InvalidParameters("package_name is required".to_string()))?
+                            .to_string();
                     
-                    let symbol_name = arguments
-                        .get("symbol_name")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| ToolError::InvalidParameters("symbol_name is required".to_string()))?
-                        .to_string();
+                        let symbol_name = arguments
+                            .get("symbol_name")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| ToolError::InvalidParameters("symbol_name is required".to_string()))?
+                            .to_string();
                     
-                    let version = arguments
-                        .get("version")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string());
+                        let version = arguments
+                            .get("version")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string());
                     
-                    let doc = this.lookup_go_symbol(package_name, symbol_name, version).await?;
-                    Ok(vec![Content::text(doc)])
-                },
-                "lookup_go_item" => {
-                    let package_name = arguments
-                        .get("package_name")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| ToolError::InvalidParameters("package_name is required".to_string()))?
-                        .to_string();
-
-                    let item_path = arguments
-                        .get("item_path")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| ToolError::InvalidParameters("item_path is required".to_string()))?
-                        .to_string();
-
-                    let version = arguments
-                        .get("version")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string());
-
-                    let doc = this.lookup_go_item(package_name, item_path, version).await?;
-                    Ok(vec![Content::text(doc)])
-                },
+                        let (version, pinned) = this.pin_version(Ecosystem::Go, &package_name, version).await;
+                        let doc = this.lookup_go_symbol(package_name, symbol_name, version).await?;
+                        Ok(vec![Content::text(with_note(pinned, doc))])
+                    },
+                    "lookup_go_item" => {
+                        let package_name = arguments
+                            .get("package_name")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| ToolError::InvalidParameters("package_name is required".to_string()))?
+                            .to_string();
+
+                        let item_path = arguments
+                            .get("item_path")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| ToolError::InvalidParameters("item_path is required".to_string()))?
+                            .to_string();
+
+                        let version = arguments
+                            .get("version")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string());
+
+                        let (version, pinned) = this.pin_version(Ecosystem::Go, &package_name, version).await;
+                        let doc = this.lookup_go_item(package_name, item_path, version).await?;
+                        Ok(vec![Content::text(with_note(pinned, doc))])
+                    },
                 
-                _ => Err(ToolError::NotFound(format!("Tool {} not found", tool_name))),
+                    _ => Err(ToolError::NotFound(format!("Tool {} not found", tool_name))),
+                }
             }
+            .await;
+
+            let bytes = result.as_ref().ok().map(|contents| {
+                contents
+                    .iter()
+                    .map(|content| match content {
+                        Content::Text(text) => text.text.len(),
+                        _ => 0,
+                    })
+                    .sum()
+            });
+            let known = TOOL_NAMES.contains(&tool_name.as_str());
+            metrics().record_tool_call(if known { &tool_name } else { "unknown" }, bytes);
+            result
         })
     }
 
     fn list_resources(&self) -> Vec<Resource> {
-        vec![]
+        let Some(project) = &self.project else {
+            return vec![];
+        };
+
+        project
+            .dependencies()
+            .iter()
+            .filter_map(|dependency| {
+                let uri = DocUri::for_dependency(dependency);
+                let name = match &uri.version {
+                    Some(version) => format!("{} {}", dependency.name, version),
+                    None => dependency.name.clone(),
+                };
+                let description = match &dependency.locked {
+                    Some(locked) => format!("{} documentation (locked in {})", ecosystem_label(dependency.ecosystem), locked.source),
+                    None => format!("{} documentation", ecosystem_label(dependency.ecosystem)),
+                };
+                Resource::new(uri.to_string(), Some("text".to_string()), Some(name))
+                    .ok()
+                    .map(|resource| resource.with_description(description))
+            })
+            .collect()
     }
 
     fn read_resource(
         &self,
-        _uri: &str,
+        uri: &str,
     ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
+        let this = self.clone();
+        let uri = uri.to_string();
+
         Box::pin(async move {
-            Err(ResourceError::NotFound("Resource not found".to_string()))
+            let doc_uri = DocUri::parse(&uri)
+                .ok_or_else(|| ResourceError::NotFound(format!("Resource {} not found", uri)))?;
+            this.read_doc(doc_uri)
+                .await
+                .map_err(|e| ResourceError::ExecutionError(e.to_string()))
         })
     }
 
     fn list_prompts(&self) -> Vec<Prompt> {
-        vec![]
+        PROMPTS.iter().map(PromptSpec::to_prompt).collect()
     }
 
+    /// The bare instructions with `{argument}` placeholders; `DocService` answers
+    /// `prompts/get` with `render_prompt` instead, which also embeds the documentation
     fn get_prompt(
         &self,
         prompt_name: &str,
     ) -> Pin<Box<dyn Future<Output = Result<String, PromptError>> + Send + 'static>> {
         let prompt_name = prompt_name.to_string();
         Box::pin(async move {
-            Err(PromptError::NotFound(format!(
-                "Prompt {} not found",
-                prompt_name
-            )))
+            prompts::find(&prompt_name)
+                .map(|spec| spec.template.to_string())
+                .ok_or_else(|| PromptError::NotFound(format!("Prompt {} not found", prompt_name)))
         })
     }
 }
\ No newline at end of file
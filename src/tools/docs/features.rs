@@ -0,0 +1,280 @@
+use std::collections::BTreeMap;
+
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+
+/// Items listed per feature before the rest are summarized
+const GATED_ITEMS_SHOWN: usize = 25;
+
+/// One published version of a crate, as recorded in the registry index
+#[derive(Debug, Clone, Deserialize)]
+pub struct IndexEntry {
+    pub name: String,
+    pub vers: String,
+    #[serde(default)]
+    pub deps: Vec<IndexDependency>,
+    #[serde(default)]
+    pub features: BTreeMap<String, Vec<String>>,
+    /// Features using `dep:` or `?/` syntax, kept apart for old Cargo versions
+    #[serde(default)]
+    pub features2: Option<BTreeMap<String, Vec<String>>>,
+    #[serde(default)]
+    pub yanked: bool,
+}
+
+/// A dependency of an index entry
+#[derive(Debug, Clone, Deserialize)]
+pub struct IndexDependency {
+    /// Name the dependency is referred to by, which may be a rename
+    pub name: String,
+    pub req: String,
+    #[serde(default)]
+    pub optional: bool,
+    /// Actual crate name when the dependency is renamed
+    #[serde(default)]
+    pub package: Option<String>,
+    #[serde(default)]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub target: Option<String>,
+}
+
+/// Check that `crate_name` could name a crate: ASCII letters, digits, `-` and `_`
+pub fn validate_crate_name(crate_name: &str) -> Result<(), String> {
+    if crate_name.is_empty() {
+        return Err("crate_name must not be empty".to_string());
+    }
+    if !crate_name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_') {
+        return Err(format!(
+            "Invalid crate name `{}`: only ASCII letters, digits, `-` and `_` are allowed",
+            crate_name
+        ));
+    }
+    Ok(())
+}
+
+/// Path of a crate's file in a sparse registry index (`se/rd/serde`)
+///
+/// `crate_name` must have passed [`validate_crate_name`].
+pub fn index_path(crate_name: &str) -> String {
+    let name = crate_name.to_lowercase();
+    match name.len() {
+        1 => format!("1/{}", name),
+        2 => format!("2/{}", name),
+        3 => format!("3/{}/{}", &name[..1], name),
+        _ => format!("{}/{}/{}", &name[..2], &name[2..4], name),
+    }
+}
+
+/// Parse a sparse index file, one JSON entry per line
+pub fn parse_index(text: &str) -> Vec<IndexEntry> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Pick the entry for `version`: an exact version, a requirement like `1.2`, or the latest release
+///
+/// Without a version, this is the latest stable release, falling back to pre-releases and
+/// then to yanked versions.
+pub fn select_version<'a>(entries: &'a [IndexEntry], version: Option<&str>) -> Option<&'a IndexEntry> {
+    let version = version.map(str::trim).filter(|v| !v.is_empty() && *v != "latest");
+    if let Some(wanted) = version {
+        if let Some(entry) = entries.iter().find(|e| e.vers == wanted) {
+            return Some(entry);
+        }
+        let req = VersionReq::parse(wanted).ok()?;
+        return newest(entries.iter().filter(|e| !e.yanked), |v| req.matches(v));
+    }
+
+    let live = || entries.iter().filter(|e| !e.yanked);
+    newest(live(), |v| v.pre.is_empty())
+        .or_else(|| newest(live(), |_| true))
+        .or_else(|| entries.last())
+}
+
+fn newest<'a>(entries: impl Iterator<Item = &'a IndexEntry>, accept: impl Fn(&Version) -> bool) -> Option<&'a IndexEntry> {
+    entries
+        .filter_map(|e| Version::parse(&e.vers).ok().map(|v| (v, e)))
+        .filter(|(v, _)| accept(v))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, e)| e)
+}
+
+impl IndexEntry {
+    /// Explicit features merged with the implicit feature of every optional dependency
+    /// that is never referred to with `dep:`
+    pub fn all_features(&self) -> BTreeMap<String, Vec<String>> {
+        let mut features = self.features.clone();
+        if let Some(features2) = &self.features2 {
+            features.extend(features2.clone());
+        }
+
+        let uses_dep_syntax = |dep: &str| {
+            features
+                .values()
+                .flatten()
+                .any(|value| value.strip_prefix("dep:") == Some(dep))
+        };
+        let implicit: Vec<String> = self
+            .optional_deps()
+            .map(|dep| dep.name.clone())
+            .filter(|name| !features.contains_key(name) && !uses_dep_syntax(name))
+            .collect();
+        for name in implicit {
+            features.insert(name.clone(), vec![format!("dep:{}", name)]);
+        }
+        features
+    }
+
+    fn optional_deps(&self) -> impl Iterator<Item = &IndexDependency> {
+        self.deps.iter().filter(|dep| dep.optional)
+    }
+
+    fn is_optional_dep(&self, name: &str) -> bool {
+        self.optional_deps().any(|dep| dep.name == name)
+    }
+
+    /// Features that turn on the optional dependency `dep`
+    fn enabling_features<'a>(&self, features: &'a BTreeMap<String, Vec<String>>, dep: &str) -> Vec<&'a str> {
+        features
+            .iter()
+            .filter(|(_, values)| {
+                values.iter().any(|value| {
+                    value.strip_prefix("dep:") == Some(dep)
+                        || value == dep
+                        || value.split_once('/').is_some_and(|(name, _)| name == dep)
+                })
+            })
+            .map(|(feature, _)| feature.as_str())
+            .collect()
+    }
+
+    /// Describe what enabling one entry of a feature's list does
+    fn describe_value(&self, features: &BTreeMap<String, Vec<String>>, value: &str) -> String {
+        if let Some(dep) = value.strip_prefix("dep:") {
+            return format!("optional dependency `{}`", dep);
+        }
+        if let Some((dep, feature)) = value.split_once('/') {
+            return match dep.strip_suffix('?') {
+                Some(dep) => format!("feature `{}` of `{}` (if enabled)", feature, dep),
+                None => format!("feature `{}` of `{}`", feature, dep),
+            };
+        }
+        if !features.contains_key(value) && self.is_optional_dep(value) {
+            return format!("optional dependency `{}`", value);
+        }
+        format!("`{}`", value)
+    }
+
+    /// Markdown feature table, optional dependencies and (if known) the items gated behind each feature
+    pub fn render_features(&self, gated_items: Option<&BTreeMap<String, Vec<String>>>) -> String {
+        let features = self.all_features();
+        let mut out = format!("# Features of `{}` {}\n\n", self.name, self.vers);
+        if self.yanked {
+            out.push_str("> This version has been yanked.\n\n");
+        }
+
+        let defaults = features.get("default").filter(|d| !d.is_empty());
+        match defaults {
+            Some(defaults) => {
+                let list: Vec<String> = defaults.iter().map(|d| format!("`{}`", d)).collect();
+                out.push_str(&format!("**Default features:** {}\n\n", list.join(", ")));
+            }
+            None => out.push_str("**Default features:** none\n\n"),
+        }
+
+        let listed: Vec<(&String, &Vec<String>)> = features.iter().filter(|(name, _)| *name != "default").collect();
+        if listed.is_empty() {
+            out.push_str("This crate has no optional features.\n");
+            return out;
+        }
+
+        out.push_str("## Features\n\n| Feature | Enables |\n|---------|---------|\n");
+        for (name, values) in listed {
+            let enables = if values.is_empty() {
+                "—".to_string()
+            } else {
+                values
+                    .iter()
+                    .map(|value| self.describe_value(&features, value))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            let implicit = if self.features.contains_key(name)
+                || self.features2.as_ref().is_some_and(|f| f.contains_key(name))
+            {
+                ""
+            } else {
+                " (implicit)"
+            };
+            out.push_str(&format!("| `{}`{} | {} |\n", name, implicit, enables));
+        }
+        out.push('\n');
+
+        let optional: Vec<&IndexDependency> = self.optional_deps().collect();
+        if !optional.is_empty() {
+            out.push_str("## Optional dependencies\n\n");
+            for dep in optional {
+                out.push_str(&format!("- `{}` `{}`", dep.name, dep.req));
+                if let Some(package) = dep.package.as_deref().filter(|p| *p != dep.name) {
+                    out.push_str(&format!(" (package `{}`)", package));
+                }
+                if let Some(kind) = dep.kind.as_deref().filter(|k| *k != "normal") {
+                    out.push_str(&format!(" ({} dependency)", kind));
+                }
+                if let Some(target) = &dep.target {
+                    out.push_str(&format!(" on `{}`", target));
+                }
+                let enabled_by = self.enabling_features(&features, &dep.name);
+                if !enabled_by.is_empty() {
+                    let list: Vec<String> = enabled_by.iter().map(|f| format!("`{}`", f)).collect();
+                    out.push_str(&format!(" — enabled by {}", list.join(", ")));
+                }
+                out.push('\n');
+            }
+            out.push('\n');
+        }
+
+        if let Some(gated_items) = gated_items.filter(|g| !g.is_empty()) {
+            out.push_str("## Items gated by features\n\n");
+            for (feature, items) in gated_items {
+                out.push_str(&format!("### `{}`\n\n", feature));
+                for item in items.iter().take(GATED_ITEMS_SHOWN) {
+                    out.push_str(&format!("- `{}`\n", item));
+                }
+                if items.len() > GATED_ITEMS_SHOWN {
+                    out.push_str(&format!("- _and {} more_\n", items.len() - GATED_ITEMS_SHOWN));
+                }
+                out.push('\n');
+            }
+        }
+
+        out.trim_end().to_string() + "\n"
+    }
+}
+
+/// Features named in a `#[doc(cfg(...))]` attribute, e.g. `["fs", "rt"]` for
+/// `#[doc(cfg(all(feature = "fs", feature = "rt")))]`
+pub fn doc_cfg_features(attr: &str) -> Vec<String> {
+    let compact: String = attr.chars().filter(|c| !c.is_whitespace()).collect();
+    let Some(start) = compact.find("doc(cfg(") else {
+        return Vec::new();
+    };
+
+    let mut features = Vec::new();
+    let mut rest = &compact[start..];
+    while let Some(i) = rest.find("feature=\"") {
+        rest = &rest[i + 9..];
+        let Some(end) = rest.find('"') else {
+            break;
+        };
+        let feature = rest[..end].to_string();
+        if !features.contains(&feature) {
+            features.push(feature);
+        }
+        rest = &rest[end..];
+    }
+    features
+}
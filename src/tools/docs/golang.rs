@@ -1,13 +1,20 @@
 use mcp_core::ToolError;
 use reqwest::Client;
 use html2md::parse_html;
-use std::sync::{Arc, Mutex};
-use std::collections::HashMap;
+
+use super::auth::{go_path_matches, RegistryAuth, RequestAuthExt};
+use super::cache::{DocCache, Freshness};
+use super::metrics::TimedSend;
+use super::progress::report;
+use super::registry::{base_url, RegistryUrls};
 
 #[derive(Clone)]
 pub struct GoClient {
     client: Client,
-    cache: Arc<Mutex<HashMap<String, String>>>,
+    cache: DocCache,
+    base_url: String,
+    auth: Option<RegistryAuth>,
+    private_modules: Vec<String>,
 }
 
 impl Default for GoClient {
@@ -18,9 +25,42 @@ impl Default for GoClient {
 
 impl GoClient {
     pub fn new() -> Self {
+        Self::with_cache(DocCache::new())
+    }
+
+    /// Create a client that stores its lookups in `cache`
+    pub fn with_cache(cache: DocCache) -> Self {
         Self {
             client: Client::new(),
-            cache: Arc::new(Mutex::new(HashMap::new())),
+            cache,
+            base_url: RegistryUrls::default().go,
+            auth: None,
+            private_modules: Vec::new(),
+        }
+    }
+
+    /// Use `url` as the Go documentation server base URL instead of the public one
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        self.base_url = base_url(url);
+        self.cache = self.cache.for_registry(url, &RegistryUrls::default().go);
+        self
+    }
+
+    /// Send `auth` with requests for modules matching one of the `GOPRIVATE`-style
+    /// `private_modules` patterns, or with every request when there are none
+    pub fn with_auth(mut self, auth: Option<RegistryAuth>, private_modules: Vec<String>) -> Self {
+        self.auth = auth;
+        self.private_modules = private_modules;
+        self
+    }
+
+    fn auth_for(&self, module: &str) -> Option<&RegistryAuth> {
+        if self.private_modules.is_empty()
+            || self.private_modules.iter().any(|pattern| go_path_matches(pattern, module))
+        {
+            self.auth.as_ref()
+        } else {
+            None
         }
     }
 
@@ -31,22 +71,22 @@ impl GoClient {
             format!("go:package:{}", package_name)
         };
 
-        if let Some(cached_doc) = self.cache.lock()
-            .map_err(|e| ToolError::ExecutionError(format!("Cache lock error: {}", e)))?
-            .get(&cache_key)
-        {
-            return Ok(cached_doc.clone());
+        if let Some(cached_doc) = self.cache.get(&cache_key).await {
+            return Ok(cached_doc);
         }
 
+        let freshness = Freshness::for_version(&version);
         let url = if let Some(ver) = version {
-            format!("https://pkg.go.dev/{}@{}", package_name, ver)
+            format!("{}/{}@{}", self.base_url, package_name, ver)
         } else {
-            format!("https://pkg.go.dev/{}", package_name)
+            format!("{}/{}", self.base_url, package_name)
         };
         
+        report(format!("Fetching `{}` from pkg.go.dev", package_name));
         let response = self.client.get(&url)
             .header("User-Agent", "CodeNav/0.1.0")
-            .send()
+            .registry_auth(self.auth_for(&package_name))
+            .send_timed()
             .await
             .map_err(|e| ToolError::ExecutionError(format!("Failed to fetch Go package documentation: {}", e)))?;
 
@@ -60,11 +100,10 @@ impl GoClient {
         let html_body = response.text().await
             .map_err(|e| ToolError::ExecutionError(format!("Failed to read response body: {}", e)))?;
         
+        report("Converting HTML to Markdown");
         let markdown_body = parse_html(&html_body);
 
-        self.cache.lock()
-            .map_err(|e| ToolError::ExecutionError(format!("Cache lock error: {}", e)))?
-            .insert(cache_key, markdown_body.clone());
+        self.cache.set_with(cache_key, markdown_body.clone(), freshness).await;
 
         Ok(markdown_body)
     }
@@ -73,18 +112,16 @@ impl GoClient {
         let limit = limit.unwrap_or(10).min(100);
         let cache_key = format!("go:search:{}:{}", query, limit);
 
-        if let Some(cached_results) = self.cache.lock()
-            .map_err(|e| ToolError::ExecutionError(format!("Cache lock error: {}", e)))?
-            .get(&cache_key)
-        {
-            return Ok(cached_results.clone());
+        if let Some(cached_results) = self.cache.get(&cache_key).await {
+            return Ok(cached_results);
         }
 
-        let url = format!("https://pkg.go.dev/search?q={}&limit={}", query, limit);
+        let url = format!("{}/search?q={}&limit={}", self.base_url, query, limit);
         
         let response = self.client.get(&url)
             .header("User-Agent", "CodeNav/0.1.0")
-            .send()
+            .registry_auth(self.auth_for(""))
+            .send_timed()
             .await
             .map_err(|e| ToolError::ExecutionError(format!("Failed to search Go packages: {}", e)))?;
 
@@ -100,13 +137,44 @@ impl GoClient {
         
         let markdown_body = parse_html(&html_body);
 
-        self.cache.lock()
-            .map_err(|e| ToolError::ExecutionError(format!("Cache lock error: {}", e)))?
-            .insert(cache_key, markdown_body.clone());
+        self.cache.set(cache_key, markdown_body.clone()).await;
 
         Ok(markdown_body)
     }
 
+    /// Import paths of packages matching `prefix`, for argument completion
+    pub async fn package_names(&self, prefix: &str) -> Result<Vec<String>, ToolError> {
+        let url = format!("{}/search", self.base_url);
+        let response = self.client.get(&url)
+            .query(&[("q", prefix), ("limit", "50")])
+            .header("User-Agent", "CodeNav/0.1.0")
+            .registry_auth(self.auth_for(""))
+            .send_timed()
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to search Go packages: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(ToolError::ExecutionError(format!(
+                "Failed to search Go packages. Status: {}",
+                response.status()
+            )));
+        }
+
+        let html_body = response.text().await
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read response body: {}", e)))?;
+
+        let paths = html_body
+            .match_indices("data-test-id=\"snippet-title\"")
+            .filter_map(|(at, _)| {
+                let tag = &html_body[html_body[..at].rfind('<')?..at];
+                let href = tag.split_once("href=\"/")?.1;
+                let href = &href[..href.find('"')?];
+                Some(href.split('?').next().unwrap_or(href).to_string())
+            })
+            .collect();
+        Ok(paths)
+    }
+
     pub async fn lookup_item(&self, package_name: String, item_path: String, version: Option<String>) -> Result<String, ToolError> {
         let cache_key = if let Some(ver) = &version {
             format!("go:item:{}@{}#{}", package_name, ver, item_path)
@@ -114,22 +182,22 @@ impl GoClient {
             format!("go:item:{}#{}", package_name, item_path)
         };
 
-        if let Some(cached_doc) = self.cache.lock()
-            .map_err(|e| ToolError::ExecutionError(format!("Cache lock error: {}", e)))?
-            .get(&cache_key)
-        {
-            return Ok(cached_doc.clone());
+        if let Some(cached_doc) = self.cache.get(&cache_key).await {
+            return Ok(cached_doc);
         }
 
+        let freshness = Freshness::for_version(&version);
         let url = if let Some(ver) = version {
-            format!("https://pkg.go.dev/{}@{}#{}", package_name, ver, item_path)
+            format!("{}/{}@{}#{}", self.base_url, package_name, ver, item_path)
         } else {
-            format!("https://pkg.go.dev/{}#{}", package_name, item_path)
+            format!("{}/{}#{}", self.base_url, package_name, item_path)
         };
 
+        report(format!("Fetching `{}` from pkg.go.dev", package_name));
         let response = self.client.get(&url)
             .header("User-Agent", "CodeNav/0.1.0")
-            .send()
+            .registry_auth(self.auth_for(&package_name))
+            .send_timed()
             .await
             .map_err(|e| ToolError::ExecutionError(format!("Failed to fetch Go item documentation: {}", e)))?;
 
@@ -143,11 +211,10 @@ impl GoClient {
         let html_body = response.text().await
             .map_err(|e| ToolError::ExecutionError(format!("Failed to read response body: {}", e)))?;
         
+        report("Converting HTML to Markdown");
         let markdown_body = parse_html(&html_body);
 
-        self.cache.lock()
-            .map_err(|e| ToolError::ExecutionError(format!("Cache lock error: {}", e)))?
-            .insert(cache_key, markdown_body.clone());
+        self.cache.set_with(cache_key, markdown_body.clone(), freshness).await;
 
         Ok(markdown_body)
     }
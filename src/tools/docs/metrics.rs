@@ -0,0 +1,143 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    future::Future,
+    sync::{Mutex, MutexGuard, OnceLock},
+    time::{Duration, Instant},
+};
+
+use reqwest::{RequestBuilder, Response};
+
+/// Upper bounds, in seconds, of the upstream latency histogram buckets
+const LATENCY_BUCKETS: [f64; 9] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// Process-wide counters of tool calls and upstream requests, exported by `/metrics`
+///
+/// Like [`progress::report`](super::progress::report), recording needs no handle, so
+/// every documentation client can time its requests without being handed one.
+#[derive(Default)]
+pub struct Metrics {
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    /// Calls per (tool, outcome)
+    tool_calls: BTreeMap<(String, &'static str), u64>,
+    /// Bytes of content returned per tool
+    tool_bytes: BTreeMap<String, u64>,
+    upstream: BTreeMap<String, Histogram>,
+    upstream_errors: BTreeMap<String, u64>,
+}
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+/// The metrics of this process
+pub fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(Metrics::default)
+}
+
+impl Metrics {
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Count a call of `tool` and, if it succeeded, the bytes it returned
+    pub fn record_tool_call(&self, tool: &str, bytes: Option<usize>) {
+        let mut inner = self.lock();
+        let outcome = if bytes.is_some() { "success" } else { "error" };
+        *inner.tool_calls.entry((tool.to_string(), outcome)).or_default() += 1;
+        *inner.tool_bytes.entry(tool.to_string()).or_default() += bytes.unwrap_or(0) as u64;
+    }
+
+    /// Record how long a request to `host` took, and whether it failed without a response
+    pub fn record_upstream(&self, host: &str, elapsed: Duration, failed: bool) {
+        let mut inner = self.lock();
+        if failed {
+            *inner.upstream_errors.entry(host.to_string()).or_default() += 1;
+        }
+        let histogram = inner.upstream.entry(host.to_string()).or_default();
+        let secs = elapsed.as_secs_f64();
+        for (bucket, bound) in histogram.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if secs <= bound {
+                *bucket += 1;
+            }
+        }
+        histogram.count += 1;
+        histogram.sum += secs;
+    }
+
+    /// Append these metrics to `out` in the Prometheus text format
+    pub fn render(&self, out: &mut String) {
+        let inner = self.lock();
+
+        out.push_str("# HELP devdocs_tool_calls_total Tool calls by tool and outcome.\n");
+        out.push_str("# TYPE devdocs_tool_calls_total counter\n");
+        for ((tool, outcome), count) in &inner.tool_calls {
+            let _ = writeln!(out, "devdocs_tool_calls_total{{tool=\"{}\",outcome=\"{}\"}} {}", escape(tool), outcome, count);
+        }
+
+        out.push_str("# HELP devdocs_tool_response_bytes_total Bytes of documentation returned by tool calls.\n");
+        out.push_str("# TYPE devdocs_tool_response_bytes_total counter\n");
+        for (tool, bytes) in &inner.tool_bytes {
+            let _ = writeln!(out, "devdocs_tool_response_bytes_total{{tool=\"{}\"}} {}", escape(tool), bytes);
+        }
+
+        out.push_str("# HELP devdocs_upstream_request_duration_seconds Latency of requests to documentation registries.\n");
+        out.push_str("# TYPE devdocs_upstream_request_duration_seconds histogram\n");
+        for (host, histogram) in &inner.upstream {
+            let host = escape(host);
+            for (count, bound) in histogram.buckets.iter().zip(LATENCY_BUCKETS) {
+                let _ = writeln!(
+                    out,
+                    "devdocs_upstream_request_duration_seconds_bucket{{host=\"{}\",le=\"{}\"}} {}",
+                    host, bound, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "devdocs_upstream_request_duration_seconds_bucket{{host=\"{}\",le=\"+Inf\"}} {}",
+                host, histogram.count
+            );
+            let _ = writeln!(out, "devdocs_upstream_request_duration_seconds_sum{{host=\"{}\"}} {}", host, histogram.sum);
+            let _ = writeln!(out, "devdocs_upstream_request_duration_seconds_count{{host=\"{}\"}} {}", host, histogram.count);
+        }
+
+        out.push_str("# HELP devdocs_upstream_request_errors_total Requests to documentation registries that got no response.\n");
+        out.push_str("# TYPE devdocs_upstream_request_errors_total counter\n");
+        for (host, count) in &inner.upstream_errors {
+            let _ = writeln!(out, "devdocs_upstream_request_errors_total{{host=\"{}\"}} {}", escape(host), count);
+        }
+    }
+}
+
+/// Escape a Prometheus label value
+pub fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Send a request, recording its latency under the host it went to
+pub(crate) trait TimedSend {
+    fn send_timed(self) -> impl Future<Output = reqwest::Result<Response>> + Send;
+}
+
+impl TimedSend for RequestBuilder {
+    async fn send_timed(self) -> reqwest::Result<Response> {
+        let started = Instant::now();
+        let result = self.send().await;
+        let url = match &result {
+            Ok(response) => Some(response.url()),
+            Err(e) => e.url(),
+        };
+        if let Some(host) = url.and_then(|url| url.host_str()) {
+            metrics().record_upstream(host, started.elapsed(), result.is_err());
+        }
+        result
+    }
+}
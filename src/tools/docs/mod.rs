@@ -1,8 +1,22 @@
 pub mod docs;
 pub use docs::DocRouter;
+pub use service::DocService;
+pub mod auth;
+pub mod cache;
+pub mod completion;
+pub mod crate_items;
 pub mod devdocs;
+pub mod features;
+pub mod metrics;
 pub mod npm;
+pub mod progress;
+pub mod project;
+pub mod prompts;
 pub mod pypi;
 pub mod golang;
+pub mod registry;
+pub mod resources;
+pub mod rustdoc;
+pub mod service;
 #[cfg(test)]
 mod tests;
\ No newline at end of file
@@ -1,15 +1,21 @@
 use reqwest::Client;
 use serde_json::Value;
 use anyhow::Result;
-use std::sync::Arc;
-use tokio::sync::Mutex;
 use mcp_core::ToolError;
 
+use super::auth::{RegistryAuth, RequestAuthExt};
+use super::cache::{DocCache, Freshness};
+use super::metrics::TimedSend;
+use super::progress::report;
+use super::registry::{base_url, RegistryUrls};
+
 /// NPM Client for fetching package documentation from npm registry
 #[derive(Clone)]
 pub struct NpmClient {
     client: Client,
-    cache: Arc<Mutex<std::collections::HashMap<String, String>>>,
+    cache: DocCache,
+    base_url: String,
+    auth: Option<RegistryAuth>,
 }
 
 impl Default for NpmClient {
@@ -20,22 +26,40 @@ impl Default for NpmClient {
 
 impl NpmClient {
     pub fn new() -> Self {
+        Self::with_cache(DocCache::new())
+    }
+
+    /// Create a client that stores its lookups in `cache`
+    pub fn with_cache(cache: DocCache) -> Self {
         Self {
             client: Client::new(),
-            cache: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            cache,
+            base_url: RegistryUrls::default().npm,
+            auth: None,
         }
     }
 
+    /// Use `url` as the NPM registry base URL instead of the public one
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        self.base_url = base_url(url);
+        self.cache = self.cache.for_registry(url, &RegistryUrls::default().npm);
+        self
+    }
+
+    /// Send `auth` with every registry request
+    pub fn with_auth(mut self, auth: Option<RegistryAuth>) -> Self {
+        self.auth = auth;
+        self
+    }
+
     /// Get cache entry
     pub async fn get_cache(&self, key: &str) -> Option<String> {
-        let cache = self.cache.lock().await;
-        cache.get(key).cloned()
+        self.cache.get(key).await
     }
 
     /// Set cache entry
     pub async fn set_cache(&self, key: String, value: String) {
-        let mut cache = self.cache.lock().await;
-        cache.insert(key, value);
+        self.cache.set(key, value).await;
     }
 
     /// Lookup NPM package information
@@ -54,16 +78,18 @@ impl NpmClient {
 
         // Construct the URL for package info
         let url = if let Some(ver) = &version {
-            format!("https://registry.npmjs.org/{}/{}", package_name, ver)
+            format!("{}/{}/{}", self.base_url, package_name, ver)
         } else {
-            format!("https://registry.npmjs.org/{}", package_name)
+            format!("{}/{}", self.base_url, package_name)
         };
 
         // Fetch package information
+        report(format!("Fetching `{}` from the npm registry", package_name));
         let response = self.client
             .get(&url)
             .header("User-Agent", "CodeNav-MCP/0.1.0")
-            .send()
+            .registry_auth(self.auth.as_ref())
+            .send_timed()
             .await
             .map_err(|e| ToolError::ExecutionError(format!("Failed to fetch npm package: {}", e)))?;
 
@@ -154,11 +180,46 @@ impl NpmClient {
         }
         
         // Cache the result
-        self.set_cache(cache_key, markdown.clone()).await;
+        self.cache.set_with(cache_key, markdown.clone(), Freshness::for_version(&version)).await;
         
         Ok(markdown)
     }
 
+    /// Names of packages matching `prefix`, for argument completion
+    pub async fn package_names(&self, prefix: &str) -> Result<Vec<String>, ToolError> {
+        let url = format!("{}/-/v1/search", self.base_url);
+        let response = self.client
+            .get(&url)
+            .query(&[("text", prefix), ("size", "50")])
+            .header("User-Agent", "CodeNav-MCP/0.1.0")
+            .registry_auth(self.auth.as_ref())
+            .send_timed()
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to search npm packages: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(ToolError::ExecutionError(format!(
+                "Failed to search npm packages. Status: {}",
+                response.status()
+            )));
+        }
+
+        let parsed_json: Value = response.json().await
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to parse JSON: {}", e)))?;
+        let names = parsed_json
+            .get("objects")
+            .and_then(|v| v.as_array())
+            .map(|objects| {
+                objects
+                    .iter()
+                    .filter_map(|pkg| pkg.pointer("/package/name").and_then(|v| v.as_str()))
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(names)
+    }
+
     /// Search NPM packages
     pub async fn search_packages(&self, query: String, limit: Option<u32>) -> Result<String, ToolError> {
         let limit = limit.unwrap_or(10).min(100); // Cap at 100 results
@@ -170,13 +231,14 @@ impl NpmClient {
         }
         
         // Construct search URL
-        let url = format!("https://registry.npmjs.org/-/v1/search?text={}&size={}", query, limit);
+        let url = format!("{}/-/v1/search?text={}&size={}", self.base_url, query, limit);
         
         // Fetch search results
         let response = self.client
             .get(&url)
             .header("User-Agent", "CodeNav-MCP/0.1.0")
-            .send()
+            .registry_auth(self.auth.as_ref())
+            .send_timed()
             .await
             .map_err(|e| ToolError::ExecutionError(format!("Failed to search npm packages: {}", e)))?;
             
@@ -0,0 +1,56 @@
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use serde_json::{json, Value};
+use tokio::sync::mpsc::UnboundedSender;
+
+tokio::task_local! {
+    static PROGRESS: Progress;
+}
+
+/// Destination of the progress notifications of one request: the `progressToken` the client
+/// sent with it and the channel of messages written to the client
+#[derive(Clone)]
+pub struct Progress {
+    token: Value,
+    messages: UnboundedSender<Value>,
+    steps: Arc<AtomicU64>,
+}
+
+impl Progress {
+    pub fn new(token: Value, messages: UnboundedSender<Value>) -> Self {
+        Self {
+            token,
+            messages,
+            steps: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Run `future`, sending whatever it passes to [`report`] to the client
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        PROGRESS.scope(self, future).await
+    }
+}
+
+/// Tell the client what the current request is doing, if it asked for progress
+///
+/// Does nothing outside [`Progress::scope`], e.g. in the CLI test tool.
+pub fn report(message: impl Into<String>) {
+    let _ = PROGRESS.try_with(|progress| {
+        let step = progress.steps.fetch_add(1, Ordering::Relaxed) + 1;
+        let _ = progress.messages.send(json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/progress",
+            "params": {
+                "progressToken": progress.token,
+                "progress": step,
+                "message": message.into(),
+            }
+        }));
+    });
+}
@@ -0,0 +1,775 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Package ecosystems whose lockfiles are understood
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ecosystem {
+    Rust,
+    Npm,
+    PyPI,
+    Go,
+}
+
+impl Ecosystem {
+    /// Name used for the ecosystem in `docs://` URIs
+    pub fn scheme(self) -> &'static str {
+        match self {
+            Ecosystem::Rust => "rust",
+            Ecosystem::Npm => "npm",
+            Ecosystem::PyPI => "pypi",
+            Ecosystem::Go => "go",
+        }
+    }
+
+    pub fn from_scheme(scheme: &str) -> Option<Self> {
+        Some(match scheme {
+            "rust" => Ecosystem::Rust,
+            "npm" => Ecosystem::Npm,
+            "pypi" => Ecosystem::PyPI,
+            "go" => Ecosystem::Go,
+            _ => return None,
+        })
+    }
+
+    /// Normalized package name, so lookups match however the lockfile spells it
+    ///
+    /// `-` and `_` are interchangeable in crate names, and PyPI names follow PEP 503:
+    /// case-insensitive, with runs of `-`, `_` and `.` equivalent.
+    fn key(self, name: &str) -> String {
+        let name = name.trim();
+        match self {
+            Ecosystem::Rust => name.to_lowercase().replace('_', "-"),
+            Ecosystem::PyPI => {
+                let mut key = String::new();
+                for c in name.to_lowercase().chars() {
+                    if matches!(c, '-' | '_' | '.') {
+                        if !key.ends_with('-') {
+                            key.push('-');
+                        }
+                    } else {
+                        key.push(c);
+                    }
+                }
+                key
+            }
+            Ecosystem::Npm => name.to_string(),
+            Ecosystem::Go => name.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+/// A dependency version resolved by one of the project's lockfiles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedVersion {
+    pub version: String,
+    /// Lockfile the version was read from, e.g. `Cargo.lock`
+    pub source: String,
+}
+
+impl LockedVersion {
+    /// Line prepended to documentation to say which version was looked up and why
+    pub fn note(&self, package: &str) -> String {
+        format!("> Using version {} of `{}` (locked in {})", self.version, package, self.source)
+    }
+}
+
+/// A package the project's manifests depend on directly
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    pub ecosystem: Ecosystem,
+    /// Name as spelled in the manifest
+    pub name: String,
+    /// Locked version, if a lockfile resolves it
+    pub locked: Option<LockedVersion>,
+}
+
+/// How long the files read are trusted before their modification times are checked again
+pub const RECHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Dependency versions of a local project, used when a lookup does not name a version
+///
+/// Lockfiles are re-read whenever one of them changes on disk, checked at most once per
+/// [`RECHECK_INTERVAL`].
+#[derive(Clone)]
+pub struct Project {
+    root: PathBuf,
+    recheck_interval: Duration,
+    loaded: Arc<Mutex<Option<Loaded>>>,
+}
+
+struct Loaded {
+    stamps: Vec<(PathBuf, Option<SystemTime>)>,
+    versions: LockedVersions,
+    checked: Instant,
+}
+
+/// Versions resolved by a project's lockfiles, keyed by normalized package name
+#[derive(Debug, Clone, Default)]
+pub struct LockedVersions {
+    pub crates: HashMap<String, LockedVersion>,
+    pub npm: HashMap<String, LockedVersion>,
+    pub pypi: HashMap<String, LockedVersion>,
+    /// Keyed by module path
+    pub go: HashMap<String, LockedVersion>,
+    /// Packages declared directly in the project's manifests
+    pub direct: Vec<(Ecosystem, String)>,
+}
+
+impl Project {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            recheck_interval: RECHECK_INTERVAL,
+            loaded: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Check the files for changes at most once per `interval` instead of [`RECHECK_INTERVAL`]
+    pub fn with_recheck_interval(mut self, interval: Duration) -> Self {
+        self.recheck_interval = interval;
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Locked version of the package `name`, if the project depends on it
+    pub fn locked(&self, ecosystem: Ecosystem, name: &str) -> Option<LockedVersion> {
+        self.versions().get(ecosystem, name).cloned()
+    }
+
+    /// Direct dependencies of the project, with their locked versions
+    pub fn dependencies(&self) -> Vec<Dependency> {
+        self.versions().dependencies()
+    }
+
+    /// Current lockfile versions, reloading them if any file changed since the last read
+    pub fn versions(&self) -> LockedVersions {
+        self.checked().unwrap_or_else(|| self.refresh())
+    }
+
+    /// [`Project::versions`], with the file checks and reads run on a blocking thread
+    pub async fn current_versions(&self) -> LockedVersions {
+        if let Some(versions) = self.checked() {
+            return versions;
+        }
+        let project = self.clone();
+        tokio::task::spawn_blocking(move || project.refresh())
+            .await
+            .unwrap_or_default()
+    }
+
+    /// The loaded versions, if their files were checked within the recheck interval
+    fn checked(&self) -> Option<LockedVersions> {
+        let loaded = self.loaded.lock().unwrap_or_else(|e| e.into_inner());
+        loaded
+            .as_ref()
+            .filter(|current| current.checked.elapsed() < self.recheck_interval)
+            .map(|current| current.versions.clone())
+    }
+
+    /// Check the files read last time and re-read them all if one changed
+    ///
+    /// The lock is only held to copy state in and out, never across file system calls.
+    fn refresh(&self) -> LockedVersions {
+        let stamps = {
+            let loaded = self.loaded.lock().unwrap_or_else(|e| e.into_inner());
+            loaded.as_ref().map(|current| current.stamps.clone())
+        };
+        if let Some(stamps) = stamps {
+            if stamps.iter().all(|(path, stamp)| modified(path) == *stamp) {
+                let mut loaded = self.loaded.lock().unwrap_or_else(|e| e.into_inner());
+                if let Some(current) = loaded.as_mut().filter(|current| current.stamps == stamps) {
+                    current.checked = Instant::now();
+                    return current.versions.clone();
+                }
+            }
+        }
+
+        let (versions, files) = LockedVersions::read(&self.root);
+        let stamps = files.into_iter().map(|path| {
+            let stamp = modified(&path);
+            (path, stamp)
+        });
+        let mut loaded = self.loaded.lock().unwrap_or_else(|e| e.into_inner());
+        *loaded = Some(Loaded {
+            stamps: stamps.collect(),
+            versions: versions.clone(),
+            checked: Instant::now(),
+        });
+        versions
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl LockedVersions {
+    /// Read every supported lockfile under `root`, returning the versions and the files consulted
+    pub fn read(root: &Path) -> (Self, Vec<PathBuf>) {
+        let mut files = Vec::new();
+        let mut direct: Vec<(Ecosystem, String)> = Vec::new();
+        for (name, _) in manifest_requirements(root, &mut files) {
+            if !direct.iter().any(|(_, known)| Ecosystem::Rust.key(known) == Ecosystem::Rust.key(&name)) {
+                direct.push((Ecosystem::Rust, name));
+            }
+        }
+        direct.extend(npm_direct(root, &mut files).into_iter().map(|name| (Ecosystem::Npm, name)));
+        direct.extend(pypi_direct(root, &mut files).into_iter().map(|name| (Ecosystem::PyPI, name)));
+        direct.extend(go_direct(root, &mut files).into_iter().map(|name| (Ecosystem::Go, name)));
+
+        let versions = Self {
+            crates: cargo_versions(root, &mut files),
+            npm: npm_versions(root, &mut files),
+            pypi: pypi_versions(root, &mut files),
+            go: go_versions(root, &mut files),
+            direct,
+        };
+        files.sort();
+        files.dedup();
+        (versions, files)
+    }
+
+    /// Direct dependencies, with their locked versions
+    pub fn dependencies(&self) -> Vec<Dependency> {
+        self.direct
+            .iter()
+            .map(|(ecosystem, name)| Dependency {
+                ecosystem: *ecosystem,
+                name: name.clone(),
+                locked: self.get(*ecosystem, name).cloned(),
+            })
+            .collect()
+    }
+
+    /// Locked version of the package `name`
+    ///
+    /// Go lookups name a package, whose module is the longest locked module path it lives under.
+    pub fn get(&self, ecosystem: Ecosystem, name: &str) -> Option<&LockedVersion> {
+        let key = ecosystem.key(name);
+        match ecosystem {
+            Ecosystem::Rust => self.crates.get(&key),
+            Ecosystem::Npm => self.npm.get(&key),
+            Ecosystem::PyPI => self.pypi.get(&key),
+            Ecosystem::Go => self
+                .go
+                .iter()
+                .filter(|(module, _)| {
+                    key.strip_prefix(module.as_str())
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+                })
+                .max_by_key(|(module, _)| module.len())
+                .map(|(_, locked)| locked),
+        }
+    }
+}
+
+/// `[[package]]` entries shared by `Cargo.lock`, `poetry.lock` and `uv.lock`
+#[derive(Deserialize)]
+struct TomlLock {
+    #[serde(default)]
+    package: Vec<TomlLockPackage>,
+}
+
+#[derive(Deserialize)]
+struct TomlLockPackage {
+    name: String,
+    version: Option<String>,
+    /// Registry of the package; absent for Cargo workspace members and path dependencies
+    source: Option<toml::Value>,
+}
+
+/// Versions from `Cargo.lock`, choosing the one the manifests ask for when several are locked
+fn cargo_versions(root: &Path, files: &mut Vec<PathBuf>) -> HashMap<String, LockedVersion> {
+    let lock_path = root.join("Cargo.lock");
+    files.push(lock_path.clone());
+    let Ok(content) = std::fs::read_to_string(&lock_path) else {
+        return HashMap::new();
+    };
+    let lock: TomlLock = match toml::from_str(&content) {
+        Ok(lock) => lock,
+        Err(e) => {
+            tracing::warn!(path = %lock_path.display(), "Ignoring invalid Cargo.lock: {}", e);
+            return HashMap::new();
+        }
+    };
+
+    let mut requirements: HashMap<String, Vec<VersionReq>> = HashMap::new();
+    for (name, req) in manifest_requirements(root, files) {
+        requirements.entry(Ecosystem::Rust.key(&name)).or_default().push(req);
+    }
+    let mut candidates: HashMap<String, Vec<Version>> = HashMap::new();
+    for package in lock.package.into_iter().filter(|p| p.source.is_some()) {
+        if let Some(Ok(version)) = package.version.as_deref().map(Version::parse) {
+            candidates.entry(Ecosystem::Rust.key(&package.name)).or_default().push(version);
+        }
+    }
+
+    candidates
+        .into_iter()
+        .filter_map(|(name, versions)| {
+            let wanted = requirements.get(&name);
+            let matching = versions
+                .iter()
+                .filter(|v| wanted.map_or(true, |reqs| reqs.iter().any(|req| req.matches(v))))
+                .max();
+            let version = matching.or_else(|| versions.iter().max())?;
+            let locked = LockedVersion {
+                version: version.to_string(),
+                source: "Cargo.lock".to_string(),
+            };
+            Some((name, locked))
+        })
+        .collect()
+}
+
+/// Version requirements of the dependencies declared by the root manifest and workspace members
+fn manifest_requirements(root: &Path, files: &mut Vec<PathBuf>) -> Vec<(String, VersionReq)> {
+    let mut requirements = Vec::new();
+    let Some(manifest) = read_manifest(&root.join("Cargo.toml"), files) else {
+        return requirements;
+    };
+
+    let mut manifests = vec![manifest.clone()];
+    let members = manifest
+        .get("workspace")
+        .and_then(|w| w.get("members"))
+        .and_then(toml::Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    for member in members.iter().filter_map(toml::Value::as_str) {
+        let dirs = match member.strip_suffix("/*") {
+            Some(parent) => {
+                files.push(root.join(parent));
+                std::fs::read_dir(root.join(parent))
+                    .map(|entries| entries.flatten().map(|e| e.path()).collect())
+                    .unwrap_or_default()
+            }
+            None => vec![root.join(member)],
+        };
+        manifests.extend(dirs.iter().filter_map(|dir| read_manifest(&dir.join("Cargo.toml"), files)));
+    }
+
+    for manifest in &manifests {
+        let mut tables = vec![manifest];
+        tables.extend(manifest.get("workspace"));
+        if let Some(targets) = manifest.get("target").and_then(toml::Value::as_table) {
+            tables.extend(targets.values());
+        }
+        for table in tables {
+            for section in ["dependencies", "dev-dependencies", "build-dependencies"] {
+                let Some(deps) = table.get(section).and_then(toml::Value::as_table) else {
+                    continue;
+                };
+                for (key, spec) in deps {
+                    let (name, req) = match spec {
+                        toml::Value::String(req) => (key.as_str(), Some(req.as_str())),
+                        toml::Value::Table(spec) => (
+                            spec.get("package").and_then(toml::Value::as_str).unwrap_or(key),
+                            spec.get("version").and_then(toml::Value::as_str),
+                        ),
+                        _ => continue,
+                    };
+                    if let Some(req) = req.and_then(|r| VersionReq::parse(r).ok()) {
+                        requirements.push((name.to_string(), req));
+                    }
+                }
+            }
+        }
+    }
+    requirements
+}
+
+fn read_manifest(path: &Path, files: &mut Vec<PathBuf>) -> Option<toml::Value> {
+    files.push(path.to_path_buf());
+    let content = std::fs::read_to_string(path).ok()?;
+    toml::from_str(&content).ok()
+}
+
+/// Lockfiles of an ecosystem in order of preference, with their parsers
+type Readers = [(&'static str, fn(&str, &Path) -> Vec<(String, String)>)];
+
+/// Versions from the first of `readers`' lockfiles that exists and parses
+fn first_lockfile(root: &Path, files: &mut Vec<PathBuf>, ecosystem: Ecosystem, readers: &Readers) -> HashMap<String, LockedVersion> {
+    for (file_name, parse) in readers {
+        let path = root.join(file_name);
+        files.push(path.clone());
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let locked: HashMap<String, LockedVersion> = parse(&content, root)
+            .into_iter()
+            .map(|(name, version)| {
+                let source = file_name.to_string();
+                (ecosystem.key(&name), LockedVersion { version, source })
+            })
+            .collect();
+        if !locked.is_empty() {
+            return locked;
+        }
+        tracing::warn!(path = %path.display(), "No package versions found in lockfile");
+    }
+    HashMap::new()
+}
+
+/// Versions from the first npm lockfile found
+///
+/// `package.json` is stamped too, as yarn.lock records ranges rather than which one
+/// package.json asks for.
+fn npm_versions(root: &Path, files: &mut Vec<PathBuf>) -> HashMap<String, LockedVersion> {
+    files.push(root.join("package.json"));
+    first_lockfile(root, files, Ecosystem::Npm, &[
+        ("package-lock.json", parse_package_lock),
+        ("pnpm-lock.yaml", parse_pnpm_lock),
+        ("yarn.lock", parse_yarn_lock),
+    ])
+}
+
+/// Dependencies declared in `package.json`
+fn npm_direct(root: &Path, files: &mut Vec<PathBuf>) -> Vec<String> {
+    let path = root.join("package.json");
+    files.push(path.clone());
+    let manifest: Value = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    let mut names: Vec<String> = Vec::new();
+    for section in ["dependencies", "devDependencies", "optionalDependencies"] {
+        for name in manifest[section].as_object().into_iter().flat_map(|deps| deps.keys()) {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+    }
+    names
+}
+
+/// Top-level packages of a `package-lock.json` (lockfile v1 to v3)
+pub(crate) fn parse_package_lock(content: &str, _root: &Path) -> Vec<(String, String)> {
+    let Ok(lock) = serde_json::from_str::<Value>(content) else {
+        return Vec::new();
+    };
+
+    if let Some(packages) = lock["packages"].as_object() {
+        return packages
+            .iter()
+            .filter(|(_, package)| package["link"] != true)
+            .filter_map(|(key, package)| {
+                let name = key.strip_prefix("node_modules/").filter(|name| !name.contains("/node_modules/"))?;
+                let name = package["name"].as_str().unwrap_or(name);
+                Some((name.to_string(), package["version"].as_str()?.to_string()))
+            })
+            .collect();
+    }
+
+    lock["dependencies"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, package)| Some((name.clone(), package["version"].as_str()?.to_string())))
+        .collect()
+}
+
+/// Direct dependencies of the root project in a `pnpm-lock.yaml`
+///
+/// Lockfile v6 and later list them under `importers: { .: { dependencies: ... } }`, with
+/// versions like `1.2.3(peer@1)`; v5 under a top-level `dependencies`, with versions like
+/// `1.2.3_peer@1`.
+pub(crate) fn parse_pnpm_lock(content: &str, _root: &Path) -> Vec<(String, String)> {
+    let is_dependencies = |section: &str| {
+        matches!(section, "dependencies" | "devDependencies" | "optionalDependencies")
+    };
+    let mut versions = Vec::new();
+    let mut stack: Vec<(usize, String)> = Vec::new();
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('-') {
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        let Some((key, value)) = yaml_entry(trimmed) else {
+            continue;
+        };
+        while stack.last().is_some_and(|(i, _)| *i >= indent) {
+            stack.pop();
+        }
+
+        let path: Vec<&str> = stack.iter().map(|(_, k)| k.as_str()).collect();
+        let locked = match (path.as_slice(), &value) {
+            (["importers", ".", section, name], Some(version)) if key == "version" && is_dependencies(section) => {
+                Some((name.to_string(), version))
+            }
+            ([section], Some(version)) if is_dependencies(section) => Some((key.clone(), version)),
+            _ => None,
+        };
+        if let Some((name, version)) = locked {
+            let version = version.split(['(', '_']).next().unwrap_or_default();
+            if !version.is_empty() && !version.contains(':') {
+                versions.push((name, version.to_string()));
+            }
+        }
+        if value.is_none() {
+            stack.push((indent, key));
+        }
+    }
+    versions
+}
+
+/// Split a YAML mapping line into its unquoted key and value (`None` for a nested mapping)
+fn yaml_entry(line: &str) -> Option<(String, Option<String>)> {
+    let unquote = |s: &str| s.trim().trim_matches(|c| c == '\'' || c == '"').to_string();
+    let (key, rest) = match line.chars().next()? {
+        quote @ ('\'' | '"') => {
+            let end = line[1..].find(quote)? + 1;
+            (line[1..end].to_string(), line[end + 1..].strip_prefix(':')?)
+        }
+        _ => {
+            let colon = line.find(": ").or_else(|| line.strip_suffix(':').map(str::len))?;
+            (line[..colon].trim().to_string(), &line[colon + 1..])
+        }
+    };
+    let value = Some(unquote(rest)).filter(|v| !v.is_empty());
+    Some((key, value))
+}
+
+/// The `(name, range)` specs of a `yarn.lock` entry and the version they resolve to
+type YarnBlock = (Vec<(String, String)>, Option<String>);
+
+/// Packages of a `yarn.lock` (classic or Berry), preferring the version resolved for package.json's range
+pub(crate) fn parse_yarn_lock(content: &str, root: &Path) -> Vec<(String, String)> {
+    let manifest: Value = std::fs::read_to_string(root.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    let wanted = |name: &str| {
+        ["dependencies", "devDependencies", "optionalDependencies", "peerDependencies"]
+            .iter()
+            .find_map(|section| manifest[*section][name].as_str())
+            .map(String::from)
+    };
+
+    let mut blocks: Vec<YarnBlock> = Vec::new();
+    for line in content.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) {
+            let specs = line
+                .trim_end_matches(':')
+                .split(',')
+                .filter_map(|spec| {
+                    let spec = spec.trim().trim_matches('"');
+                    let at = spec[1..].find('@')? + 1;
+                    let range = spec[at + 1..].trim_start_matches("npm:");
+                    Some((spec[..at].to_string(), range.to_string()))
+                })
+                .collect();
+            blocks.push((specs, None));
+        } else if let Some(version) = line.trim().strip_prefix("version") {
+            if let Some((_, resolved)) = blocks.last_mut() {
+                *resolved = Some(version.trim_start_matches(':').trim().trim_matches('"').to_string());
+            }
+        }
+    }
+
+    let mut versions: HashMap<String, (bool, String)> = HashMap::new();
+    for (specs, version) in blocks {
+        let (Some(version), Some((name, _))) = (version, specs.first()) else {
+            continue;
+        };
+        let direct = specs.iter().any(|(_, range)| wanted(name).as_deref() == Some(range));
+        let newer = |current: &str| match (Version::parse(&version), Version::parse(current)) {
+            (Ok(new), Ok(current)) => new > current,
+            _ => false,
+        };
+        let replace = match versions.get(name) {
+            Some((current_direct, current)) => match (direct, *current_direct) {
+                (true, false) => true,
+                (false, true) => false,
+                _ => newer(current),
+            },
+            None => true,
+        };
+        if replace {
+            versions.insert(name.clone(), (direct, version));
+        }
+    }
+    versions.into_iter().map(|(name, (_, version))| (name, version)).collect()
+}
+
+fn pypi_versions(root: &Path, files: &mut Vec<PathBuf>) -> HashMap<String, LockedVersion> {
+    first_lockfile(root, files, Ecosystem::PyPI, &[
+        ("poetry.lock", parse_python_lock),
+        ("uv.lock", parse_python_lock),
+        ("requirements.txt", parse_requirements),
+    ])
+}
+
+/// Dependencies declared in `pyproject.toml` (PEP 621 or Poetry), or else in `requirements.txt`
+fn pypi_direct(root: &Path, files: &mut Vec<PathBuf>) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let add = |names: &mut Vec<String>, name: &str| {
+        let name = name.trim();
+        if !name.is_empty() && !names.iter().any(|n| Ecosystem::PyPI.key(n) == Ecosystem::PyPI.key(name)) {
+            names.push(name.to_string());
+        }
+    };
+    let requirement_name = |spec: &str| {
+        spec.split(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '_' | '.')))
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    if let Some(pyproject) = read_manifest(&root.join("pyproject.toml"), files) {
+        let project_deps = pyproject.get("project").and_then(|p| p.get("dependencies")).and_then(toml::Value::as_array);
+        for spec in project_deps.into_iter().flatten().filter_map(toml::Value::as_str) {
+            add(&mut names, &requirement_name(spec));
+        }
+
+        let poetry = pyproject.get("tool").and_then(|t| t.get("poetry"));
+        let groups = poetry
+            .and_then(|p| p.get("group"))
+            .and_then(toml::Value::as_table)
+            .into_iter()
+            .flat_map(|groups| groups.values());
+        for deps in poetry.into_iter().chain(groups).filter_map(|t| t.get("dependencies")?.as_table()) {
+            deps.keys().filter(|name| *name != "python").for_each(|name| add(&mut names, name));
+        }
+    }
+
+    if names.is_empty() {
+        let path = root.join("requirements.txt");
+        files.push(path.clone());
+        let requirements = std::fs::read_to_string(&path).unwrap_or_default();
+        for line in requirements.lines().map(str::trim) {
+            if !line.is_empty() && !line.starts_with('#') && !line.starts_with('-') {
+                add(&mut names, &requirement_name(line));
+            }
+        }
+    }
+    names
+}
+
+/// Packages of a `poetry.lock` or `uv.lock`, skipping the project itself and local paths
+pub(crate) fn parse_python_lock(content: &str, _root: &Path) -> Vec<(String, String)> {
+    let Ok(lock) = toml::from_str::<TomlLock>(content) else {
+        return Vec::new();
+    };
+    let is_local = |source: &toml::Value| {
+        ["editable", "virtual", "directory", "path"].iter().any(|key| source.get(key).is_some())
+            || matches!(source.get("type").and_then(toml::Value::as_str), Some("directory" | "file"))
+    };
+    lock.package
+        .into_iter()
+        .filter(|package| !package.source.as_ref().is_some_and(is_local))
+        .filter_map(|package| Some((package.name, package.version?)))
+        .collect()
+}
+
+/// Pinned (`name==version`) requirements of a `requirements.txt`
+pub(crate) fn parse_requirements(content: &str, _root: &Path) -> Vec<(String, String)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.split(" #").next()?.split(';').next()?.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with('-') {
+                return None;
+            }
+            let (name, version) = line.split_once("===").or_else(|| line.split_once("=="))?;
+            let name = name.split('[').next()?.trim();
+            let version = version.split_whitespace().next()?.trim_end_matches('\\');
+            (!name.is_empty() && !version.contains('*')).then(|| (name.to_string(), version.to_string()))
+        })
+        .collect()
+}
+
+/// Modules required by `go.mod`, with `go.sum` filling in modules it does not list
+fn go_versions(root: &Path, files: &mut Vec<PathBuf>) -> HashMap<String, LockedVersion> {
+    let mut versions = first_lockfile(root, files, Ecosystem::Go, &[("go.mod", parse_go_mod)]);
+    let sum = first_lockfile(root, files, Ecosystem::Go, &[("go.sum", parse_go_sum)]);
+    for (module, locked) in sum {
+        versions.entry(module).or_insert(locked);
+    }
+    versions
+}
+
+/// Modules required directly (not `// indirect`) by `go.mod`
+fn go_direct(root: &Path, files: &mut Vec<PathBuf>) -> Vec<String> {
+    let path = root.join("go.mod");
+    files.push(path.clone());
+    let content = std::fs::read_to_string(&path).unwrap_or_default();
+    go_requires(&content)
+        .into_iter()
+        .filter(|(_, _, indirect)| !indirect)
+        .map(|(module, _, _)| module)
+        .collect()
+}
+
+/// `require` directives of a `go.mod`, single-line or in a block
+pub(crate) fn parse_go_mod(content: &str, _root: &Path) -> Vec<(String, String)> {
+    go_requires(content)
+        .into_iter()
+        .map(|(module, version, _)| (module, version))
+        .collect()
+}
+
+/// Module, version and whether it is marked `// indirect`, for every requirement of a `go.mod`
+fn go_requires(content: &str) -> Vec<(String, String, bool)> {
+    let mut requires = Vec::new();
+    let mut in_require = false;
+    for line in content.lines() {
+        let (line, comment) = line.split_once("//").unwrap_or((line, ""));
+        let line = line.trim();
+        let spec = if in_require {
+            if line == ")" {
+                in_require = false;
+                continue;
+            }
+            line
+        } else if let Some(rest) = line.strip_prefix("require") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_require = true;
+                continue;
+            }
+            rest
+        } else {
+            continue;
+        };
+        let mut parts = spec.split_whitespace();
+        if let (Some(module), Some(version)) = (parts.next(), parts.next()) {
+            requires.push((module.to_string(), version.to_string(), comment.trim() == "indirect"));
+        }
+    }
+    requires
+}
+
+/// The newest version of each module checksummed in a `go.sum`
+pub(crate) fn parse_go_sum(content: &str, _root: &Path) -> Vec<(String, String)> {
+    let semver = |v: &str| Version::parse(v.trim_start_matches('v').trim_end_matches("+incompatible")).ok();
+    let mut newest: HashMap<String, String> = HashMap::new();
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(module), Some(version)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let version = version.trim_end_matches("/go.mod");
+        let newer = match newest.get(module) {
+            Some(current) => semver(version) > semver(current),
+            None => true,
+        };
+        if newer {
+            newest.insert(module.to_string(), version.to_string());
+        }
+    }
+    newest.into_iter().collect()
+}
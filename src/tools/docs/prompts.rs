@@ -0,0 +1,142 @@
+use mcp_core::prompt::{Prompt, PromptArgument};
+use serde_json::{Map, Value};
+
+use super::project::Ecosystem;
+
+/// Longest documentation excerpt embedded in a prompt, in characters
+pub const MAX_DOC_CHARS: usize = 24_000;
+
+/// Most packages `compare_packages` fetches documentation for
+pub const MAX_COMPARED: usize = 5;
+
+/// A server-provided prompt and the instructions it starts from
+pub struct PromptSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// `(name, description, required)`
+    pub arguments: &'static [(&'static str, &'static str, bool)],
+    /// Instructions with `{argument}` placeholders for the required arguments
+    pub template: &'static str,
+}
+
+const ECOSYSTEM_ARGUMENT: (&str, &str, bool) = (
+    "ecosystem",
+    "Package ecosystem: rust, npm, pypi or go (default: rust)",
+    false,
+);
+
+pub const PROMPTS: &[PromptSpec] = &[
+    PromptSpec {
+        name: "explain_crate",
+        description: "Explain what a Rust crate does and how to use it, from its documentation and features",
+        arguments: &[
+            ("crate_name", "The name of the crate", true),
+            ("version", "The version of the crate (optional, defaults to the locked or latest version)", false),
+        ],
+        template: "Explain what the Rust crate `{crate_name}` is for. Summarize its main types, traits and \
+            functions, the Cargo features worth knowing about, and show a short example of typical usage. \
+            Base the explanation on the documentation below.",
+    },
+    PromptSpec {
+        name: "migrate_versions",
+        description: "Plan a migration of a dependency from one version to another, from the documentation of both",
+        arguments: &[
+            ("package", "The package to migrate", true),
+            ("from_version", "The version currently in use", true),
+            ("to_version", "The version to migrate to", true),
+            ECOSYSTEM_ARGUMENT,
+        ],
+        template: "Help migrate code using `{package}` from version {from_version} to {to_version}. List the \
+            breaking changes, renamed or removed APIs and newly recommended patterns, and show before/after \
+            code for the most common changes. Use the documentation of both versions below.",
+    },
+    PromptSpec {
+        name: "compare_packages",
+        description: "Compare several packages for the same job, from their documentation",
+        arguments: &[
+            ("packages", "Comma-separated package names (at most 5)", true),
+            ECOSYSTEM_ARGUMENT,
+        ],
+        template: "Compare the packages {packages}: what each one is designed for, its API style, maturity, \
+            features and trade-offs. Finish with a recommendation of when to choose each one. Use the \
+            documentation below.",
+    },
+    PromptSpec {
+        name: "find_api",
+        description: "Find the API in a dependency that does a given task",
+        arguments: &[
+            ("package", "The package to search", true),
+            ("task", "What the code should do", true),
+            ("version", "The version of the package (optional, defaults to the locked or latest version)", false),
+            ECOSYSTEM_ARGUMENT,
+        ],
+        template: "Find the API in `{package}` that does the following: {task}\n\nName the exact functions, \
+            types or methods to use, show how to call them, and mention any feature flags or setup they need. \
+            If nothing in the package does this, say so. Use the documentation below.",
+    },
+];
+
+/// The prompt called `name`
+pub fn find(name: &str) -> Option<&'static PromptSpec> {
+    PROMPTS.iter().find(|spec| spec.name == name)
+}
+
+impl PromptSpec {
+    pub fn to_prompt(&self) -> Prompt {
+        let arguments = self
+            .arguments
+            .iter()
+            .map(|(name, description, required)| PromptArgument {
+                name: name.to_string(),
+                description: Some(description.to_string()),
+                required: Some(*required),
+            })
+            .collect();
+        Prompt::new(self.name, Some(self.description), Some(arguments))
+    }
+
+    /// The template with its placeholders filled in, failing if a required argument is missing
+    pub fn instructions(&self, arguments: &Map<String, Value>) -> Result<String, String> {
+        let mut text = self.template.to_string();
+        for (name, _, required) in self.arguments {
+            match argument(arguments, name) {
+                Some(value) => text = text.replace(&format!("{{{}}}", name), value),
+                None if *required => return Err(format!("Missing required argument: {}", name)),
+                None => {}
+            }
+        }
+        Ok(text)
+    }
+}
+
+/// A non-empty string argument
+pub fn argument<'a>(arguments: &'a Map<String, Value>, name: &str) -> Option<&'a str> {
+    arguments
+        .get(name)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+/// Ecosystem named by a prompt argument, accepting common aliases
+pub fn parse_ecosystem(name: Option<&str>) -> Result<Ecosystem, String> {
+    let Some(name) = name else {
+        return Ok(Ecosystem::Rust);
+    };
+    let ecosystem = match name.to_lowercase().as_str() {
+        "rust" | "crates" | "cargo" => Ecosystem::Rust,
+        "npm" | "node" | "javascript" | "typescript" => Ecosystem::Npm,
+        "pypi" | "python" | "pip" => Ecosystem::PyPI,
+        "go" | "golang" => Ecosystem::Go,
+        _ => return Err(format!("Unknown ecosystem: {}", name)),
+    };
+    Ok(ecosystem)
+}
+
+/// Cut documentation down to `max_chars`, saying so if anything was dropped
+pub fn truncate(doc: &str, max_chars: usize) -> String {
+    match doc.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}\n\n_(documentation truncated)_", &doc[..end]),
+        None => doc.to_string(),
+    }
+}
@@ -1,16 +1,27 @@
-use reqwest::Client;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use reqwest::{Client, StatusCode};
 use serde_json::Value;
 use html2md::parse_html;
 use anyhow::Result;
-use std::sync::Arc;
-use tokio::sync::Mutex;
 use mcp_core::ToolError;
 
+use super::auth::{RegistryAuth, RequestAuthExt};
+use super::cache::{DocCache, Freshness};
+use super::metrics::TimedSend;
+use super::progress::report;
+use super::registry::{base_url, RegistryUrls};
+
 /// PyPI Client for fetching Python package documentation from PyPI
 #[derive(Clone)]
 pub struct PyPIClient {
     client: Client,
-    cache: Arc<Mutex<std::collections::HashMap<String, String>>>,
+    cache: DocCache,
+    base_url: String,
+    auth: Option<RegistryAuth>,
+    /// Set once the index answers the search page with 404, as simple and devpi indexes do
+    no_search: Arc<AtomicBool>,
 }
 
 impl Default for PyPIClient {
@@ -21,22 +32,42 @@ impl Default for PyPIClient {
 
 impl PyPIClient {
     pub fn new() -> Self {
+        Self::with_cache(DocCache::new())
+    }
+
+    /// Create a client that stores its lookups in `cache`
+    pub fn with_cache(cache: DocCache) -> Self {
         Self {
             client: Client::new(),
-            cache: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            cache,
+            base_url: RegistryUrls::default().pypi,
+            auth: None,
+            no_search: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Use `url` as the PyPI base URL instead of the public one
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        self.base_url = base_url(url);
+        self.cache = self.cache.for_registry(url, &RegistryUrls::default().pypi);
+        self.no_search = Arc::new(AtomicBool::new(false));
+        self
+    }
+
+    /// Send `auth` with every registry request
+    pub fn with_auth(mut self, auth: Option<RegistryAuth>) -> Self {
+        self.auth = auth;
+        self
+    }
+
     /// Get cache entry
     pub async fn get_cache(&self, key: &str) -> Option<String> {
-        let cache = self.cache.lock().await;
-        cache.get(key).cloned()
+        self.cache.get(key).await
     }
 
     /// Set cache entry
     pub async fn set_cache(&self, key: String, value: String) {
-        let mut cache = self.cache.lock().await;
-        cache.insert(key, value);
+        self.cache.set(key, value).await;
     }
 
     /// Lookup Python package metadata
@@ -55,15 +86,17 @@ impl PyPIClient {
 
         // Construct the URL for package info
         let url = match &version {
-            Some(ver) => format!("https://pypi.org/pypi/{}/{}/json", package_name, ver),
-            None => format!("https://pypi.org/pypi/{}/json", package_name),
+            Some(ver) => format!("{}/pypi/{}/{}/json", self.base_url, package_name, ver),
+            None => format!("{}/pypi/{}/json", self.base_url, package_name),
         };
 
         // Fetch package information
+        report(format!("Fetching `{}` from PyPI", package_name));
         let response = self.client
             .get(&url)
             .header("User-Agent", "CodeNav-MCP/0.1.0")
-            .send()
+            .registry_auth(self.auth.as_ref())
+            .send_timed()
             .await
             .map_err(|e| ToolError::ExecutionError(format!("Failed to fetch PyPI package: {}", e)))?;
 
@@ -155,6 +188,7 @@ impl PyPIClient {
                     .map_or(false, |t| t.contains("text/html") || t.contains("html"))
                 {
                     // Convert HTML to markdown
+                    report("Converting README from HTML to Markdown");
                     markdown.push_str(&parse_html(description));
                 } else {
                     // Plain text, preserve as is
@@ -189,7 +223,7 @@ impl PyPIClient {
         }
         
         // Cache the result
-        self.set_cache(cache_key, markdown.clone()).await;
+        self.cache.set_with(cache_key, markdown.clone(), Freshness::for_version(&version)).await;
         
         Ok(markdown)
     }
@@ -205,13 +239,14 @@ impl PyPIClient {
         }
         
         // Search PyPI using their JSON API
-        let url = format!("https://pypi.org/search/?q={}&format=json", query);
+        let url = format!("{}/search/?q={}&format=json", self.base_url, query);
         
         // Fetch search results
         let response = self.client
             .get(&url)
             .header("User-Agent", "CodeNav-MCP/0.1.0")
-            .send()
+            .registry_auth(self.auth.as_ref())
+            .send_timed()
             .await
             .map_err(|e| ToolError::ExecutionError(format!("Failed to search PyPI packages: {}", e)))?;
             
@@ -241,7 +276,7 @@ impl PyPIClient {
                         markdown.push_str(&format!("## {}. {} (v{})\n\n", i + 1, name, version));
                         markdown.push_str(&format!("{}\n\n", description));
                         
-                        let pkg_url = format!("https://pypi.org/project/{}", name);
+                        let pkg_url = format!("{}/project/{}", self.base_url, name);
                         markdown.push_str(&format!("- [PyPI Page]({})\n\n", pkg_url));
                     }
                 } else {
@@ -260,18 +295,59 @@ impl PyPIClient {
         }
     }
     
+    /// Names of packages matching `prefix`, for argument completion, from the HTML search page
+    ///
+    /// Indexes without a search page yield no names, and are not asked again.
+    pub async fn package_names(&self, prefix: &str) -> Result<Vec<String>, ToolError> {
+        if self.no_search.load(Ordering::Relaxed) {
+            return Ok(Vec::new());
+        }
+        let url = format!("{}/search/", self.base_url);
+        let response = self.client
+            .get(&url)
+            .query(&[("q", prefix)])
+            .header("User-Agent", "CodeNav-MCP/0.1.0")
+            .registry_auth(self.auth.as_ref())
+            .send_timed()
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to search PyPI packages: {}", e)))?;
+
+        if matches!(response.status(), StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED) {
+            self.no_search.store(true, Ordering::Relaxed);
+            return Ok(Vec::new());
+        }
+        if !response.status().is_success() {
+            return Err(ToolError::ExecutionError(format!(
+                "Failed to search PyPI packages. Status: {}",
+                response.status()
+            )));
+        }
+
+        let html_body = response.text().await
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read response body: {}", e)))?;
+        let names = html_body
+            .split("class=\"package-snippet__name\">")
+            .skip(1)
+            .filter_map(|rest| rest.split_once('<').map(|(name, _)| name.trim()))
+            .filter(|name| !name.is_empty())
+            .map(String::from)
+            .collect();
+        Ok(names)
+    }
+
     /// Fallback method to scrape search results from the HTML page
     async fn scrape_search_results(&self, query: String, limit: Option<u32>) -> Result<String, ToolError> {
         let limit = limit.unwrap_or(10).min(100);
         let cache_key = format!("pypi:search:{}:{}", query, limit);
         
         // Fetch the HTML search page
-        let url = format!("https://pypi.org/search/?q={}", query);
+        let url = format!("{}/search/?q={}", self.base_url, query);
         
         let response = self.client
             .get(&url)
             .header("User-Agent", "CodeNav-MCP/0.1.0")
-            .send()
+            .registry_auth(self.auth.as_ref())
+            .send_timed()
             .await
             .map_err(|e| ToolError::ExecutionError(format!("Failed to search PyPI packages: {}", e)))?;
             
@@ -0,0 +1,154 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use super::project::{Dependency, Ecosystem};
+
+/// Scheme of the documentation resources served by the router
+pub const SCHEME: &str = "docs://";
+
+/// A documentation resource URI
+///
+/// Supported forms:
+/// - `docs://rust/{crate}@{version}` and `docs://rust/{crate}/{version}/{item_path}`
+/// - `docs://npm/{package}@{version}` (scoped packages included)
+/// - `docs://pypi/{package}@{version}`
+/// - `docs://go/{module}@{version}#{symbol}`
+///
+/// The `@{version}` part is optional everywhere; `latest` stands for no version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocUri {
+    pub ecosystem: Ecosystem,
+    pub package: String,
+    pub version: Option<String>,
+    /// Rust item path or Go symbol inside the package
+    pub item: Option<String>,
+}
+
+impl DocUri {
+    /// Parse a `docs://{ecosystem}/{package}[@{version}]` URI
+    ///
+    /// Scoped npm packages start with `@`, so only a later `@` separates the version.
+    pub fn parse(uri: &str) -> Option<Self> {
+        let rest = uri.strip_prefix(SCHEME)?;
+        let (scheme, spec) = rest.split_once('/')?;
+        let ecosystem = Ecosystem::from_scheme(scheme)?;
+
+        let (spec, item) = match ecosystem {
+            Ecosystem::Go => match spec.split_once('#') {
+                Some((module, symbol)) => (module, Some(symbol)),
+                None => (spec, None),
+            },
+            _ => (spec, None),
+        };
+
+        let (package, version, item) = match (ecosystem, spec.split_once('/')) {
+            (Ecosystem::Rust, Some((krate, rest))) => {
+                let (version, item_path) = rest.split_once('/').unwrap_or((rest, ""));
+                (krate, Some(version), Some(item_path))
+            }
+            _ => match spec.rfind('@').filter(|&at| at > 0) {
+                Some(at) => (&spec[..at], Some(&spec[at + 1..]), item),
+                None => (spec, None, item),
+            },
+        };
+
+        let package = package.trim_end_matches('/');
+        if package.is_empty() {
+            return None;
+        }
+        Some(Self {
+            ecosystem,
+            package: package.to_string(),
+            version: version.filter(|v| !v.is_empty() && *v != "latest").map(String::from),
+            item: item.filter(|i| !i.is_empty()).map(String::from),
+        })
+    }
+
+    /// URI of a project dependency, pinned to its locked version when there is one
+    pub fn for_dependency(dependency: &Dependency) -> Self {
+        Self {
+            ecosystem: dependency.ecosystem,
+            package: dependency.name.clone(),
+            version: dependency.locked.as_ref().map(|locked| locked.version.clone()),
+            item: None,
+        }
+    }
+}
+
+impl fmt::Display for DocUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}/{}", SCHEME, self.ecosystem.scheme(), self.package)?;
+        match (self.ecosystem, &self.item) {
+            (Ecosystem::Rust, Some(item)) => {
+                write!(f, "/{}/{}", self.version.as_deref().unwrap_or("latest"), item)
+            }
+            (_, item) => {
+                if let Some(version) = &self.version {
+                    write!(f, "@{}", version)?;
+                }
+                match item {
+                    Some(item) => write!(f, "#{}", item),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// An RFC 6570 URI template clients can fill in to read any documentation page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceTemplate {
+    pub uri_template: String,
+    pub name: String,
+    pub description: String,
+    pub mime_type: String,
+}
+
+impl ResourceTemplate {
+    fn new(uri_template: &str, name: &str, description: &str) -> Self {
+        Self {
+            uri_template: uri_template.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            mime_type: "text".to_string(),
+        }
+    }
+}
+
+/// Templates of every kind of [`DocUri`]
+pub fn templates() -> Vec<ResourceTemplate> {
+    vec![
+        ResourceTemplate::new(
+            "docs://rust/{crate}@{version}",
+            "Rust crate",
+            "Documentation of a Rust crate from docs.rs",
+        ),
+        ResourceTemplate::new(
+            "docs://rust/{crate}/{version}/{item_path}",
+            "Rust item",
+            "Documentation of an item in a Rust crate, e.g. docs://rust/tokio/latest/sync::Mutex",
+        ),
+        ResourceTemplate::new(
+            "docs://npm/{package}@{version}",
+            "npm package",
+            "Documentation of an npm package",
+        ),
+        ResourceTemplate::new(
+            "docs://pypi/{package}",
+            "PyPI package",
+            "Documentation of a Python package from PyPI",
+        ),
+        ResourceTemplate::new(
+            "docs://go/{module}@{version}",
+            "Go package",
+            "Documentation of a Go package from pkg.go.dev",
+        ),
+        ResourceTemplate::new(
+            "docs://go/{module}#{symbol}",
+            "Go symbol",
+            "Documentation of a symbol in a Go package, e.g. docs://go/net/http#Client",
+        ),
+    ]
+}
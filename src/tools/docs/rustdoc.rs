@@ -0,0 +1,1461 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use flate2::read::GzDecoder;
+use reqwest::{Client, StatusCode};
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+use super::auth::{RegistryAuth, RequestAuthExt};
+use super::cache::CachePolicy;
+use super::crate_items::CrateItem;
+use super::features::doc_cfg_features;
+use super::metrics::TimedSend;
+use super::progress::report;
+use super::registry::{base_url, RegistryUrls};
+
+/// Maximum number of parsed rustdoc JSON documents kept in memory
+const MAX_LOADED_CRATES: usize = 8;
+
+/// Maximum JSON bytes of the parsed documents kept in memory
+const MAX_LOADED_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Default size limit of a single rustdoc JSON document, compressed or not
+const MAX_JSON_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Maximum number of implementors listed for a trait
+const MAX_IMPLEMENTORS: usize = 50;
+
+static NULL: Value = Value::Null;
+
+/// Client for structured Rust documentation built from rustdoc JSON
+///
+/// JSON produced locally by `cargo doc --output-format json` is preferred; otherwise
+/// it is downloaded from docs.rs, which serves it for recent builds.
+#[derive(Clone)]
+pub struct RustdocClient {
+    client: Client,
+    base_url: String,
+    auth: Option<RegistryAuth>,
+    local_dirs: Vec<PathBuf>,
+    remote: bool,
+    latest_ttl: Option<Duration>,
+    max_json_bytes: u64,
+    loaded: Arc<Mutex<Loaded>>,
+}
+
+/// Parsed crates by `name@version`; `None` records that no JSON exists for that build
+#[derive(Default)]
+struct Loaded {
+    crates: HashMap<String, Option<Arc<RustdocCrate>>>,
+    order: VecDeque<String>,
+    /// JSON bytes of the documents in `crates`
+    bytes: u64,
+    /// Key in `crates` that `name@latest` resolved to, until it expires
+    latest: HashMap<String, (String, Option<Instant>)>,
+    /// Loads in progress by key, so concurrent lookups of a crate wait for a single download
+    loading: HashMap<String, Arc<Mutex<()>>>,
+}
+
+impl Loaded {
+    /// The document loaded for `key`, or `None` when it has to be loaded (again)
+    fn get(&self, key: &str) -> Option<Option<Arc<RustdocCrate>>> {
+        match self.latest.get(key) {
+            Some((_, Some(expires_at))) if *expires_at <= Instant::now() => None,
+            Some((resolved, _)) => self.crates.get(resolved).cloned(),
+            None if key.ends_with("@latest") => None,
+            None => self.crates.get(key).cloned(),
+        }
+    }
+
+    /// Keep `krate` under `key`, evicting the oldest documents past the count and byte limits
+    fn insert(&mut self, key: String, krate: Option<Arc<RustdocCrate>>) {
+        let size = krate.as_ref().map_or(0, |krate| krate.size);
+        if size > MAX_LOADED_BYTES {
+            return;
+        }
+        self.bytes += size;
+        match self.crates.insert(key.clone(), krate) {
+            Some(previous) => self.bytes -= previous.map_or(0, |krate| krate.size),
+            None => self.order.push_back(key),
+        }
+        while self.order.len() > MAX_LOADED_CRATES || self.bytes > MAX_LOADED_BYTES {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            if let Some(evicted) = self.crates.remove(&oldest) {
+                self.bytes -= evicted.map_or(0, |krate| krate.size);
+            }
+        }
+    }
+}
+
+impl Default for RustdocClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RustdocClient {
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+            base_url: RegistryUrls::default().docs_rs,
+            auth: None,
+            local_dirs: Vec::new(),
+            remote: true,
+            latest_ttl: CachePolicy::default().latest_ttl,
+            max_json_bytes: MAX_JSON_BYTES,
+            loaded: Arc::new(Mutex::new(Loaded::default())),
+        }
+    }
+
+    /// Download rustdoc JSON from another docs.rs instance
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        self.base_url = base_url(url);
+        self
+    }
+
+    /// Credentials sent with docs.rs requests
+    pub fn with_auth(mut self, auth: Option<RegistryAuth>) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Directories searched for `<crate>.json` before docs.rs (for example `target/doc`)
+    pub fn with_local_dirs(mut self, dirs: Vec<PathBuf>) -> Self {
+        self.local_dirs = dirs;
+        self
+    }
+
+    /// Whether rustdoc JSON may be downloaded from docs.rs
+    pub fn with_remote(mut self, remote: bool) -> Self {
+        self.remote = remote;
+        self
+    }
+
+    /// How long the document loaded for an unversioned lookup is reused, as
+    /// [`CachePolicy::latest_ttl`]; `None` keeps it until it is evicted
+    pub fn with_latest_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.latest_ttl = ttl;
+        self
+    }
+
+    /// Skip rustdoc JSON larger than `bytes`, before or after decompressing it
+    pub fn with_max_json_bytes(mut self, bytes: u64) -> Self {
+        self.max_json_bytes = bytes;
+        self
+    }
+
+    /// Render an item of `crate_name`, or `None` if no rustdoc JSON is available or the item is not in it
+    pub async fn lookup_item(&self, crate_name: &str, item_path: &str, version: Option<&str>) -> Option<String> {
+        let krate = self.load(crate_name, version).await?;
+        let segments: Vec<&str> = item_path.split("::").filter(|s| !s.is_empty()).collect();
+        let resolved = krate.resolve(&segments)?;
+
+        let crate_ident = crate_name.replace('-', "_");
+        let path = if segments.is_empty() {
+            crate_ident
+        } else {
+            format!("{}::{}", crate_ident, segments.join("::"))
+        };
+        Some(krate.render(&resolved, &path))
+    }
+
+    /// Load and parse the rustdoc JSON of a crate, reusing previously parsed documents
+    ///
+    /// Documents are kept by the version they were built for. An unversioned lookup reuses
+    /// the version it resolved to for [`RustdocClient::with_latest_ttl`], and only once a
+    /// document was found. Concurrent lookups of the same crate share a single download.
+    pub async fn load(&self, crate_name: &str, version: Option<&str>) -> Option<Arc<RustdocCrate>> {
+        self.load_from(crate_name, version, self.remote).await
+    }
+
+    /// Like [`RustdocClient::load`], but never downloads anything: only documents already
+    /// loaded or found in the local directories are returned
+    pub async fn load_offline(&self, crate_name: &str, version: Option<&str>) -> Option<Arc<RustdocCrate>> {
+        self.load_from(crate_name, version, false).await
+    }
+
+    async fn load_from(&self, crate_name: &str, version: Option<&str>, remote: bool) -> Option<Arc<RustdocCrate>> {
+        let version = version.filter(|v| !v.is_empty()).unwrap_or("latest");
+        let key = format!("{}@{}", crate_name, version);
+        let gate = {
+            let mut loaded = self.loaded.lock().await;
+            if let Some(krate) = loaded.get(&key) {
+                return krate;
+            }
+            loaded.loading.entry(key.clone()).or_default().clone()
+        };
+        let _loading = gate.lock().await;
+        if let Some(krate) = self.loaded.lock().await.get(&key) {
+            return krate;
+        }
+
+        let krate = match self.load_local(crate_name, version).await {
+            Some(krate) => Ok(Some(Arc::new(krate))),
+            None if remote => self.load_remote(crate_name, version).await.map(|krate| krate.map(Arc::new)),
+            None => Ok(None),
+        };
+
+        let mut loaded = self.loaded.lock().await;
+        loaded.loading.remove(&key);
+        let krate = match krate {
+            Ok(krate) => krate,
+            Err(e) => {
+                tracing::debug!("Failed to fetch rustdoc JSON for {}: {}", key, e);
+                return None;
+            }
+        };
+        if version != "latest" {
+            if krate.is_some() || remote == self.remote {
+                loaded.insert(key, krate.clone());
+            }
+        } else if let Some(found) = &krate {
+            let resolved = format!("{}@{}", crate_name, found.version().unwrap_or(version));
+            let expires_at = self.latest_ttl.map(|ttl| Instant::now() + ttl);
+            loaded.insert(resolved.clone(), krate.clone());
+            loaded.latest.insert(key, (resolved, expires_at));
+        }
+        krate
+    }
+
+    async fn load_local(&self, crate_name: &str, version: &str) -> Option<RustdocCrate> {
+        let file_name = format!("{}.json", crate_name.replace('-', "_"));
+        for dir in &self.local_dirs {
+            let path = dir.join(&file_name);
+            let Ok(metadata) = tokio::fs::metadata(&path).await else {
+                continue;
+            };
+            if metadata.len() > self.max_json_bytes {
+                tracing::warn!(path = %path.display(), "Ignoring rustdoc JSON larger than the size limit");
+                continue;
+            }
+            let Ok(bytes) = tokio::fs::read(&path).await else {
+                continue;
+            };
+            match parse(bytes, self.max_json_bytes).await {
+                Some(krate) if version == "latest" || krate.version() == Some(version) => return Some(krate),
+                Some(_) => continue,
+                None => tracing::warn!(path = %path.display(), "Ignoring invalid rustdoc JSON"),
+            }
+        }
+        None
+    }
+
+    /// Download the rustdoc JSON of a crate version from docs.rs
+    ///
+    /// docs.rs answers 404 for crates built before rustdoc JSON was published, which yields `None`.
+    async fn load_remote(&self, crate_name: &str, version: &str) -> Result<Option<RustdocCrate>, String> {
+        let url = format!("{}/crate/{}/{}/json.gz", self.base_url, crate_name, version);
+        report(format!("Downloading rustdoc JSON of `{}` {}", crate_name, version));
+        let mut response = self
+            .client
+            .get(&url)
+            .header("User-Agent", "CodeNav/0.1.0 (https://github.com/HikaruEgashira/codenav-mcp)")
+            .registry_auth(self.auth.as_ref())
+            .send_timed()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(format!("Status code: {}", response.status()));
+        }
+
+        let too_large = || format!("rustdoc JSON exceeds {} bytes", self.max_json_bytes);
+        if response.content_length().is_some_and(|len| len > self.max_json_bytes) {
+            return Err(too_large());
+        }
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+            if (bytes.len() + chunk.len()) as u64 > self.max_json_bytes {
+                return Err(too_large());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        report("Parsing rustdoc JSON");
+        Ok(parse(bytes, self.max_json_bytes).await)
+    }
+}
+
+/// Decompress (if gzipped) and parse rustdoc JSON off the async runtime
+///
+/// Documents that decompress to more than `max_bytes` are rejected.
+async fn parse(bytes: Vec<u8>, max_bytes: u64) -> Option<RustdocCrate> {
+    tokio::task::spawn_blocking(move || {
+        let (json, size): (Value, usize) = if bytes.starts_with(&[0x1f, 0x8b]) {
+            let mut decoded = Vec::new();
+            GzDecoder::new(bytes.as_slice())
+                .take(max_bytes.saturating_add(1))
+                .read_to_end(&mut decoded)
+                .ok()?;
+            if decoded.len() as u64 > max_bytes {
+                tracing::debug!("Ignoring rustdoc JSON that decompresses to more than {} bytes", max_bytes);
+                return None;
+            }
+            (serde_json::from_slice(&decoded).ok()?, decoded.len())
+        } else {
+            (serde_json::from_slice(&bytes).ok()?, bytes.len())
+        };
+        let mut krate = RustdocCrate::from_json(json)?;
+        krate.size = size as u64;
+        Some(krate)
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// An item found by [`RustdocCrate::resolve`]
+#[derive(Clone, Copy)]
+pub struct Resolved<'a> {
+    pub item: &'a Value,
+    /// Type or trait owning a method, associated item, variant or field
+    pub parent: Option<&'a Value>,
+    /// Impl block a method or associated item was found in
+    pub impl_block: Option<&'a Value>,
+}
+
+impl<'a> Resolved<'a> {
+    fn item(item: &'a Value) -> Self {
+        Self {
+            item,
+            parent: None,
+            impl_block: None,
+        }
+    }
+}
+
+/// A crate's rustdoc JSON document
+pub struct RustdocCrate {
+    json: Value,
+    /// Length of the JSON text it was parsed from
+    size: u64,
+}
+
+impl RustdocCrate {
+    /// Wrap a rustdoc JSON document, rejecting anything that does not look like one
+    pub fn from_json(json: Value) -> Option<Self> {
+        json.get("index")?.as_object()?;
+        let krate = Self { json, size: 0 };
+        kind(krate.item(&krate.json["root"])?)?;
+        Some(krate)
+    }
+
+    /// Version recorded by rustdoc, if any
+    pub fn version(&self) -> Option<&str> {
+        self.json["crate_version"].as_str()
+    }
+
+    fn item(&self, id: &Value) -> Option<&Value> {
+        self.json["index"].get(id_key(id)?)
+    }
+
+    fn items<'a>(&'a self, ids: &'a Value) -> impl Iterator<Item = &'a Value> + 'a {
+        arr(ids).filter_map(|id| self.item(id))
+    }
+
+    /// Resolve a path relative to the crate root, following re-exports
+    ///
+    /// Segments after a type or trait name resolve to its methods, associated
+    /// items, variants and fields (`mpsc::Sender::send`, `Option::Some`).
+    pub fn resolve(&self, path: &[&str]) -> Option<Resolved<'_>> {
+        let mut resolved = Resolved::item(self.item(&self.json["root"])?);
+        for (i, name) in path.iter().enumerate() {
+            if kind_name(resolved.item) != Some("module") {
+                resolved = self.member(resolved.item, name)?;
+                continue;
+            }
+
+            let candidates = self.module_children(resolved.item, name, 0);
+            let preferred = if i + 1 == path.len() {
+                candidates.iter().find(|item| kind_name(item) != Some("module"))
+            } else {
+                candidates.iter().find(|item| kind_name(item) == Some("module"))
+            };
+            resolved = match preferred.or(candidates.first()) {
+                Some(item) => Resolved::item(item),
+                None => Resolved::item(self.by_canonical_path(&path[..=i])?),
+            };
+        }
+        Some(resolved)
+    }
+
+    /// A member of a type or trait: variant, field, trait item, or item of one of its impls
+    ///
+    /// Inherent impls take precedence over trait impls, as in method resolution, and
+    /// provided trait methods the impl does not override are found on the trait itself.
+    fn member<'a>(&'a self, owner: &'a Value, name: &str) -> Option<Resolved<'a>> {
+        let (owner_kind, inner) = kind(owner)?;
+        let found = |item: &'a Value| Resolved {
+            item,
+            parent: Some(owner),
+            impl_block: None,
+        };
+
+        let direct = match owner_kind {
+            "enum" => self.named(&inner["variants"], name),
+            "struct" | "union" => match single(&inner["kind"]) {
+                Some(("plain", plain)) => self.named(&plain["fields"], name),
+                Some(("tuple", fields)) => self.named(fields, name),
+                _ => self.named(&inner["fields"], name),
+            },
+            "variant" => match single(&inner["kind"]) {
+                Some(("struct", fields)) => self.named(&fields["fields"], name),
+                Some(("tuple", fields)) => self.named(fields, name),
+                _ => None,
+            },
+            "trait" => return self.named(&inner["items"], name).map(found),
+            _ => None,
+        };
+        if let Some(item) = direct {
+            return Some(found(item));
+        }
+
+        let impls: Vec<&Value> = self
+            .items(&inner["impls"])
+            .filter_map(|item| match kind(item) {
+                Some(("impl", imp)) => Some(imp),
+                _ => None,
+            })
+            .filter(|imp| !flag(imp, &["is_synthetic", "synthetic"]))
+            .collect();
+        let (inherent, traits): (Vec<&Value>, Vec<&Value>) =
+            impls.into_iter().partition(|imp| imp["trait"].is_null());
+
+        for imp in inherent.iter().chain(&traits) {
+            if let Some(item) = self.named(&imp["items"], name) {
+                return Some(Resolved {
+                    impl_block: Some(imp),
+                    ..found(item)
+                });
+            }
+        }
+
+        traits.into_iter().find_map(|imp| {
+            if !arr(&imp["provided_trait_methods"]).any(|m| m == name) {
+                return None;
+            }
+            let trait_item = self.item(&imp["trait"]["id"])?;
+            let item = self.named(&kind(trait_item)?.1["items"], name)?;
+            Some(Resolved {
+                impl_block: Some(imp),
+                ..found(item)
+            })
+        })
+    }
+
+    fn named<'a>(&'a self, ids: &'a Value, name: &str) -> Option<&'a Value> {
+        self.items(ids).find(|item| item["name"].as_str() == Some(name))
+    }
+
+    /// Items named `name` in a module, including `pub use` and glob re-exports
+    fn module_children<'a>(&'a self, module: &'a Value, name: &str, depth: usize) -> Vec<&'a Value> {
+        let mut found = Vec::new();
+        let Some(("module", module)) = kind(module) else {
+            return found;
+        };
+        if depth > 8 {
+            return found;
+        }
+
+        for child in self.items(&module["items"]) {
+            match kind(child) {
+                Some(("use", import)) | Some(("import", import)) => {
+                    let target = import.get("id").and_then(|id| self.item(id));
+                    if flag(import, &["is_glob", "glob"]) {
+                        if let Some(target) = target {
+                            found.extend(self.module_children(target, name, depth + 1));
+                        }
+                    } else if import["name"].as_str() == Some(name) {
+                        found.extend(target);
+                    }
+                }
+                _ if child["name"].as_str() == Some(name) => found.push(child),
+                _ => {}
+            }
+        }
+        found
+    }
+
+    /// Every public item reachable from the crate root, under the paths it is exported at
+    pub fn inventory(&self) -> Vec<CrateItem> {
+        let mut items = Vec::new();
+        self.walk_items(&mut |path, kind, _| {
+            items.push(CrateItem {
+                path: path.to_string(),
+                kind: kind.to_string(),
+            })
+        });
+        items.sort();
+        items.dedup();
+        items
+    }
+
+    /// Paths of the items marked `#[doc(cfg(feature = "..."))]`, by feature
+    pub fn feature_gates(&self) -> BTreeMap<String, Vec<String>> {
+        let mut gates: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        self.walk_items(&mut |path, _, item| {
+            for attr in arr(&item["attrs"]) {
+                for feature in doc_cfg_features(&attr_text(attr)) {
+                    gates.entry(feature).or_default().push(path.to_string());
+                }
+            }
+        });
+        for paths in gates.values_mut() {
+            paths.sort();
+            paths.dedup();
+        }
+        gates
+    }
+
+    /// Call `f` with the path, page kind and item of everything reachable from the crate root
+    fn walk_items(&self, f: &mut dyn FnMut(&str, &'static str, &Value)) {
+        if let Some(root) = self.item(&self.json["root"]) {
+            self.walk_module(root, "", f, 0);
+        }
+    }
+
+    fn walk_module(&self, module: &Value, prefix: &str, f: &mut dyn FnMut(&str, &'static str, &Value), depth: usize) {
+        let Some(("module", module)) = kind(module) else {
+            return;
+        };
+        if depth > 16 {
+            return;
+        }
+
+        for child in self.items(&module["items"]) {
+            let (item, name) = match kind(child) {
+                Some(("use", import)) | Some(("import", import)) => {
+                    let Some(target) = import.get("id").and_then(|id| self.item(id)) else {
+                        continue;
+                    };
+                    if flag(import, &["is_glob", "glob"]) {
+                        self.walk_module(target, prefix, f, depth + 1);
+                        continue;
+                    }
+                    (target, import["name"].as_str())
+                }
+                _ => (child, child["name"].as_str()),
+            };
+            let (Some(name), Some(kind)) = (name, page_kind(item)) else {
+                continue;
+            };
+
+            let path = if prefix.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}::{}", prefix, name)
+            };
+            f(&path, kind, item);
+            if kind == "mod" {
+                self.walk_module(item, &path, f, depth + 1);
+            }
+        }
+    }
+
+    /// Look an item up by its canonical (defining) path
+    fn by_canonical_path(&self, path: &[&str]) -> Option<&Value> {
+        let root = self.item(&self.json["root"])?["name"].as_str()?;
+        let (id, _) = self.json["paths"].as_object()?.iter().find(|(_, summary)| {
+            summary["crate_id"] == 0
+                && arr(&summary["path"])
+                    .map(Value::as_str)
+                    .eq(std::iter::once(Some(root)).chain(path.iter().map(|s| Some(*s))))
+        })?;
+        self.json["index"].get(id)
+    }
+
+    /// Markdown documentation of an item: signature, docs and related items
+    pub fn render(&self, resolved: &Resolved<'_>, path: &str) -> String {
+        let item = resolved.item;
+        let (kind, inner) = kind(item).unwrap_or(("", &NULL));
+        let label = match kind {
+            "function" if resolved.parent.is_some() => {
+                let receiver = arr(&field(inner, &["sig", "decl"])["inputs"]).next();
+                if receiver.is_some_and(|input| input[0] == "self") {
+                    "Method"
+                } else {
+                    "Associated Function"
+                }
+            }
+            _ => kind_label(kind, inner),
+        };
+        let mut out = format!("# {} {}\n\n", label, path);
+
+        if let Some(imp) = resolved.impl_block {
+            out.push_str(&format!("Defined in `{}`\n\n", impl_header(imp).replace('\n', " ")));
+        }
+
+        if let Some(deprecation) = item.get("deprecation").filter(|d| !d.is_null()) {
+            out.push_str("**Deprecated**");
+            if let Some(since) = deprecation["since"].as_str() {
+                out.push_str(&format!(" since {}", since));
+            }
+            if let Some(note) = deprecation["note"].as_str() {
+                out.push_str(&format!(": {}", note));
+            }
+            out.push_str("\n\n");
+        }
+
+        out.push_str(&format!("```rust\n{}\n```\n\n", self.signature(item)));
+        if let Some(docs) = docs(item) {
+            out.push_str(docs.trim());
+            out.push_str("\n\n");
+        }
+
+        match kind {
+            "module" => self.render_module(&mut out, inner),
+            "struct" | "union" => {
+                let fields = match inner.get("kind").and_then(single) {
+                    Some(("plain", plain)) => &plain["fields"],
+                    _ => &inner["fields"],
+                };
+                self.render_members(&mut out, "Fields", fields);
+                self.render_impls(&mut out, &inner["impls"]);
+            }
+            "enum" => {
+                self.render_members(&mut out, "Variants", &inner["variants"]);
+                self.render_impls(&mut out, &inner["impls"]);
+            }
+            "primitive" => self.render_impls(&mut out, &inner["impls"]),
+            "trait" => {
+                self.render_members(&mut out, "Associated Items", &inner["items"]);
+                self.render_implementors(&mut out, &inner["implementations"]);
+            }
+            _ => {}
+        }
+
+        let mut out = out.trim_end().to_string();
+        out.push('\n');
+        out
+    }
+
+    fn render_module(&self, out: &mut String, module: &Value) {
+        const SECTIONS: &[(&str, &[&str])] = &[
+            ("Re-exports", &["use", "import"]),
+            ("Modules", &["module"]),
+            ("Macros", &["macro", "proc_macro"]),
+            ("Structs", &["struct"]),
+            ("Enums", &["enum"]),
+            ("Unions", &["union"]),
+            ("Traits", &["trait", "trait_alias"]),
+            ("Functions", &["function"]),
+            ("Type Aliases", &["type_alias", "typedef"]),
+            ("Constants", &["constant"]),
+            ("Statics", &["static"]),
+        ];
+
+        for (title, kinds) in SECTIONS {
+            let mut section = String::new();
+            for child in self.items(&module["items"]) {
+                let Some(child_kind) = kind_name(child).filter(|k| kinds.contains(k)) else {
+                    continue;
+                };
+                if matches!(child_kind, "use" | "import") {
+                    section.push_str(&format!("- `{}`\n", self.signature(child)));
+                    continue;
+                }
+                let name = child["name"].as_str().unwrap_or_default();
+                match docs(child).map(summary) {
+                    Some(summary) => section.push_str(&format!("- `{}`: {}\n", name, summary)),
+                    None => section.push_str(&format!("- `{}`\n", name)),
+                }
+            }
+            if !section.is_empty() {
+                out.push_str(&format!("## {}\n\n{}\n", title, section));
+            }
+        }
+    }
+
+    /// Signature and summary of each member (field, variant, associated item)
+    fn render_members(&self, out: &mut String, title: &str, ids: &Value) {
+        let mut section = String::new();
+        for member in self.items(ids) {
+            if kind_name(member) == Some("struct_field") && member["visibility"] != "public" {
+                continue;
+            }
+            section.push_str(&format!("```rust\n{}\n```\n", self.member_signature(member)));
+            if let Some(docs) = docs(member) {
+                section.push_str(&format!("{}\n", summary(docs)));
+            }
+            section.push('\n');
+        }
+        if !section.is_empty() {
+            out.push_str(&format!("## {}\n\n{}", title, section));
+        }
+    }
+
+    fn render_impls(&self, out: &mut String, ids: &Value) {
+        let impls: Vec<&Value> = self
+            .items(ids)
+            .filter_map(|item| match kind(item) {
+                Some(("impl", inner)) => Some(inner),
+                _ => None,
+            })
+            .collect();
+
+        let mut methods = String::new();
+        for imp in impls.iter().filter(|imp| imp["trait"].is_null()) {
+            for member in self.items(&imp["items"]).filter(|m| m["visibility"] == "public") {
+                methods.push_str(&format!("```rust\n{}\n```\n", self.member_signature(member)));
+                if let Some(docs) = docs(member) {
+                    methods.push_str(&format!("{}\n", summary(docs)));
+                }
+                methods.push('\n');
+            }
+        }
+        if !methods.is_empty() {
+            out.push_str(&format!("## Implementations\n\n{}", methods));
+        }
+
+        let (auto, traits): (Vec<&Value>, Vec<&Value>) = impls
+            .iter()
+            .filter(|imp| !imp["trait"].is_null() && imp["blanket_impl"].is_null())
+            .partition(|imp| flag(imp, &["is_synthetic", "synthetic"]));
+
+        if !traits.is_empty() {
+            out.push_str("## Trait Implementations\n\n");
+            for imp in traits {
+                out.push_str(&format!("- `{}`\n", impl_header(imp).replace('\n', " ")));
+            }
+            out.push('\n');
+        }
+        if !auto.is_empty() {
+            let names: Vec<String> = auto
+                .iter()
+                .map(|imp| {
+                    let negative = if flag(imp, &["is_negative", "negative"]) { "!" } else { "" };
+                    format!("`{}{}`", negative, path(&imp["trait"]))
+                })
+                .collect();
+            out.push_str(&format!("## Auto Trait Implementations\n\n{}\n\n", names.join(", ")));
+        }
+    }
+
+    fn render_implementors(&self, out: &mut String, ids: &Value) {
+        let implementors: Vec<String> = self
+            .items(ids)
+            .filter_map(|item| match kind(item) {
+                Some(("impl", inner)) => Some(format!("- `{}`\n", impl_header(inner).replace('\n', " "))),
+                _ => None,
+            })
+            .collect();
+        if implementors.is_empty() {
+            return;
+        }
+
+        out.push_str("## Implementors\n\n");
+        for line in implementors.iter().take(MAX_IMPLEMENTORS) {
+            out.push_str(line);
+        }
+        if implementors.len() > MAX_IMPLEMENTORS {
+            out.push_str(&format!("- ... and {} more\n", implementors.len() - MAX_IMPLEMENTORS));
+        }
+        out.push('\n');
+    }
+
+    /// Signature of a member as it appears inside its parent
+    fn member_signature(&self, member: &Value) -> String {
+        match kind(member) {
+            Some(("function", inner)) => {
+                let body = if inner["has_body"] == true { " { ... }" } else { ";" };
+                format!("{}{}", self.signature(member), body)
+            }
+            Some(("variant", _)) => self.variant(member),
+            _ => self.signature(member),
+        }
+    }
+
+    /// Rust declaration of an item, without its body
+    fn signature(&self, item: &Value) -> String {
+        let name = item["name"].as_str().unwrap_or_default();
+        let vis = visibility(&item["visibility"]);
+        let Some((kind, inner)) = kind(item) else {
+            return name.to_string();
+        };
+        let generics = &inner["generics"];
+
+        match kind {
+            "function" => fn_signature(name, &vis, inner),
+            "struct" => format!(
+                "{}struct {}{}{}",
+                vis,
+                name,
+                generic_params(generics),
+                self.struct_body(inner)
+            ),
+            "union" => format!(
+                "{}union {}{}{} {}",
+                vis,
+                name,
+                generic_params(generics),
+                where_clause(generics),
+                self.fields_block(&inner["fields"], flag(inner, &["has_stripped_fields", "fields_stripped"]), true)
+            ),
+            "enum" => {
+                let mut body = String::new();
+                for variant in self.items(&inner["variants"]) {
+                    body.push_str(&format!("    {},\n", self.variant(variant)));
+                }
+                if flag(inner, &["has_stripped_variants", "variants_stripped"]) {
+                    body.push_str("    // some variants omitted\n");
+                }
+                format!(
+                    "{}enum {}{}{} {{\n{}}}",
+                    vis,
+                    name,
+                    generic_params(generics),
+                    where_clause(generics),
+                    body
+                )
+            }
+            "variant" => self.variant(item),
+            "struct_field" => format!("{}{}: {}", vis, name, ty(inner)),
+            "trait" => {
+                let mut head = vis.clone();
+                if flag(inner, &["is_unsafe"]) {
+                    head.push_str("unsafe ");
+                }
+                if flag(inner, &["is_auto"]) {
+                    head.push_str("auto ");
+                }
+                let supertraits = bounds(&inner["bounds"]);
+                let supertraits = if supertraits.is_empty() {
+                    String::new()
+                } else {
+                    format!(": {}", supertraits)
+                };
+
+                let mut body = String::new();
+                for member in self.items(&inner["items"]) {
+                    for line in self.member_signature(member).lines() {
+                        body.push_str(&format!("    {}\n", line));
+                    }
+                }
+                format!(
+                    "{}trait {}{}{}{} {{\n{}}}",
+                    head,
+                    name,
+                    generic_params(generics),
+                    supertraits,
+                    where_clause(generics),
+                    body
+                )
+            }
+            "trait_alias" => format!(
+                "{}trait {}{} = {};",
+                vis,
+                name,
+                generic_params(generics),
+                bounds(&inner["params"])
+            ),
+            "type_alias" | "typedef" => format!(
+                "{}type {}{}{} = {};",
+                vis,
+                name,
+                generic_params(generics),
+                where_clause(generics),
+                ty(field(inner, &["type", "type_"]))
+            ),
+            "constant" => {
+                let expr = inner["const"]["expr"].as_str().or(inner["expr"].as_str()).unwrap_or("_");
+                format!("{}const {}: {} = {};", vis, name, ty(field(inner, &["type", "type_"])), expr)
+            }
+            "static" => format!(
+                "{}static {}{}: {} = {};",
+                vis,
+                if flag(inner, &["is_mutable", "mutable"]) { "mut " } else { "" },
+                name,
+                ty(field(inner, &["type", "type_"])),
+                inner["expr"].as_str().unwrap_or("_")
+            ),
+            "macro" => inner.as_str().unwrap_or(name).to_string(),
+            "proc_macro" => match inner["kind"].as_str() {
+                Some("derive") => {
+                    let helpers: Vec<&str> = arr(&inner["helpers"]).filter_map(Value::as_str).collect();
+                    if helpers.is_empty() {
+                        format!("#[derive({})]", name)
+                    } else {
+                        format!("#[derive({})]\n// helper attributes: {}", name, helpers.join(", "))
+                    }
+                }
+                Some("attr") => format!("#[{}]", name),
+                _ => format!("{}!() {{ /* proc-macro */ }}", name),
+            },
+            "assoc_const" => {
+                let value = field(inner, &["value", "default"])
+                    .as_str()
+                    .map(|v| format!(" = {}", v))
+                    .unwrap_or_default();
+                format!("const {}: {}{};", name, ty(field(inner, &["type", "type_"])), value)
+            }
+            "assoc_type" => {
+                let bounds = bounds(&inner["bounds"]);
+                let bounds = if bounds.is_empty() { String::new() } else { format!(": {}", bounds) };
+                let default = match field(inner, &["type", "default"]) {
+                    Value::Null => String::new(),
+                    default => format!(" = {}", ty(default)),
+                };
+                format!(
+                    "type {}{}{}{}{};",
+                    name,
+                    generic_params(generics),
+                    bounds,
+                    where_clause(generics),
+                    default
+                )
+            }
+            "module" => format!("{}mod {}", vis, name),
+            "impl" => impl_header(inner),
+            "extern_crate" => format!("{}extern crate {};", vis, name),
+            "use" | "import" => {
+                let source = inner["source"].as_str().unwrap_or(name);
+                if flag(inner, &["is_glob", "glob"]) {
+                    format!("{}use {}::*;", vis, source)
+                } else if source.rsplit("::").next() != inner["name"].as_str() {
+                    format!("{}use {} as {};", vis, source, inner["name"].as_str().unwrap_or(name))
+                } else {
+                    format!("{}use {};", vis, source)
+                }
+            }
+            _ => name.to_string(),
+        }
+    }
+
+    fn struct_body(&self, inner: &Value) -> String {
+        let generics = &inner["generics"];
+        match &inner["kind"] {
+            Value::String(unit) if unit == "unit" => format!("{};", where_clause(generics)),
+            kind => match single(kind) {
+                Some(("tuple", fields)) => format!("({}){};", self.tuple_fields(fields, true), where_clause(generics)),
+                Some(("plain", plain)) => format!(
+                    "{} {}",
+                    where_clause(generics),
+                    self.fields_block(
+                        &plain["fields"],
+                        flag(plain, &["has_stripped_fields", "fields_stripped"]),
+                        true
+                    )
+                ),
+                _ => String::new(),
+            },
+        }
+    }
+
+    fn tuple_fields(&self, ids: &Value, with_vis: bool) -> String {
+        arr(ids)
+            .map(|id| match self.item(id) {
+                Some(field) => {
+                    let vis = if with_vis { visibility(&field["visibility"]) } else { String::new() };
+                    format!("{}{}", vis, ty(field_type(field)))
+                }
+                None => "/* private field */".to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn fields_block(&self, ids: &Value, stripped: bool, with_vis: bool) -> String {
+        let mut body = String::new();
+        for field in self.items(ids) {
+            let vis = if with_vis { visibility(&field["visibility"]) } else { String::new() };
+            body.push_str(&format!(
+                "    {}{}: {},\n",
+                vis,
+                field["name"].as_str().unwrap_or("_"),
+                ty(field_type(field))
+            ));
+        }
+        if stripped {
+            body.push_str("    /* private fields */\n");
+        }
+        if body.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{\n{}}}", body)
+        }
+    }
+
+    fn variant(&self, item: &Value) -> String {
+        let name = item["name"].as_str().unwrap_or_default();
+        let Some(("variant", inner)) = kind(item) else {
+            return name.to_string();
+        };
+        let discriminant = inner["discriminant"]["expr"]
+            .as_str()
+            .map(|expr| format!(" = {}", expr))
+            .unwrap_or_default();
+
+        match single(&inner["kind"]) {
+            Some(("tuple", fields)) => format!("{}({})", name, self.tuple_fields(fields, false)),
+            Some(("struct", fields)) => {
+                let block = self.fields_block(
+                    &fields["fields"],
+                    flag(fields, &["has_stripped_fields", "fields_stripped"]),
+                    false,
+                );
+                format!("{} {}", name, block.replace("\n    ", "\n        ").replace("\n}", "\n    }"))
+            }
+            _ => format!("{}{}", name, discriminant),
+        }
+    }
+}
+
+fn field_type(field: &Value) -> &Value {
+    match kind(field) {
+        Some(("struct_field", ty)) => ty,
+        _ => &NULL,
+    }
+}
+
+fn fn_signature(name: &str, vis: &str, inner: &Value) -> String {
+    let generics = &inner["generics"];
+    format!(
+        "{}{}fn {}{}{}{}",
+        vis,
+        fn_header(&inner["header"]),
+        name,
+        generic_params(generics),
+        fn_decl(field(inner, &["sig", "decl"]), true),
+        where_clause(generics)
+    )
+}
+
+fn fn_header(header: &Value) -> String {
+    let mut out = String::new();
+    if flag(header, &["is_const", "const_"]) {
+        out.push_str("const ");
+    }
+    if flag(header, &["is_async", "async_"]) {
+        out.push_str("async ");
+    }
+    if flag(header, &["is_unsafe", "unsafe_"]) {
+        out.push_str("unsafe ");
+    }
+    match &header["abi"] {
+        Value::String(abi) if abi == "Rust" => {}
+        Value::String(abi) => out.push_str(&format!("extern \"{}\" ", abi)),
+        abi => match single(abi) {
+            Some(("Other", other)) => out.push_str(&format!("extern {} ", other.as_str().unwrap_or("\"C\""))),
+            Some((abi, _)) => out.push_str(&format!("extern \"{}\" ", abi)),
+            None => {}
+        },
+    }
+    out
+}
+
+fn fn_decl(sig: &Value, named: bool) -> String {
+    let mut inputs: Vec<String> = arr(&sig["inputs"])
+        .map(|input| {
+            let name = input[0].as_str().unwrap_or("_");
+            let ty_value = &input[1];
+            if !named {
+                return ty(ty_value);
+            }
+            self_param(name, ty_value).unwrap_or_else(|| format!("{}: {}", name, ty(ty_value)))
+        })
+        .collect();
+    if flag(sig, &["is_c_variadic", "c_variadic"]) {
+        inputs.push("...".to_string());
+    }
+    format!("({}){}", inputs.join(", "), ret(&sig["output"]))
+}
+
+/// `self`, `&self`, `&mut self` and `&'a self` receivers
+fn self_param(name: &str, ty_value: &Value) -> Option<String> {
+    if name != "self" {
+        return None;
+    }
+    let is_self = |t: &Value| t.get("generic").and_then(Value::as_str) == Some("Self");
+    if is_self(ty_value) {
+        return Some("self".to_string());
+    }
+    match single(ty_value) {
+        Some(("borrowed_ref", r)) if is_self(field(r, &["type", "type_"])) => {
+            let lifetime = r["lifetime"].as_str().map(|l| format!("{} ", l)).unwrap_or_default();
+            let mutability = if flag(r, &["is_mutable", "mutable"]) { "mut " } else { "" };
+            Some(format!("&{}{}self", lifetime, mutability))
+        }
+        _ => Some(format!("self: {}", ty(ty_value))),
+    }
+}
+
+fn ret(output: &Value) -> String {
+    match output {
+        Value::Null => String::new(),
+        output => format!(" -> {}", ty(output)),
+    }
+}
+
+fn impl_header(inner: &Value) -> String {
+    let generics = &inner["generics"];
+    let unsafety = if flag(inner, &["is_unsafe"]) { "unsafe " } else { "" };
+    let trait_part = match &inner["trait"] {
+        Value::Null => String::new(),
+        tr => format!(
+            "{}{} for ",
+            if flag(inner, &["is_negative", "negative"]) { "!" } else { "" },
+            path(tr)
+        ),
+    };
+    format!(
+        "{}impl{} {}{}{}",
+        unsafety,
+        generic_params(generics),
+        trait_part,
+        ty(&inner["for"]),
+        where_clause(generics)
+    )
+}
+
+/// Render a rustdoc JSON `Type`
+fn ty(t: &Value) -> String {
+    let Some((kind, v)) = single(t) else {
+        return "_".to_string();
+    };
+    match kind {
+        "resolved_path" => path(v),
+        "generic" | "primitive" => v.as_str().unwrap_or("_").to_string(),
+        "dyn_trait" => {
+            let mut parts: Vec<String> = arr(&v["traits"])
+                .map(|poly| format!("{}{}", for_lifetimes(&poly["generic_params"]), path(&poly["trait"])))
+                .collect();
+            parts.extend(v["lifetime"].as_str().map(String::from));
+            format!("dyn {}", parts.join(" + "))
+        }
+        "function_pointer" => format!(
+            "{}{}fn{}",
+            for_lifetimes(&v["generic_params"]),
+            fn_header(&v["header"]),
+            fn_decl(field(v, &["sig", "decl"]), false)
+        ),
+        "tuple" => {
+            let elems: Vec<String> = arr(v).map(ty).collect();
+            match elems.len() {
+                1 => format!("({},)", elems[0]),
+                _ => format!("({})", elems.join(", ")),
+            }
+        }
+        "slice" => format!("[{}]", ty(v)),
+        "array" => format!("[{}; {}]", ty(field(v, &["type", "type_"])), v["len"].as_str().unwrap_or("_")),
+        "pat" => ty(field(v, &["type", "type_"])),
+        "impl_trait" => format!("impl {}", bounds(v)),
+        "raw_pointer" => format!(
+            "*{} {}",
+            if flag(v, &["is_mutable", "mutable"]) { "mut" } else { "const" },
+            ty(field(v, &["type", "type_"]))
+        ),
+        "borrowed_ref" => format!(
+            "&{}{}{}",
+            v["lifetime"].as_str().map(|l| format!("{} ", l)).unwrap_or_default(),
+            if flag(v, &["is_mutable", "mutable"]) { "mut " } else { "" },
+            ty(field(v, &["type", "type_"]))
+        ),
+        "qualified_path" => {
+            let name = v["name"].as_str().unwrap_or("_");
+            let self_type = ty(&v["self_type"]);
+            match &v["trait"] {
+                Value::Null => format!("{}::{}", self_type, name),
+                tr => format!("<{} as {}>::{}", self_type, path(tr), name),
+            }
+        }
+        _ => "_".to_string(),
+    }
+}
+
+/// Render a rustdoc JSON `Path` with its generic arguments
+fn path(p: &Value) -> String {
+    let name = field(p, &["path", "name"]).as_str().unwrap_or("_");
+    format!("{}{}", name, generic_args(&p["args"]))
+}
+
+fn generic_args(args: &Value) -> String {
+    match single(args) {
+        Some(("angle_bracketed", v)) => {
+            let mut parts: Vec<String> = arr(&v["args"]).map(generic_arg).collect();
+            parts.extend(arr(field(v, &["constraints", "bindings"])).map(constraint));
+            if parts.is_empty() {
+                String::new()
+            } else {
+                format!("<{}>", parts.join(", "))
+            }
+        }
+        Some(("parenthesized", v)) => {
+            let inputs: Vec<String> = arr(&v["inputs"]).map(ty).collect();
+            format!("({}){}", inputs.join(", "), ret(&v["output"]))
+        }
+        Some(("return_type_notation", _)) => "(..)".to_string(),
+        _ => String::new(),
+    }
+}
+
+fn generic_arg(arg: &Value) -> String {
+    match single(arg) {
+        Some(("type", t)) => ty(t),
+        Some(("lifetime", l)) => l.as_str().unwrap_or("'_").to_string(),
+        Some(("const", c)) => c["expr"].as_str().unwrap_or("_").to_string(),
+        _ => "_".to_string(),
+    }
+}
+
+fn constraint(c: &Value) -> String {
+    let name = format!("{}{}", c["name"].as_str().unwrap_or("_"), generic_args(&c["args"]));
+    match single(&c["binding"]) {
+        Some(("equality", term)) => format!("{} = {}", name, term_str(term)),
+        Some(("constraint", b)) => format!("{}: {}", name, bounds(b)),
+        _ => name,
+    }
+}
+
+fn term_str(term: &Value) -> String {
+    match single(term) {
+        Some(("type", t)) => ty(t),
+        Some(("constant", c)) => c["expr"].as_str().unwrap_or("_").to_string(),
+        _ => ty(term),
+    }
+}
+
+fn bounds(list: &Value) -> String {
+    arr(list)
+        .map(bound)
+        .filter(|b| !b.is_empty())
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+fn bound(b: &Value) -> String {
+    match single(b) {
+        Some(("trait_bound", tb)) => {
+            let modifier = match tb["modifier"].as_str() {
+                Some("maybe") => "?",
+                Some("maybe_const") => "~const ",
+                _ => "",
+            };
+            format!("{}{}{}", for_lifetimes(&tb["generic_params"]), modifier, path(&tb["trait"]))
+        }
+        Some(("outlives", l)) => l.as_str().unwrap_or_default().to_string(),
+        Some(("use", args)) => {
+            let args: Vec<&str> = arr(args)
+                .filter_map(|a| a.as_str().or_else(|| single(a).and_then(|(_, v)| v.as_str())))
+                .collect();
+            format!("use<{}>", args.join(", "))
+        }
+        _ => String::new(),
+    }
+}
+
+fn for_lifetimes(params: &Value) -> String {
+    let names: Vec<&str> = arr(params).filter_map(|p| p["name"].as_str()).collect();
+    if names.is_empty() {
+        String::new()
+    } else {
+        format!("for<{}> ", names.join(", "))
+    }
+}
+
+fn generic_params(generics: &Value) -> String {
+    let params: Vec<String> = arr(&generics["params"]).filter_map(generic_param).collect();
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", params.join(", "))
+    }
+}
+
+fn generic_param(param: &Value) -> Option<String> {
+    let name = param["name"].as_str()?;
+    match single(&param["kind"]) {
+        Some(("lifetime", v)) => {
+            let outlives: Vec<&str> = arr(&v["outlives"]).filter_map(Value::as_str).collect();
+            if outlives.is_empty() {
+                Some(name.to_string())
+            } else {
+                Some(format!("{}: {}", name, outlives.join(" + ")))
+            }
+        }
+        Some(("type", v)) => {
+            if flag(v, &["is_synthetic", "synthetic"]) {
+                return None;
+            }
+            let mut out = name.to_string();
+            let bounds = bounds(&v["bounds"]);
+            if !bounds.is_empty() {
+                out.push_str(&format!(": {}", bounds));
+            }
+            if !v["default"].is_null() {
+                out.push_str(&format!(" = {}", ty(&v["default"])));
+            }
+            Some(out)
+        }
+        Some(("const", v)) => {
+            let default = v["default"].as_str().map(|d| format!(" = {}", d)).unwrap_or_default();
+            Some(format!("const {}: {}{}", name, ty(field(v, &["type", "type_"])), default))
+        }
+        _ => Some(name.to_string()),
+    }
+}
+
+fn where_clause(generics: &Value) -> String {
+    let predicates: Vec<String> = arr(&generics["where_predicates"])
+        .filter_map(|predicate| match single(predicate)? {
+            ("bound_predicate", v) => Some(format!(
+                "{}{}: {}",
+                for_lifetimes(&v["generic_params"]),
+                ty(field(v, &["type", "type_"])),
+                bounds(&v["bounds"])
+            )),
+            ("lifetime_predicate", v) => {
+                let outlives: Vec<&str> = arr(&v["outlives"]).filter_map(Value::as_str).collect();
+                Some(format!("{}: {}", v["lifetime"].as_str()?, outlives.join(" + ")))
+            }
+            ("eq_predicate", v) => Some(format!("{} = {}", ty(&v["lhs"]), term_str(&v["rhs"]))),
+            _ => None,
+        })
+        .collect();
+    if predicates.is_empty() {
+        String::new()
+    } else {
+        format!("\nwhere\n    {},", predicates.join(",\n    "))
+    }
+}
+
+fn visibility(vis: &Value) -> String {
+    match vis {
+        Value::String(v) if v == "public" => "pub ".to_string(),
+        Value::String(v) if v == "crate" => "pub(crate) ".to_string(),
+        Value::String(_) => String::new(),
+        vis => match vis["restricted"]["path"].as_str() {
+            Some(path) => format!("pub(in {}) ", path),
+            None => String::new(),
+        },
+    }
+}
+
+fn kind_label(kind: &str, inner: &Value) -> &'static str {
+    match kind {
+        "module" => "Module",
+        "function" => "Function",
+        "struct" => "Struct",
+        "enum" => "Enum",
+        "union" => "Union",
+        "trait" => "Trait",
+        "trait_alias" => "Trait Alias",
+        "type_alias" | "typedef" => "Type Alias",
+        "constant" => "Constant",
+        "static" => "Static",
+        "macro" => "Macro",
+        "proc_macro" => match inner["kind"].as_str() {
+            Some("derive") => "Derive Macro",
+            Some("attr") => "Attribute Macro",
+            _ => "Macro",
+        },
+        "primitive" => "Primitive Type",
+        "variant" => "Variant",
+        "struct_field" => "Field",
+        "assoc_const" => "Associated Constant",
+        "assoc_type" => "Associated Type",
+        _ => "Item",
+    }
+}
+
+/// Item kind as used in rustdoc page names (`struct.Foo.html`, `fn.bar.html`)
+fn page_kind(item: &Value) -> Option<&'static str> {
+    let (kind, inner) = kind(item)?;
+    Some(match kind {
+        "module" => "mod",
+        "function" => "fn",
+        "struct" => "struct",
+        "enum" => "enum",
+        "union" => "union",
+        "trait" => "trait",
+        "trait_alias" => "traitalias",
+        "type_alias" | "typedef" => "type",
+        "constant" => "constant",
+        "static" => "static",
+        "macro" => "macro",
+        "proc_macro" => match inner["kind"].as_str() {
+            Some("derive") => "derive",
+            Some("attr") => "attr",
+            _ => "macro",
+        },
+        "primitive" => "primitive",
+        _ => return None,
+    })
+}
+
+/// Source text of an attribute, which newer format versions wrap in an object
+fn attr_text(attr: &Value) -> String {
+    match attr {
+        Value::String(s) => s.clone(),
+        Value::Array(values) => values.iter().map(attr_text).collect::<Vec<_>>().join(" "),
+        Value::Object(map) => map.values().map(attr_text).collect::<Vec<_>>().join(" "),
+        _ => String::new(),
+    }
+}
+
+fn docs(item: &Value) -> Option<&str> {
+    item["docs"].as_str().filter(|d| !d.trim().is_empty())
+}
+
+/// First paragraph of a doc comment, on one line
+fn summary(docs: &str) -> String {
+    docs.trim()
+        .split("\n\n")
+        .next()
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The `(kind, payload)` of an item's `inner`
+fn kind(item: &Value) -> Option<(&str, &Value)> {
+    single(item.get("inner")?)
+}
+
+fn kind_name(item: &Value) -> Option<&str> {
+    kind(item).map(|(kind, _)| kind)
+}
+
+/// The single `{ "tag": payload }` entry of an externally tagged enum
+fn single(v: &Value) -> Option<(&str, &Value)> {
+    let object = v.as_object()?;
+    if object.len() != 1 {
+        return None;
+    }
+    object.iter().next().map(|(k, v)| (k.as_str(), v))
+}
+
+/// First present field out of names used by different rustdoc JSON format versions
+fn field<'a>(v: &'a Value, names: &[&str]) -> &'a Value {
+    names
+        .iter()
+        .map(|name| &v[*name])
+        .find(|value| !value.is_null())
+        .unwrap_or(&NULL)
+}
+
+fn flag(v: &Value, names: &[&str]) -> bool {
+    field(v, names).as_bool().unwrap_or(false)
+}
+
+fn arr(v: &Value) -> impl Iterator<Item = &Value> {
+    v.as_array().into_iter().flatten()
+}
+
+/// Item ids are strings in older format versions and integers in newer ones
+fn id_key(id: &Value) -> Option<String> {
+    match id {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
@@ -0,0 +1,159 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use mcp_core::{
+    handler::PromptError,
+    protocol::{ErrorData, JsonRpcRequest, JsonRpcResponse},
+};
+use mcp_server::router::RouterService;
+use serde_json::{json, Value};
+use tower_service::Service;
+
+use super::completion::CompletionRef;
+use super::DocRouter;
+
+type RouterError = <RouterService<DocRouter> as Service<JsonRpcRequest>>::Error;
+
+/// JSON-RPC error code of a message that is not valid JSON
+pub(crate) const PARSE_ERROR: i32 = -32700;
+/// JSON-RPC error code of a message that is not a valid request object
+pub(crate) const INVALID_REQUEST: i32 = -32600;
+/// JSON-RPC error code of a request whose parameters are missing or invalid
+pub(crate) const INVALID_PARAMS: i32 = -32602;
+/// JSON-RPC error code of a request that failed while being handled
+pub(crate) const INTERNAL_ERROR: i32 = -32603;
+
+/// JSON-RPC service of a [`DocRouter`]
+///
+/// Requests the generic [`RouterService`] has no handler for, such as
+/// `resources/templates/list` and `completion/complete`, are answered here, as is
+/// `prompts/get` so prompts can embed fetched documentation; everything else is passed
+/// through, with `initialize` also advertising completions.
+#[derive(Clone)]
+pub struct DocService {
+    router: DocRouter,
+}
+
+impl DocService {
+    pub fn new(router: DocRouter) -> Self {
+        Self { router }
+    }
+
+    pub fn router(&self) -> &DocRouter {
+        &self.router
+    }
+}
+
+impl Service<JsonRpcRequest> for DocService {
+    type Response = JsonRpcResponse;
+    type Error = RouterError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: JsonRpcRequest) -> Self::Future {
+        match request.method.as_str() {
+            "resources/templates/list" => {
+                let result = json!({ "resourceTemplates": self.router.list_resource_templates() });
+                Box::pin(async move { Ok(success(request.id, result)) })
+            }
+            "prompts/get" => {
+                let router = self.router.clone();
+                Box::pin(async move {
+                    let params = request.params.unwrap_or_default();
+                    let Some(name) = params.get("name").and_then(Value::as_str) else {
+                        return Ok(failure(request.id, INVALID_PARAMS, "Missing prompt name".to_string()));
+                    };
+                    let arguments = params
+                        .get("arguments")
+                        .and_then(Value::as_object)
+                        .cloned()
+                        .unwrap_or_default();
+
+                    let response = match router.render_prompt(name, &arguments).await {
+                        Ok((description, text)) => success(
+                            request.id,
+                            json!({
+                                "description": description,
+                                "messages": [{
+                                    "role": "user",
+                                    "content": { "type": "text", "text": text }
+                                }]
+                            }),
+                        ),
+                        Err(PromptError::InternalError(message)) => failure(request.id, INTERNAL_ERROR, message),
+                        Err(PromptError::InvalidParameters(message) | PromptError::NotFound(message)) => {
+                            failure(request.id, INVALID_PARAMS, message)
+                        }
+                    };
+                    Ok(response)
+                })
+            }
+            "completion/complete" => {
+                let router = self.router.clone();
+                Box::pin(async move {
+                    let params = request.params.unwrap_or_default();
+                    let reference = params.get("ref").and_then(CompletionRef::parse);
+                    let argument = params.get("argument");
+                    let name = argument.and_then(|a| a.get("name")).and_then(Value::as_str);
+                    let (Some(reference), Some(name)) = (reference, name) else {
+                        return Ok(failure(request.id, INVALID_PARAMS, "Missing completion reference or argument".to_string()));
+                    };
+                    let value = argument.and_then(|a| a.get("value")).and_then(Value::as_str).unwrap_or_default();
+                    let context = params
+                        .pointer("/context/arguments")
+                        .and_then(Value::as_object)
+                        .cloned()
+                        .unwrap_or_default();
+
+                    let completion = router.complete(&reference, name, value, &context).await;
+                    Ok(success(request.id, json!({ "completion": completion })))
+                })
+            }
+            "initialize" => {
+                let response = RouterService(self.router.clone()).call(request);
+                Box::pin(async move {
+                    let mut response = response.await?;
+                    if let Some(capabilities) = response
+                        .result
+                        .as_mut()
+                        .and_then(|result| result.get_mut("capabilities"))
+                        .and_then(Value::as_object_mut)
+                    {
+                        capabilities.insert("completions".to_string(), json!({}));
+                    }
+                    Ok(response)
+                })
+            }
+            _ => RouterService(self.router.clone()).call(request),
+        }
+    }
+}
+
+fn success(id: Option<u64>, result: Value) -> JsonRpcResponse {
+    JsonRpcResponse {
+        jsonrpc: "2.0".to_string(),
+        id,
+        result: Some(result),
+        error: None,
+    }
+}
+
+/// JSON-RPC error response to the request `id`
+pub(crate) fn failure(id: Option<u64>, code: i32, message: String) -> JsonRpcResponse {
+    JsonRpcResponse {
+        jsonrpc: "2.0".to_string(),
+        id,
+        result: None,
+        error: Some(ErrorData {
+            code,
+            message,
+            data: None,
+        }),
+    }
+}
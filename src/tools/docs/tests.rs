@@ -1,5 +1,21 @@
-use crate::tools::{DocCache, DocRouter};
-use mcp_core::{Content, ToolError};
+use crate::test_support::TempDir;
+use crate::tools::docs::auth::{go_path_matches, netrc_auth, npmrc_auth, Credentials, RegistryAuth};
+use crate::tools::docs::cache::{CachePolicy, CacheStats, Freshness};
+use crate::tools::docs::completion::{last_entry, Completion, CompletionRef};
+use crate::tools::docs::crate_items::{parse_all_html, render_inventory, suggest, CrateItem, Suggestion};
+use crate::tools::docs::project::{
+    parse_go_mod, parse_go_sum, parse_package_lock, parse_pnpm_lock, parse_python_lock, parse_requirements,
+    parse_yarn_lock, Ecosystem, LockedVersion, LockedVersions, Project,
+};
+use crate::tools::docs::metrics::{escape, metrics};
+use crate::tools::docs::progress::Progress;
+use crate::tools::docs::prompts;
+use crate::tools::docs::resources::DocUri;
+use crate::tools::docs::features::{doc_cfg_features, index_path, parse_index, select_version};
+use crate::tools::docs::registry::RegistryUrls;
+use crate::tools::docs::rustdoc::RustdocClient;
+use crate::tools::{DocCache, DocRouter, DocService};
+use mcp_core::{handler::ResourceError, Content, ToolError};
 use mcp_server::Router;
 use reqwest::Client;
 use serde_json::json;
@@ -52,6 +68,154 @@ async fn test_cache_concurrent_access() {
     }
 }
 
+#[tokio::test]
+async fn test_persistent_cache_survives_restart() {
+    let dir = TempDir::new("cache-test");
+
+    let cache = DocCache::persistent(dir.path());
+    cache
+        .set("npm:left-pad".to_string(), "left-pad docs".to_string())
+        .await;
+
+    let reopened = DocCache::persistent(dir.path());
+    assert_eq!(reopened.get("npm:left-pad").await, Some("left-pad docs".to_string()));
+    assert_eq!(reopened.get("npm:right-pad").await, None);
+
+    let router = DocRouter::with_cache(DocCache::persistent(dir.path()));
+    assert_eq!(
+        router.npm_client.get_cache("npm:left-pad").await,
+        Some("left-pad docs".to_string())
+    );
+
+    let mirror = RegistryUrls {
+        npm: "https://npm.internal.example.com/".to_string(),
+        ..RegistryUrls::default()
+    };
+    let router = DocRouter::with_cache(DocCache::persistent(dir.path())).with_registries(mirror.clone());
+    assert_eq!(router.npm_client.get_cache("npm:left-pad").await, None);
+    router
+        .npm_client
+        .set_cache("npm:left-pad".to_string(), "mirrored docs".to_string())
+        .await;
+    router.cache.flush().await;
+
+    let reopened = DocRouter::with_cache(DocCache::persistent(dir.path())).with_registries(mirror);
+    assert_eq!(reopened.npm_client.get_cache("npm:left-pad").await, Some("mirrored docs".to_string()));
+    let public = DocRouter::with_cache(DocCache::persistent(dir.path())).with_registries(RegistryUrls::default());
+    assert_eq!(public.npm_client.get_cache("npm:left-pad").await, Some("left-pad docs".to_string()));
+}
+
+#[tokio::test]
+async fn test_cache_ttl_by_freshness() {
+    let cache = DocCache::new().with_policy(CachePolicy {
+        latest_ttl: Some(Duration::from_millis(50)),
+        pinned_ttl: None,
+        max_entries: 16,
+        ..CachePolicy::default()
+    });
+
+    cache.set("serde".to_string(), "latest docs".to_string()).await;
+    cache
+        .set_with("serde:1.0.0".to_string(), "pinned docs".to_string(), Freshness::Pinned)
+        .await;
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(cache.get("serde").await, None);
+    assert_eq!(cache.get("serde:1.0.0").await, Some("pinned docs".to_string()));
+
+    assert_eq!(Freshness::for_version(&None), Freshness::Latest);
+    assert_eq!(Freshness::for_version(&Some("latest".to_string())), Freshness::Latest);
+    assert_eq!(Freshness::for_version(&Some("1.2.3".to_string())), Freshness::Pinned);
+    assert_eq!(Freshness::for_version(&Some("v0.14.0".to_string())), Freshness::Pinned);
+    for version in ["next", "^1", "1", "1.0", ""] {
+        assert_eq!(Freshness::for_version(&Some(version.to_string())), Freshness::Latest);
+    }
+}
+
+#[tokio::test]
+async fn test_cache_lru_eviction_and_stats() {
+    let cache = DocCache::new().with_policy(CachePolicy {
+        max_entries: 2,
+        ..CachePolicy::default()
+    });
+
+    cache.set("a".to_string(), "1".to_string()).await;
+    cache.set("b".to_string(), "2".to_string()).await;
+    assert_eq!(cache.get("a").await, Some("1".to_string()));
+    cache.set("c".to_string(), "3".to_string()).await;
+
+    assert_eq!(cache.get("b").await, None);
+    assert_eq!(cache.get("a").await, Some("1".to_string()));
+    assert_eq!(cache.get("c").await, Some("3".to_string()));
+
+    assert_eq!(
+        cache.stats().await,
+        CacheStats {
+            hits: 3,
+            misses: 1,
+            evictions: 1,
+            entries: 2,
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_cache_byte_budgets() {
+    let cache = DocCache::new().with_policy(CachePolicy {
+        max_memory_bytes: Some(12),
+        ..CachePolicy::default()
+    });
+    cache.set("a".to_string(), "12345".to_string()).await;
+    cache.set("b".to_string(), "12345".to_string()).await;
+    assert_eq!(cache.get("b").await, Some("12345".to_string()));
+    cache.set("c".to_string(), "12345".to_string()).await;
+    assert_eq!(cache.get("a").await, None);
+    cache.set("d".to_string(), "x".repeat(20)).await;
+    assert_eq!(cache.get("d").await, None);
+    assert_eq!(cache.get("c").await, Some("12345".to_string()));
+
+    let dir = TempDir::new("budget-test");
+    let files = |dir: &std::path::Path| std::fs::read_dir(dir).unwrap().count();
+
+    let expiring = DocCache::persistent(dir.path()).with_policy(CachePolicy {
+        latest_ttl: Some(Duration::ZERO),
+        ..CachePolicy::default()
+    });
+    expiring.set_with("kept".to_string(), "pinned docs".to_string(), Freshness::Pinned).await;
+    expiring.flush().await;
+    expiring.set("stale".to_string(), "old docs".to_string()).await;
+    expiring.flush().await;
+    assert_eq!(files(&dir), 2);
+    let reopened = DocCache::persistent(dir.path());
+    assert_eq!(reopened.get("other").await, None);
+    reopened.flush().await;
+    assert_eq!(files(&dir), 1);
+    assert!(reopened.get("kept").await.is_some());
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    std::fs::create_dir_all(&dir).unwrap();
+    let two_hours_ago = std::time::SystemTime::now() - Duration::from_secs(2 * 60 * 60);
+    for name in ["notes.json", "0123456789abcdef.json.bak", "upload.tmp"] {
+        std::fs::write(dir.join(name), "x".repeat(5000)).unwrap();
+        std::fs::File::options().write(true).open(dir.join(name)).unwrap().set_modified(two_hours_ago).unwrap();
+    }
+    let bounded = DocCache::persistent(dir.path()).with_policy(CachePolicy {
+        max_disk_bytes: Some(2500),
+        ..CachePolicy::default()
+    });
+    for key in ["first", "second", "third"] {
+        bounded.set_with(key.to_string(), "x".repeat(1000), Freshness::Pinned).await;
+        bounded.flush().await;
+    }
+    assert_eq!(files(&dir), 5);
+    assert!(dir.join("notes.json").exists() && dir.join("upload.tmp").exists());
+    let reopened = DocCache::persistent(dir.path());
+    assert_eq!(reopened.get("first").await, None);
+    assert!(reopened.get("second").await.is_some());
+    assert!(reopened.get("third").await.is_some());
+}
+
 #[tokio::test]
 async fn test_router_capabilities() {
     let router = DocRouter::new();
@@ -181,14 +345,622 @@ async fn test_lookup_crate_network_error() {
     }
 }
 
+fn mock_registries(url: &str) -> RegistryUrls {
+    RegistryUrls {
+        docs_rs: url.to_string(),
+        crates_io: url.to_string(),
+        crates_index: url.to_string(),
+        npm: url.to_string(),
+        pypi: url.to_string(),
+        go: url.to_string(),
+        devdocs: url.to_string(),
+    }
+}
+
 #[tokio::test]
 async fn test_lookup_crate_with_mocks() {
-    assert!(true);
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/crate/serde/1.0.0/")
+        .with_status(200)
+        .with_body("<html><body><h1>serde</h1><p>A serialization framework</p></body></html>")
+        .create_async()
+        .await;
+
+    let router = DocRouter::new().with_registries(mock_registries(&format!("{}/", server.url())));
+    let result = router
+        .call_tool(
+            "lookup_crate",
+            json!({
+                "crate_name": "serde",
+                "version": "1.0.0"
+            }),
+        )
+        .await
+        .unwrap();
+
+    mock.assert_async().await;
+    if let Content::Text(text) = &result[0] {
+        assert!(text.text.contains("serialization framework"));
+    } else {
+        panic!("Expected text content");
+    }
 }
 
 #[tokio::test]
 async fn test_lookup_crate_not_found() {
-    assert!(true);
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/crate/does-not-exist/")
+        .with_status(404)
+        .create_async()
+        .await;
+
+    let router = DocRouter::new().with_registries(mock_registries(&server.url()));
+    let result = router
+        .call_tool(
+            "lookup_crate",
+            json!({
+                "crate_name": "does-not-exist"
+            }),
+        )
+        .await;
+
+    assert!(matches!(result, Err(ToolError::ExecutionError(ref msg)) if msg.contains("404")));
+}
+
+#[tokio::test]
+async fn test_npm_lookup_with_mock_registry() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/left-pad")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"version": "1.3.0", "description": "String left pad", "license": "WTFPL"}"#)
+        .create_async()
+        .await;
+
+    let router = DocRouter::new().with_registries(mock_registries(&server.url()));
+    let result = router
+        .call_tool("lookup_npm_package", json!({ "package_name": "left-pad" }))
+        .await
+        .unwrap();
+
+    mock.assert_async().await;
+    if let Content::Text(text) = &result[0] {
+        assert!(text.text.contains("# left-pad"));
+        assert!(text.text.contains("**Version:** 1.3.0"));
+        assert!(text.text.contains("String left pad"));
+    } else {
+        panic!("Expected text content");
+    }
+}
+
+#[tokio::test]
+async fn test_npm_lookup_sends_registry_credentials() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/@acme/widgets")
+        .match_header("authorization", "Bearer secret")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"version": "2.0.0", "description": "Internal widgets"}"#)
+        .create_async()
+        .await;
+
+    let router = DocRouter::new()
+        .with_registries(mock_registries(&server.url()))
+        .with_credentials(Credentials {
+            npm: Some(RegistryAuth::Bearer("secret".to_string())),
+            ..Credentials::default()
+        });
+    let result = router
+        .call_tool("lookup_npm_package", json!({ "package_name": "@acme/widgets" }))
+        .await;
+
+    mock.assert_async().await;
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_cargo_tokens_stay_with_their_host() {
+    let token = |registry: &str| Some(RegistryAuth::Bearer(registry.to_string()));
+    let bearer = |token: &str| Some(RegistryAuth::Bearer(token.to_string()));
+
+    for registry in [None, Some("internal")] {
+        let public = Credentials::default().with_cargo_tokens(&RegistryUrls::default(), registry, token);
+        assert_eq!(public, Credentials::default());
+    }
+
+    let mirror = RegistryUrls {
+        docs_rs: "https://docs.internal.example.com".to_string(),
+        crates_io: "https://crates.internal.example.com".to_string(),
+        crates_index: "https://index.internal.example.com".to_string(),
+        ..RegistryUrls::default()
+    };
+    let credentials = Credentials::default().with_cargo_tokens(&mirror, None, token);
+    assert_eq!(credentials, Credentials::default());
+    let credentials = Credentials::default().with_cargo_tokens(&mirror, Some("internal"), token);
+    assert_eq!(credentials.crates_index, bearer("internal"));
+    assert_eq!(credentials.crates_io, None);
+    assert_eq!(credentials.docs_rs, None);
+
+    let same_host = RegistryUrls {
+        crates_io: "https://cargo.internal.example.com".to_string(),
+        crates_index: "https://cargo.internal.example.com/index".to_string(),
+        ..RegistryUrls::default()
+    };
+    let credentials = Credentials::default().with_cargo_tokens(&same_host, Some("internal"), token);
+    assert_eq!(credentials.crates_io, bearer("internal"));
+    assert_eq!(credentials.crates_index, bearer("internal"));
+}
+
+#[test]
+fn test_registry_credential_discovery() {
+    let npmrc = "registry=https://npm.acme.dev/
+//npm.acme.dev/:_authToken=abc123
+//registry.npmjs.org/:_authToken=public";
+    assert_eq!(
+        npmrc_auth(npmrc, "https://npm.acme.dev"),
+        Some(RegistryAuth::Bearer("abc123".to_string()))
+    );
+    assert_eq!(npmrc_auth(npmrc, "https://other.example.com"), None);
+
+    let npmrc = "//npm.acme.dev/foo/:_authToken=scoped";
+    assert_eq!(
+        npmrc_auth(npmrc, "https://npm.acme.dev/foo/bar"),
+        Some(RegistryAuth::Bearer("scoped".to_string()))
+    );
+    assert_eq!(npmrc_auth(npmrc, "https://npm.acme.dev/foobar/"), None);
+
+    let npmrc = "//npm.acme.dev/:_auth=dXNlcjpwYXNz";
+    assert_eq!(
+        npmrc_auth(npmrc, "https://npm.acme.dev/"),
+        Some(RegistryAuth::Basic { username: "user".to_string(), password: Some("pass".to_string()) })
+    );
+    assert_eq!(npmrc_auth("//npm.acme.dev/:_auth=dXNlcjpwYXNz=", "https://npm.acme.dev/"), None);
+    assert_eq!(npmrc_auth("//npm.acme.dev/:_auth=dXNl-jpwYXNz", "https://npm.acme.dev/"), None);
+
+    let netrc = "machine proxy.acme.dev login alice password s3cret
+default login anon password none";
+    assert_eq!(
+        netrc_auth(netrc, "https://proxy.acme.dev"),
+        Some(RegistryAuth::Basic { username: "alice".to_string(), password: Some("s3cret".to_string()) })
+    );
+    assert_eq!(netrc_auth(netrc, "https://pkg.go.dev"), None);
+
+    assert!(go_path_matches("github.com/acme", "github.com/acme/tools/cmd"));
+    assert!(go_path_matches("*.corp.example.com", "git.corp.example.com/team/lib"));
+    assert!(!go_path_matches("github.com/acme", "github.com/other/tools"));
+
+    let debug = format!("{:?}", RegistryAuth::Bearer("abc123".to_string()));
+    assert!(!debug.contains("abc123"));
+}
+
+/// Minimal rustdoc JSON document for a crate named `demo`
+fn rustdoc_fixture() -> serde_json::Value {
+    let u32_ty = json!({ "primitive": "u32" });
+    json!({
+        "root": 0,
+        "crate_version": "0.1.0",
+        "format_version": 39,
+        "index": {
+            "0": { "id": 0, "name": "demo", "visibility": "public", "docs": "Demo crate.",
+                   "inner": { "module": { "is_crate": true, "items": [1, 2, 5, 6, 8, 9, 16] } } },
+            "1": { "id": 1, "name": "Widget", "visibility": "public", "docs": "A widget.\n\nWith details.",
+                   "inner": { "struct": {
+                       "generics": { "params": [], "where_predicates": [] },
+                       "kind": { "plain": { "fields": [3], "has_stripped_fields": true } },
+                       "impls": [4, 7, 14]
+                   } } },
+            "2": { "id": 2, "name": "inner", "visibility": "public", "docs": null,
+                   "inner": { "module": { "is_crate": false, "items": [12] } } },
+            "3": { "id": 3, "name": "id", "visibility": "public", "docs": "Identifier.",
+                   "inner": { "struct_field": u32_ty } },
+            "4": { "id": 4, "name": null, "visibility": "default", "docs": null,
+                   "inner": { "impl": {
+                       "is_unsafe": false, "generics": { "params": [], "where_predicates": [] },
+                       "trait": null, "for": { "resolved_path": { "path": "Widget", "id": 1, "args": null } },
+                       "items": [11], "is_negative": false, "is_synthetic": false, "blanket_impl": null
+                   } } },
+            "5": { "id": 5, "name": null, "visibility": "public", "docs": null,
+                   "inner": { "use": { "source": "inner::Id", "name": "WidgetId", "id": 12, "is_glob": false } } },
+            "6": { "id": 6, "name": "MAX", "visibility": "public", "docs": "Largest id.",
+                   "inner": { "constant": { "type": u32_ty, "const": { "expr": "10", "value": "10", "is_literal": true } } } },
+            "7": { "id": 7, "name": null, "visibility": "default", "docs": null,
+                   "inner": { "impl": {
+                       "is_unsafe": false, "generics": { "params": [], "where_predicates": [] },
+                       "trait": { "path": "Clone", "id": 100, "args": null },
+                       "for": { "resolved_path": { "path": "Widget", "id": 1, "args": null } },
+                       "items": [], "is_negative": false, "is_synthetic": false, "blanket_impl": null
+                   } } },
+            "8": { "id": 8, "name": "Builder", "visibility": "public", "docs": "Derive a builder.",
+                   "inner": { "proc_macro": { "kind": "derive", "helpers": [] } } },
+            "9": { "id": 9, "name": "Render", "visibility": "public", "docs": "Render to text.",
+                   "inner": { "trait": {
+                       "is_auto": false, "is_unsafe": false, "is_dyn_compatible": true,
+                       "items": [13, 15], "generics": { "params": [], "where_predicates": [] },
+                       "bounds": [], "implementations": [14]
+                   } } },
+            "11": { "id": 11, "name": "new", "visibility": "public", "docs": "Create a widget.\n\nMore details.",
+                    "inner": { "function": {
+                        "sig": { "inputs": [["id", u32_ty]], "output": { "generic": "Self" }, "is_c_variadic": false },
+                        "generics": { "params": [], "where_predicates": [] },
+                        "header": { "is_const": true, "is_unsafe": false, "is_async": false, "abi": "Rust" },
+                        "has_body": true
+                    } } },
+            "12": { "id": 12, "name": "Id", "visibility": "public", "docs": "Widget ids.",
+                    "inner": { "type_alias": { "type": { "primitive": "u64" }, "generics": { "params": [], "where_predicates": [] } } } },
+            "13": { "id": 13, "name": "render", "visibility": "default", "docs": "Render `self`.",
+                    "inner": { "function": {
+                        "sig": {
+                            "inputs": [["self", { "borrowed_ref": { "lifetime": null, "is_mutable": false, "type": { "generic": "Self" } } }]],
+                            "output": { "resolved_path": { "path": "String", "id": 101, "args": null } },
+                            "is_c_variadic": false
+                        },
+                        "generics": { "params": [], "where_predicates": [] },
+                        "header": { "is_const": false, "is_unsafe": false, "is_async": false, "abi": "Rust" },
+                        "has_body": false
+                    } } },
+            "14": { "id": 14, "name": null, "visibility": "default", "docs": null,
+                    "inner": { "impl": {
+                        "is_unsafe": false, "generics": { "params": [], "where_predicates": [] },
+                        "trait": { "path": "Render", "id": 9, "args": null },
+                        "for": { "resolved_path": { "path": "Widget", "id": 1, "args": null } },
+                        "items": [], "provided_trait_methods": ["describe"],
+                        "is_negative": false, "is_synthetic": false, "blanket_impl": null
+                    } } },
+            "15": { "id": 15, "name": "describe", "visibility": "default", "docs": "Describe `self`.",
+                    "inner": { "function": {
+                        "sig": {
+                            "inputs": [["self", { "borrowed_ref": { "lifetime": null, "is_mutable": false, "type": { "generic": "Self" } } }]],
+                            "output": null,
+                            "is_c_variadic": false
+                        },
+                        "generics": { "params": [], "where_predicates": [] },
+                        "header": { "is_const": false, "is_unsafe": false, "is_async": false, "abi": "Rust" },
+                        "has_body": true
+                    } } },
+            "16": { "id": 16, "name": "Shape", "visibility": "public", "docs": "A shape.",
+                    "attrs": ["#[doc(cfg(feature = \"shapes\"))]"],
+                    "inner": { "enum": {
+                        "generics": { "params": [], "where_predicates": [] },
+                        "variants": [17], "has_stripped_variants": false, "impls": []
+                    } } },
+            "17": { "id": 17, "name": "Circle", "visibility": "default", "docs": "A circle by radius.",
+                    "inner": { "variant": { "kind": { "tuple": [18] }, "discriminant": null } } },
+            "18": { "id": 18, "name": "0", "visibility": "default", "docs": null,
+                    "inner": { "struct_field": { "primitive": "f64" } } }
+        },
+        "paths": {
+            "1": { "crate_id": 0, "path": ["demo", "Widget"], "kind": "struct" },
+            "12": { "crate_id": 0, "path": ["demo", "inner", "Id"], "kind": "type_alias" }
+        }
+    })
+}
+
+#[tokio::test]
+async fn test_lookup_item_from_rustdoc_json() {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    std::io::Write::write_all(&mut encoder, rustdoc_fixture().to_string().as_bytes()).unwrap();
+    let body = encoder.finish().unwrap();
+
+    let mut server = mockito::Server::new_async().await;
+    let json_mock = server
+        .mock("GET", "/crate/demo/latest/json.gz")
+        .with_status(200)
+        .with_body(body)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let router = DocRouter::new().with_registries(mock_registries(&server.url()));
+    let lookup = |item_path: &str| {
+        let router = router.clone();
+        let item_path = item_path.to_string();
+        async move {
+            match router
+                .call_tool("lookup_item", json!({ "crate_name": "demo", "item_path": item_path }))
+                .await
+                .unwrap()
+                .remove(0)
+            {
+                Content::Text(text) => text.text,
+                _ => panic!("Expected text content"),
+            }
+        }
+    };
+
+    let widget = lookup("demo::Widget").await;
+    assert!(widget.starts_with("# Struct demo::Widget"));
+    assert!(widget.contains("pub struct Widget {\n    pub id: u32,\n    /* private fields */\n}"));
+    assert!(widget.contains("A widget.\n\nWith details."));
+    assert!(widget.contains("pub const fn new(id: u32) -> Self { ... }\n```\nCreate a widget."));
+    assert!(!widget.contains("More details."));
+    assert!(widget.contains("- `impl Clone for Widget`"));
+    assert!(!widget.contains("<!DOCTYPE"));
+
+    assert!(lookup("WidgetId").await.contains("pub type Id = u64;"));
+    assert!(lookup("inner::Id").await.contains("# Type Alias demo::inner::Id"));
+    assert!(lookup("MAX").await.contains("pub const MAX: u32 = 10;"));
+    assert!(lookup("Builder").await.contains("#[derive(Builder)]"));
+
+    let render = lookup("Render").await;
+    assert!(render.contains("pub trait Render {\n    fn render(&self) -> String;\n    fn describe(&self) { ... }\n}"));
+    assert!(render.contains("- `impl Render for Widget`"));
+
+    let module = lookup("inner").await;
+    assert!(module.contains("## Type Aliases\n\n- `Id`: Widget ids."));
+
+    let new = lookup("Widget::new").await;
+    assert!(new.starts_with("# Associated Function demo::Widget::new\n\nDefined in `impl Widget`"));
+    assert!(new.contains("pub const fn new(id: u32) -> Self\n```"));
+    assert!(new.contains("More details."));
+
+    assert!(lookup("Widget::id").await.contains("# Field demo::Widget::id\n\n```rust\npub id: u32\n```"));
+    assert!(lookup("Render::render").await.starts_with("# Method demo::Render::render"));
+    let describe = lookup("Widget::describe").await;
+    assert!(describe.contains("Defined in `impl Render for Widget`"));
+    assert!(describe.contains("fn describe(&self)"));
+    let id = lookup("Id").await;
+    assert!(id.starts_with("> Resolved `Id` to `inner::Id`\n\n# Type Alias demo::inner::Id"));
+    let circle = lookup("Shape::Circle").await;
+    assert!(circle.contains("# Variant demo::Shape::Circle\n\n```rust\nCircle(f64)\n```\n\nA circle by radius."));
+
+    json_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_rustdoc_json_reuse() {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    std::io::Write::write_all(&mut encoder, rustdoc_fixture().to_string().as_bytes()).unwrap();
+    let body = encoder.finish().unwrap();
+
+    let mut server = mockito::Server::new_async().await;
+    let latest = server
+        .mock("GET", "/crate/demo/latest/json.gz")
+        .with_status(200)
+        .with_body(body)
+        .expect(2)
+        .create_async()
+        .await;
+    let pinned = server.mock("GET", "/crate/demo/0.1.0/json.gz").expect(0).create_async().await;
+    let missing = server
+        .mock("GET", "/crate/other/latest/json.gz")
+        .with_status(404)
+        .expect(2)
+        .create_async()
+        .await;
+
+    let client = RustdocClient::new()
+        .with_base_url(&server.url())
+        .with_latest_ttl(Some(Duration::from_millis(200)));
+    let loads = futures::future::join_all((0..4).map(|_| client.load("demo", None))).await;
+    assert!(loads.iter().all(Option::is_some));
+    assert!(client.load("demo", Some("0.1.0")).await.is_some());
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(client.load("demo", None).await.is_some());
+    assert!(client.load("other", None).await.is_none());
+    assert!(client.load("other", None).await.is_none());
+
+    latest.assert_async().await;
+    pinned.assert_async().await;
+    missing.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_rustdoc_json_size_limit() {
+    let json = rustdoc_fixture().to_string();
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    std::io::Write::write_all(&mut encoder, json.as_bytes()).unwrap();
+    let body = encoder.finish().unwrap();
+    let compressed = body.len() as u64;
+
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/crate/demo/latest/json.gz")
+        .with_status(200)
+        .with_body(body)
+        .create_async()
+        .await;
+
+    let client = |max_bytes: u64| RustdocClient::new().with_base_url(&server.url()).with_max_json_bytes(max_bytes);
+    assert!(client(compressed - 1).load("demo", None).await.is_none());
+    assert!(client(json.len() as u64 - 1).load("demo", None).await.is_none());
+    assert!(client(json.len() as u64).load("demo", None).await.is_some());
+}
+
+#[tokio::test]
+async fn test_lookup_member_from_html_page() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/crate/demo/latest/json.gz")
+        .with_status(404)
+        .create_async()
+        .await;
+    let page = server
+        .mock("GET", "/demo/latest/demo/sync/struct.Sender.html")
+        .with_status(200)
+        .with_body(
+            r#"<html><body><h1>Struct Sender</h1><div class="docblock"><p>Sends values.</p></div>
+            <h2 id="implementations">Implementations</h2>
+            <details class="toggle method-toggle" open><summary><section id="method.send" class="method"><h4 class="code-header">pub async fn send(&amp;self, value: T)</h4></section></summary><div class="docblock"><p>Sends a value, waiting until there is capacity.</p></div></details>
+            <details class="toggle method-toggle" open><summary><section id="method.close" class="method"><h4 class="code-header">pub fn close(&amp;self)</h4></section></summary><div class="docblock"><p>Closes the channel.</p></div></details>
+            </body></html>"#,
+        )
+        .create_async()
+        .await;
+
+    let router = DocRouter::new().with_registries(mock_registries(&server.url()));
+    let result = router
+        .call_tool("lookup_item", json!({ "crate_name": "demo", "item_path": "sync::Sender::send" }))
+        .await
+        .unwrap();
+
+    page.assert_async().await;
+    if let Content::Text(text) = &result[0] {
+        assert!(text.text.starts_with("# sync::Sender::send"));
+        assert!(text.text.contains("pub async fn send"));
+        assert!(text.text.contains("waiting until there is capacity"));
+        assert!(!text.text.contains("Closes the channel"));
+        assert!(!text.text.contains("Sends values."));
+    } else {
+        panic!("Expected text content");
+    }
+}
+
+#[test]
+fn test_crate_item_suggestions() {
+    let items = parse_all_html(
+        r#"<h3 id="structs">Structs</h3><ul class="all-items">
+        <li><a href="sync/mpsc/struct.Sender.html">sync::mpsc::Sender</a></li>
+        <li><a href="sync/oneshot/struct.Sender.html">sync::oneshot::Sender</a></li>
+        <li><a href="sync/struct.Mutex.html">sync::Mutex</a></li></ul>
+        <h3 id="functions">Functions</h3><ul class="all-items">
+        <li><a href="fn.spawn.html">spawn</a></li></ul>
+        <a href="../help.html">Help</a>"#,
+    );
+    assert_eq!(items.len(), 4);
+    assert!(items.contains(&CrateItem { path: "spawn".to_string(), kind: "fn".to_string() }));
+
+    assert_eq!(suggest(&items, "Mutex", 10), Suggestion::Unique("sync::Mutex".to_string()));
+    assert_eq!(suggest(&items, "mpsc::Sender", 10), Suggestion::Unique("sync::mpsc::Sender".to_string()));
+    assert_eq!(suggest(&items, "spwan", 10), Suggestion::Candidates(vec![
+        CrateItem { path: "spawn".to_string(), kind: "fn".to_string() },
+    ]));
+    match suggest(&items, "Sender", 10) {
+        Suggestion::Candidates(candidates) => {
+            assert_eq!(candidates.len(), 2);
+            assert!(candidates.iter().all(|c| c.path.ends_with("::Sender")));
+        }
+        other => panic!("Expected candidates, got {:?}", other),
+    }
+    assert_eq!(suggest(&items, "Unrelated", 10), Suggestion::None);
+}
+
+#[tokio::test]
+async fn test_list_crate_items() {
+    let dir = TempDir::new("items-test");
+    std::fs::write(dir.join("demo.json"), rustdoc_fixture().to_string()).unwrap();
+    let router = DocRouter::new().with_rustdoc_sources(vec![dir.to_path_buf()], false);
+
+    let list = |args: serde_json::Value| {
+        let router = router.clone();
+        async move {
+            match router.call_tool("list_crate_items", args).await.unwrap().remove(0) {
+                Content::Text(text) => text.text,
+                _ => panic!("Expected text content"),
+            }
+        }
+    };
+
+    let all = list(json!({ "crate_name": "demo" })).await;
+    assert!(all.contains("## Modules\n\n- `inner`\n"));
+    assert!(all.contains("## Structs (1)\n\n- `Widget`\n"));
+    assert!(all.contains("## Type Aliases (2)\n\n- `WidgetId`\n- `inner::Id`\n"));
+    assert!(all.contains("## Derive Macros (1)\n\n- `Builder`\n"));
+
+    let inner = list(json!({ "crate_name": "demo", "module": "demo::inner" })).await;
+    assert!(inner.starts_with("# Items in `demo` under `inner`"));
+    assert!(inner.contains("- `inner::Id`"));
+    assert!(!inner.contains("Widget"));
+
+    let structs = list(json!({ "crate_name": "demo", "kind": "structs" })).await;
+    assert!(structs.contains("- `Widget`"));
+    assert!(!structs.contains("Modules"));
+    assert!(!structs.contains("inner::Id"));
+}
+
+#[test]
+fn test_render_inventory_module_tree() {
+    let items = parse_all_html(
+        r#"<a href="sync/mpsc/struct.Sender.html">sync::mpsc::Sender</a>
+        <a href="sync/struct.Mutex.html">sync::Mutex</a>
+        <a href="fn.spawn.html">spawn</a>"#,
+    );
+
+    let tree = render_inventory("tokio", &items, None, Some("module"), 100);
+    assert!(tree.contains("## Modules\n\n- `sync`\n  - `mpsc`\n"));
+    assert!(!tree.contains("spawn"));
+
+    let limited = render_inventory("tokio", &items, None, None, 2);
+    assert!(limited.contains("3 more entries not shown"));
+
+    let empty = render_inventory("tokio", &items, Some("fs"), None, 100);
+    assert!(empty.contains("No matching items found."));
+}
+
+#[tokio::test]
+async fn test_lookup_item_suggests_similar_paths() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/crate/demo/latest/json.gz")
+        .with_status(404)
+        .create_async()
+        .await;
+    let all_items = server
+        .mock("GET", "/demo/latest/demo/all.html")
+        .with_status(200)
+        .with_body(
+            r#"<ul class="all-items">
+            <li><a href="sync/mpsc/struct.Sender.html">sync::mpsc::Sender</a></li>
+            <li><a href="sync/mpsc/struct.Receiver.html">sync::mpsc::Receiver</a></li>
+            <li><a href="sync/oneshot/struct.Receiver.html">sync::oneshot::Receiver</a></li></ul>"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/demo/latest/demo/sync/mpsc/struct.Sender.html")
+        .with_status(200)
+        .with_body("<html><body><h1>Struct Sender</h1><p>Sends values to a Receiver.</p></body></html>")
+        .create_async()
+        .await;
+
+    let router = DocRouter::new().with_registries(mock_registries(&server.url()));
+
+    let result = router
+        .call_tool("lookup_item", json!({ "crate_name": "demo", "item_path": "Sender" }))
+        .await
+        .unwrap();
+    if let Content::Text(text) = &result[0] {
+        assert!(text.text.starts_with("> Resolved `Sender` to `sync::mpsc::Sender`"));
+        assert!(text.text.contains("Sends values to a Receiver."));
+    } else {
+        panic!("Expected text content");
+    }
+
+    let result = router
+        .call_tool("lookup_item", json!({ "crate_name": "demo", "item_path": "Receiver" }))
+        .await;
+    match result {
+        Err(ToolError::ExecutionError(msg)) => {
+            assert!(msg.contains("No matching item found"));
+            assert!(msg.contains("- `sync::mpsc::Receiver` (struct)"));
+            assert!(msg.contains("- `sync::oneshot::Receiver` (struct)"));
+        }
+        other => panic!("Expected candidates, got {:?}", other.map(|_| ())),
+    }
+
+    all_items.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_lookup_item_from_local_rustdoc_json() {
+    let dir = TempDir::new("rustdoc-test");
+    std::fs::write(dir.join("demo.json"), rustdoc_fixture().to_string()).unwrap();
+
+    let router = DocRouter::new().with_rustdoc_sources(vec![dir.to_path_buf()], false);
+    let doc = router
+        .rustdoc_client
+        .lookup_item("demo", "Widget", Some("0.1.0"))
+        .await
+        .unwrap();
+    assert!(doc.contains("pub struct Widget"));
+
+    assert!(router.rustdoc_client.lookup_item("demo", "Widget", Some("0.2.0")).await.is_none());
+    assert!(router.rustdoc_client.lookup_item("demo", "Missing", None).await.is_none());
 }
 
 #[tokio::test]
@@ -198,7 +970,7 @@ async fn test_lookup_crate_uses_cache() {
     router
         .cache
         .set(
-            "test_crate".to_string(),
+            "rust:crate:test_crate".to_string(),
             "Cached documentation for test_crate".to_string(),
         )
         .await;
@@ -229,7 +1001,7 @@ async fn test_lookup_item_uses_cache() {
     router
         .cache
         .set(
-            "test_crate:test::path".to_string(),
+            "rust:item:test_crate:test::path".to_string(),
             "Cached documentation for test_crate::test::path".to_string(),
         )
         .await;
@@ -486,7 +1258,7 @@ async fn test_mocked_success_case() {
     router
         .cache
         .set(
-            "serde".to_string(),
+            "rust:crate:serde".to_string(),
             "# Serde\n\nA framework for serializing and deserializing Rust data structures."
                 .to_string(),
         )
@@ -516,7 +1288,7 @@ async fn test_mocked_success_case() {
 async fn test_cache_key_generation() {
     let router = DocRouter::new();
 
-    let item_key = "tokio:sync::Mutex";
+    let item_key = "rust:item:tokio:sync::Mutex";
     router
         .cache
         .set(item_key.to_string(), "Mutex documentation".to_string())
@@ -548,7 +1320,7 @@ async fn test_version_in_cache_key() {
     router
         .cache
         .set(
-            "tokio:1.2.3".to_string(),
+            "rust:crate:tokio:1.2.3".to_string(),
             "Tokio 1.2.3 documentation".to_string(),
         )
         .await;
@@ -610,3 +1382,793 @@ async fn test_cache_consistency_during_concurrent_access() {
         result
     );
 }
+
+const DEMO_INDEX: &str = r#"{"name":"demo","vers":"0.1.0","deps":[{"name":"serde","req":"^1","features":[],"optional":true,"default_features":true,"target":null,"kind":"normal"}],"features":{"default":["std"],"std":[]},"yanked":false}
+{"name":"demo","vers":"0.2.0","deps":[{"name":"serde","req":"^1","features":[],"optional":true,"default_features":true,"target":null,"kind":"normal"},{"name":"tokio","req":"^1.30","features":[],"optional":true,"default_features":false,"target":"cfg(unix)","kind":"normal"}],"features":{"default":["std"],"std":["serde?/std"],"shapes":[]},"features2":{"async":["dep:tokio","tokio/rt"]},"yanked":false}
+{"name":"demo","vers":"0.2.1","deps":[],"features":{},"yanked":true}
+{"name":"demo","vers":"0.3.0-beta.1","deps":[],"features":{},"yanked":false}"#;
+
+#[test]
+fn test_crate_features_from_index() {
+    assert_eq!(index_path("a"), "1/a");
+    assert_eq!(index_path("cc"), "2/cc");
+    assert_eq!(index_path("syn"), "3/s/syn");
+    assert_eq!(index_path("Serde"), "se/rd/serde");
+
+    let entries = parse_index(DEMO_INDEX);
+    assert_eq!(entries.len(), 4);
+    assert_eq!(select_version(&entries, None).unwrap().vers, "0.2.0");
+    assert_eq!(select_version(&entries, Some("0.1")).unwrap().vers, "0.1.0");
+    assert_eq!(select_version(&entries, Some("0.3.0-beta.1")).unwrap().vers, "0.3.0-beta.1");
+    assert!(select_version(&entries, Some("2")).is_none());
+
+    let features = select_version(&entries, None).unwrap().render_features(None);
+    assert!(features.starts_with("# Features of `demo` 0.2.0\n\n**Default features:** `std`\n"));
+    assert!(features.contains("| `async` | optional dependency `tokio`, feature `rt` of `tokio` |"));
+    assert!(features.contains("| `std` | feature `std` of `serde` (if enabled) |"));
+    assert!(features.contains("| `shapes` | — |"));
+    assert!(features.contains("| `serde` (implicit) | optional dependency `serde` |"));
+    assert!(features.contains("- `tokio` `^1.30` on `cfg(unix)` — enabled by `async`"));
+    assert!(features.contains("- `serde` `^1` — enabled by `serde`"));
+    assert!(!features.contains("Items gated"));
+
+    assert_eq!(doc_cfg_features(r#"#[doc(cfg(all(feature = "fs", feature="rt")))]"#), vec!["fs", "rt"]);
+    assert!(doc_cfg_features(r#"#[cfg(feature = "fs")]"#).is_empty());
+}
+
+#[tokio::test]
+async fn test_lookup_crate_features() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/de/mo/demo")
+        .with_status(200)
+        .with_body(DEMO_INDEX)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let dir = TempDir::new("features-test");
+    std::fs::write(dir.join("demo.json"), rustdoc_fixture().to_string()).unwrap();
+    let download = server.mock("GET", "/crate/demo/0.2.0/json.gz").expect(0).create_async().await;
+    let router = DocRouter::new()
+        .with_registries(mock_registries(&server.url()))
+        .with_rustdoc_sources(vec![dir.to_path_buf()], true);
+
+    let lookup = |args: serde_json::Value| {
+        let router = router.clone();
+        async move {
+            match router.call_tool("lookup_crate_features", args).await.unwrap().remove(0) {
+                Content::Text(text) => text.text,
+                _ => panic!("Expected text content"),
+            }
+        }
+    };
+
+    let page = server
+        .mock("GET", "/index/latest/index/struct.demo.html")
+        .with_status(200)
+        .with_body("<html><body><main><h1>Struct demo</h1></main></body></html>")
+        .create_async()
+        .await;
+    let item = router
+        .call_tool("lookup_item", json!({ "crate_name": "index", "item_path": "demo" }))
+        .await;
+    assert!(item.is_ok());
+    page.assert_async().await;
+
+    let pinned = lookup(json!({ "crate_name": "demo", "version": "0.1.0" })).await;
+    assert!(pinned.starts_with("# Features of `demo` 0.1.0"));
+    assert!(pinned.contains("## Items gated by features\n\n### `shapes`\n\n- `Shape`\n"));
+
+    let latest = lookup(json!({ "crate_name": "demo" })).await;
+    assert!(latest.starts_with("# Features of `demo` 0.2.0"));
+    assert!(!latest.contains("Items gated"));
+    download.assert_async().await;
+
+    let restarted = DocRouter::with_cache(router.cache.clone()).with_registries(mock_registries(&server.url()));
+    let cached = match restarted
+        .call_tool("lookup_crate_features", json!({ "crate_name": "demo", "version": "0.1.0" }))
+        .await
+        .unwrap()
+        .remove(0)
+    {
+        Content::Text(text) => text.text,
+        _ => panic!("Expected text content"),
+    };
+    assert_eq!(cached, pinned);
+
+    let missing = router
+        .call_tool("lookup_crate_features", json!({ "crate_name": "demo", "version": "9.0" }))
+        .await;
+    assert!(matches!(missing, Err(ToolError::ExecutionError(msg)) if msg.contains("No version of `demo` matches `9.0`")));
+
+    for name in ["", "é1", "../demo"] {
+        let invalid = router.call_tool("lookup_crate_features", json!({ "crate_name": name })).await;
+        assert!(matches!(invalid, Err(ToolError::InvalidParameters(_))), "{:?}", name);
+    }
+
+    mock.assert_async().await;
+}
+
+fn cargo_project() -> TempDir {
+    let dir = TempDir::new("project-test");
+    std::fs::create_dir_all(dir.join("crates/app")).unwrap();
+    std::fs::write(
+        dir.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.dependencies]\nserde = \"1\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("crates/app/Cargo.toml"),
+        "[package]\nname = \"app\"\n\n[dependencies]\nrandom = { version = \"0.7\", package = \"rand\" }\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("Cargo.lock"),
+        r#"version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+
+[[package]]
+name = "rand"
+version = "0.7.3"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "rand"
+version = "0.8.5"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "serde"
+version = "1.0.197"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#,
+    )
+    .unwrap();
+    dir
+}
+
+#[test]
+fn test_project_locked_crate_versions() {
+    let dir = cargo_project();
+    let project = Project::new(dir.path()).with_recheck_interval(Duration::ZERO);
+    let debounced = Project::new(dir.path());
+    assert_eq!(debounced.locked(Ecosystem::Rust, "serde").unwrap().version, "1.0.197");
+
+    let serde = project.locked(Ecosystem::Rust, "serde").unwrap();
+    assert_eq!(serde, LockedVersion { version: "1.0.197".to_string(), source: "Cargo.lock".to_string() });
+    assert_eq!(serde.note("serde"), "> Using version 1.0.197 of `serde` (locked in Cargo.lock)");
+    assert_eq!(project.locked(Ecosystem::Rust, "rand").unwrap().version, "0.7.3");
+    assert_eq!(project.locked(Ecosystem::Rust, "app"), None);
+
+    let lock = std::fs::read_to_string(dir.join("Cargo.lock")).unwrap();
+    std::fs::write(dir.join("Cargo.lock"), lock.replace("1.0.197", "1.0.198")).unwrap();
+    let stamp = std::fs::metadata(dir.join("Cargo.toml")).unwrap().modified().unwrap();
+    let file = std::fs::File::options().write(true).open(dir.join("Cargo.lock")).unwrap();
+    file.set_modified(stamp + Duration::from_secs(5)).unwrap();
+    assert_eq!(project.locked(Ecosystem::Rust, "serde").unwrap().version, "1.0.198");
+    assert_eq!(debounced.locked(Ecosystem::Rust, "serde").unwrap().version, "1.0.197");
+}
+
+#[tokio::test]
+async fn test_lookup_crate_uses_locked_version() {
+    let mut server = mockito::Server::new_async().await;
+    let pinned = server
+        .mock("GET", "/crate/serde/1.0.197/")
+        .with_status(200)
+        .with_body("<h1>serde 1.0.197</h1>")
+        .expect(1)
+        .create_async()
+        .await;
+    let explicit = server
+        .mock("GET", "/crate/serde/1.0.100/")
+        .with_status(200)
+        .with_body("<h1>serde 1.0.100</h1>")
+        .expect(1)
+        .create_async()
+        .await;
+
+    let dir = cargo_project();
+    let router = DocRouter::new()
+        .with_registries(mock_registries(&server.url()))
+        .with_project_root(dir.to_path_buf());
+
+    let text = |contents: Vec<Content>| match contents.into_iter().next() {
+        Some(Content::Text(text)) => text.text,
+        _ => panic!("Expected text content"),
+    };
+
+    let doc = text(router.call_tool("lookup_crate", json!({ "crate_name": "serde" })).await.unwrap());
+    assert!(doc.starts_with("> Using version 1.0.197 of `serde` (locked in Cargo.lock)\n\n"));
+    assert!(doc.contains("serde 1.0.197"));
+
+    let doc = text(
+        router
+            .call_tool("lookup_crate", json!({ "crate_name": "serde", "version": "1.0.100" }))
+            .await
+            .unwrap(),
+    );
+    assert!(!doc.contains("Using version"));
+    assert!(doc.contains("serde 1.0.100"));
+
+    pinned.assert_async().await;
+    explicit.assert_async().await;
+}
+
+#[test]
+fn test_lockfile_parsers() {
+    let root = std::path::Path::new("/nonexistent");
+    let sorted = |mut versions: Vec<(String, String)>| {
+        versions.sort();
+        versions
+    };
+    let pair = |name: &str, version: &str| (name.to_string(), version.to_string());
+
+    let package_lock = r#"{"lockfileVersion": 3, "packages": {
+        "": {"name": "app"},
+        "node_modules/react": {"version": "18.2.0"},
+        "node_modules/@types/node": {"version": "20.11.5"},
+        "node_modules/react/node_modules/loose-envify": {"version": "1.4.0"},
+        "node_modules/local": {"resolved": "../local", "link": true}}}"#;
+    assert_eq!(
+        sorted(parse_package_lock(package_lock, root)),
+        vec![pair("@types/node", "20.11.5"), pair("react", "18.2.0")]
+    );
+
+    let pnpm_lock = "lockfileVersion: '9.0'
+
+importers:
+
+  .:
+    dependencies:
+      react:
+        specifier: ^18.2.0
+        version: 18.2.0
+      '@tanstack/query':
+        specifier: ^5.0.0
+        version: 5.17.9(react@18.2.0)
+    devDependencies:
+      typescript:
+        specifier: ~5.3.0
+        version: 5.3.3
+
+  packages/web:
+    dependencies:
+      vue:
+        specifier: ^3
+        version: 3.4.0
+
+packages:
+
+  react@18.2.0:
+    resolution: {integrity: sha512-abc}
+";
+    assert_eq!(
+        sorted(parse_pnpm_lock(pnpm_lock, root)),
+        vec![pair("@tanstack/query", "5.17.9"), pair("react", "18.2.0"), pair("typescript", "5.3.3")]
+    );
+    let pnpm_v5 = "lockfileVersion: 5.4\n\nspecifiers:\n  react: ^17\n\ndependencies:\n  react: 17.0.2_abc\n";
+    assert_eq!(parse_pnpm_lock(pnpm_v5, root), vec![pair("react", "17.0.2")]);
+
+    let yarn_lock = r#"# THIS IS AN AUTOGENERATED FILE.
+
+"@babel/core@^7.0.0", "@babel/core@^7.12.3":
+  version "7.23.7"
+  resolved "https://registry.yarnpkg.com/@babel/core/-/core-7.23.7.tgz"
+
+lodash@^4.17.21:
+  version "4.17.21"
+
+lodash@^3.0.0:
+  version "3.10.1"
+"#;
+    assert_eq!(
+        sorted(parse_yarn_lock(yarn_lock, root)),
+        vec![pair("@babel/core", "7.23.7"), pair("lodash", "4.17.21")]
+    );
+    let berry = "__metadata:\n  version: 6\n\n\"lodash@npm:^4.17.21\":\n  version: 4.17.21\n";
+    assert_eq!(parse_yarn_lock(berry, root), vec![pair("lodash", "4.17.21")]);
+
+    let uv_lock = r#"version = 1
+
+[[package]]
+name = "app"
+version = "0.1.0"
+source = { editable = "." }
+
+[[package]]
+name = "requests"
+version = "2.31.0"
+source = { registry = "https://pypi.org/simple" }
+"#;
+    assert_eq!(parse_python_lock(uv_lock, root), vec![pair("requests", "2.31.0")]);
+
+    let requirements = "# pinned\nDjango==4.2.9 \\\n    --hash=sha256:abc\nrequests[socks]==2.31.0 ; python_version >= \"3.8\"\nflask>=2\n-r dev.txt\n";
+    assert_eq!(
+        parse_requirements(requirements, root),
+        vec![pair("Django", "4.2.9"), pair("requests", "2.31.0")]
+    );
+
+    let go_mod = "module example.com/app\n\ngo 1.21\n\nrequire github.com/spf13/cobra v1.8.0\n\nrequire (\n\tgolang.org/x/sync v0.6.0 // indirect\n\tgithub.com/stretchr/testify v1.8.4\n)\n";
+    assert_eq!(
+        sorted(parse_go_mod(go_mod, root)),
+        vec![
+            pair("github.com/spf13/cobra", "v1.8.0"),
+            pair("github.com/stretchr/testify", "v1.8.4"),
+            pair("golang.org/x/sync", "v0.6.0"),
+        ]
+    );
+    let go_sum = "github.com/pkg/errors v0.8.1/go.mod h1:a=\ngithub.com/pkg/errors v0.9.1 h1:b=\ngithub.com/pkg/errors v0.9.1/go.mod h1:c=\n";
+    assert_eq!(parse_go_sum(go_sum, root), vec![pair("github.com/pkg/errors", "v0.9.1")]);
+}
+
+#[test]
+fn test_project_files_read_are_stamped() {
+    let dir = TempDir::new("stamps-test");
+    std::fs::create_dir_all(dir.join("crates/core")).unwrap();
+    std::fs::write(dir.join("Cargo.toml"), "[workspace]\nmembers = [\"crates/*\"]\n").unwrap();
+    std::fs::write(dir.join("package.json"), r#"{"dependencies": {"react": "^18"}}"#).unwrap();
+    std::fs::write(dir.join("requirements.txt"), "requests\n").unwrap();
+    std::fs::write(dir.join("go.mod"), "module example.com/app\n\nrequire github.com/spf13/cobra v1.8.0\n").unwrap();
+
+    let (_, files) = LockedVersions::read(&dir);
+    for read in ["package.json", "requirements.txt", "go.mod", "crates", "crates/core/Cargo.toml"] {
+        assert!(files.contains(&dir.join(read)), "{} is not stamped", read);
+    }
+    assert_eq!(files.iter().filter(|path| path.ends_with("package.json")).count(), 1);
+}
+
+#[tokio::test]
+async fn test_project_locked_package_versions() {
+    let dir = TempDir::new("lockfiles-test");
+    std::fs::write(dir.join("package-lock.json"), r#"{"packages": {"node_modules/react": {"version": "18.2.0"}}}"#).unwrap();
+    std::fs::write(dir.join("yarn.lock"), "react@^17:\n  version \"17.0.2\"\n").unwrap();
+    std::fs::write(dir.join("requirements.txt"), "Flask_SQLAlchemy==3.1.1\n").unwrap();
+    std::fs::write(dir.join("go.mod"), "module example.com/app\n\nrequire github.com/spf13/cobra v1.8.0\n").unwrap();
+    let project = Project::new(dir.path()).with_recheck_interval(Duration::ZERO);
+
+    let react = project.locked(Ecosystem::Npm, "react").unwrap();
+    assert_eq!(react, LockedVersion { version: "18.2.0".to_string(), source: "package-lock.json".to_string() });
+    assert_eq!(project.locked(Ecosystem::PyPI, "flask-sqlalchemy").unwrap().version, "3.1.1");
+    let cobra = project.locked(Ecosystem::Go, "github.com/spf13/cobra/doc").unwrap();
+    assert_eq!(cobra.version, "v1.8.0");
+    assert_eq!(project.locked(Ecosystem::Go, "github.com/spf13/cobrax"), None);
+    assert_eq!(project.locked(Ecosystem::Rust, "react"), None);
+
+    std::fs::write(dir.join("package.json"), r#"{"dependencies": {"react": "^18"}, "devDependencies": {"vitest": "^1"}}"#).unwrap();
+    std::fs::write(dir.join("pyproject.toml"), "[project]\ndependencies = [\"flask-sqlalchemy>=3\", \"requests[socks]; python_version > '3'\"]\n").unwrap();
+    let direct: Vec<(String, Option<String>)> = project
+        .dependencies()
+        .into_iter()
+        .map(|dep| (format!("{}/{}", dep.ecosystem.scheme(), dep.name), dep.locked.map(|l| l.version)))
+        .collect();
+    assert_eq!(direct, vec![
+        ("npm/react".to_string(), Some("18.2.0".to_string())),
+        ("npm/vitest".to_string(), None),
+        ("pypi/flask-sqlalchemy".to_string(), Some("3.1.1".to_string())),
+        ("pypi/requests".to_string(), None),
+        ("go/github.com/spf13/cobra".to_string(), Some("v1.8.0".to_string())),
+    ]);
+
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/react/18.2.0")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"name": "react", "version": "18.2.0", "description": "React"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let router = DocRouter::new()
+        .with_registries(mock_registries(&server.url()))
+        .with_project_root(dir.to_path_buf());
+    match router.call_tool("lookup_npm_package", json!({ "package_name": "react" })).await.unwrap().remove(0) {
+        Content::Text(text) => {
+            assert!(text.text.starts_with("> Using version 18.2.0 of `react` (locked in package-lock.json)\n\n"))
+        }
+        _ => panic!("Expected text content"),
+    }
+    mock.assert_async().await;
+}
+
+#[test]
+fn test_doc_uri_parsing() {
+    let uri = DocUri::parse("docs://rust/serde@1.0.197").unwrap();
+    assert_eq!(uri, DocUri {
+        ecosystem: Ecosystem::Rust,
+        package: "serde".to_string(),
+        version: Some("1.0.197".to_string()),
+        item: None,
+    });
+    assert_eq!(uri.to_string(), "docs://rust/serde@1.0.197");
+
+    let scoped = DocUri::parse("docs://npm/@types/node@20.11.5").unwrap();
+    assert_eq!(scoped.package, "@types/node");
+    assert_eq!(scoped.version.as_deref(), Some("20.11.5"));
+    assert_eq!(DocUri::parse("docs://npm/@types/node").unwrap().version, None);
+    assert_eq!(DocUri::parse("docs://go/github.com/spf13/cobra@v1.8.0").unwrap().package, "github.com/spf13/cobra");
+
+
+    let item = DocUri::parse("docs://rust/tokio/latest/sync::Mutex").unwrap();
+    assert_eq!((item.package.as_str(), item.version.as_deref(), item.item.as_deref()), ("tokio", None, Some("sync::Mutex")));
+    assert_eq!(item.to_string(), "docs://rust/tokio/latest/sync::Mutex");
+    assert_eq!(DocUri::parse("docs://rust/tokio/1.37.0/").unwrap().item, None);
+
+    let symbol = DocUri::parse("docs://go/net/http#Client").unwrap();
+    assert_eq!((symbol.package.as_str(), symbol.item.as_deref()), ("net/http", Some("Client")));
+    let pinned = DocUri::parse("docs://go/github.com/spf13/cobra@v1.8.0#Command").unwrap();
+    assert_eq!(pinned.version.as_deref(), Some("v1.8.0"));
+    assert_eq!(pinned.to_string(), "docs://go/github.com/spf13/cobra@v1.8.0#Command");
+
+    assert_eq!(DocUri::parse("docs://ruby/rails"), None);
+    assert_eq!(DocUri::parse("docs://rust/"), None);
+    assert_eq!(DocUri::parse("https://docs.rs/serde"), None);
+}
+
+#[tokio::test]
+async fn test_project_dependency_resources() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/crate/serde/1.0.197/")
+        .with_status(200)
+        .with_body("<h1>serde 1.0.197</h1>")
+        .expect(1)
+        .create_async()
+        .await;
+
+    let dir = cargo_project();
+    std::fs::write(dir.join("go.mod"), "module example.com/app\n\nrequire (\n\tgithub.com/spf13/cobra v1.8.0\n\tgolang.org/x/sync v0.6.0 // indirect\n)\n").unwrap();
+    let router = DocRouter::new()
+        .with_registries(mock_registries(&server.url()))
+        .with_project_root(dir.to_path_buf());
+
+    let resources = router.list_resources();
+    let uris: Vec<&str> = resources.iter().map(|r| r.uri.as_str()).collect();
+    assert_eq!(uris, vec!["docs://rust/serde@1.0.197", "docs://rust/rand@0.7.3", "docs://go/github.com/spf13/cobra@v1.8.0"]);
+    assert_eq!(resources[0].name, "serde 1.0.197");
+    assert_eq!(resources[0].description.as_deref(), Some("Rust crate documentation (locked in Cargo.lock)"));
+
+    let doc = router.read_resource("docs://rust/serde@1.0.197").await.unwrap();
+    assert!(doc.contains("serde 1.0.197"));
+    let doc = router.read_resource("docs://rust/serde").await.unwrap();
+    assert!(doc.starts_with("> Using version 1.0.197 of `serde` (locked in Cargo.lock)"));
+    assert!(matches!(router.read_resource("docs://ruby/rails").await, Err(ResourceError::NotFound(_))));
+
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_resource_templates() {
+    use mcp_core::protocol::JsonRpcRequest;
+    use tower_service::Service;
+
+    let mut service = DocService::new(DocRouter::new());
+    let response = service
+        .call(JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(7),
+            method: "resources/templates/list".to_string(),
+            params: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(response.id, Some(7));
+    let result = response.result.unwrap();
+    let templates: Vec<&str> = result["resourceTemplates"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["uriTemplate"].as_str().unwrap())
+        .collect();
+    assert!(templates.contains(&"docs://rust/{crate}/{version}/{item_path}"));
+    assert!(templates.contains(&"docs://npm/{package}@{version}"));
+    assert!(templates.contains(&"docs://pypi/{package}"));
+    assert!(templates.contains(&"docs://go/{module}#{symbol}"));
+
+    for template in service.router().list_resource_templates() {
+        let uri = template
+            .uri_template
+            .replace("{crate}", "serde")
+            .replace("{version}", "1.0.0")
+            .replace("{item_path}", "de::Deserialize")
+            .replace("{package}", "react")
+            .replace("{module}", "net/http")
+            .replace("{symbol}", "Client");
+        assert!(DocUri::parse(&uri).is_some(), "{} does not parse", uri);
+    }
+}
+
+#[tokio::test]
+async fn test_read_item_resource() {
+    let dir = TempDir::new("resource-test");
+    std::fs::write(dir.join("demo.json"), rustdoc_fixture().to_string()).unwrap();
+    let router = DocRouter::new().with_rustdoc_sources(vec![dir.to_path_buf()], false);
+
+    let doc = router.read_resource("docs://rust/demo/latest/Widget::new").await.unwrap();
+    assert!(doc.contains("fn new"));
+}
+
+#[tokio::test]
+async fn test_prompt_templates() {
+    let router = DocRouter::new();
+    let names: Vec<String> = router.list_prompts().into_iter().map(|p| p.name).collect();
+    assert_eq!(names, ["explain_crate", "migrate_versions", "compare_packages", "find_api"]);
+
+    let spec = prompts::find("migrate_versions").unwrap();
+    let arguments = json!({ "package": "tokio", "from_version": "0.2", "to_version": "1.0" });
+    let text = spec.instructions(arguments.as_object().unwrap()).unwrap();
+    assert!(text.starts_with("Help migrate code using `tokio` from version 0.2 to 1.0."));
+
+    let missing = spec.instructions(json!({ "package": "tokio", "to_version": " " }).as_object().unwrap());
+    assert_eq!(missing, Err("Missing required argument: from_version".to_string()));
+
+    assert_eq!(prompts::parse_ecosystem(Some("Python")), Ok(Ecosystem::PyPI));
+    assert!(prompts::parse_ecosystem(Some("cobol")).is_err());
+    assert_eq!(prompts::truncate("abcdef", 3), "abc\n\n_(documentation truncated)_");
+    assert_eq!(prompts::truncate("abc", 3), "abc");
+
+    assert!(router.get_prompt("find_api").await.unwrap().contains("{task}"));
+    assert!(router.get_prompt("test").await.is_err());
+}
+
+#[tokio::test]
+async fn test_get_prompt_embeds_documentation() {
+    use mcp_core::protocol::JsonRpcRequest;
+    use tower_service::Service;
+
+    let server = mockito::Server::new_async().await;
+    let dir = TempDir::new("prompt-test");
+    std::fs::write(dir.join("demo.json"), rustdoc_fixture().to_string()).unwrap();
+    let router = DocRouter::new()
+        .with_registries(mock_registries(&server.url()))
+        .with_rustdoc_sources(vec![dir.to_path_buf()], false);
+    let mut service = DocService::new(router);
+
+    let mut get_prompt = |params: serde_json::Value| {
+        service.call(JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(3),
+            method: "prompts/get".to_string(),
+            params: Some(params),
+        })
+    };
+
+    let response = get_prompt(json!({
+        "name": "find_api",
+        "arguments": { "package": "demo", "version": "0.1.0", "task": "create a widget" }
+    }))
+    .await
+    .unwrap();
+    let result = response.result.unwrap();
+    assert_eq!(result["messages"][0]["role"], "user");
+    let text = result["messages"][0]["content"]["text"].as_str().unwrap();
+    assert!(text.starts_with("Find the API in `demo` that does the following: create a widget"));
+    assert!(text.contains("## Public items"));
+    assert!(text.contains("Widget"));
+
+    let response = get_prompt(json!({ "name": "find_api", "arguments": { "package": "demo" } }))
+        .await
+        .unwrap();
+    let error = response.error.unwrap();
+    assert_eq!(error.code, -32602);
+    assert!(error.message.contains("task"));
+
+    let response = get_prompt(json!({ "name": "nonexistent" })).await.unwrap();
+    assert!(response.result.is_none());
+    assert!(response.error.is_some());
+}
+
+#[test]
+fn test_completion_matching() {
+    let names = ["serde_json", "serde", "toml-serde", "serde"].map(String::from);
+    let completion = Completion::matching(names, "Serde");
+    assert_eq!(completion.values, ["serde_json", "serde", "toml-serde"]);
+    assert_eq!(completion.total, 3);
+    assert!(!completion.has_more);
+
+    let many = (0..150).map(|i| format!("pkg{}", i));
+    let completion = Completion::matching(many, "pkg");
+    assert_eq!(completion.values.len(), 100);
+    assert_eq!(completion.total, 150);
+    assert!(completion.has_more);
+
+    let items = (0..100_000).map(|i| format!("windows::Win32::item{}", i / 2));
+    let completion = Completion::matching(items, "item");
+    assert_eq!(completion.total, 50_000);
+    assert_eq!(completion.values[0], "windows::Win32::item0");
+
+    assert_eq!(last_entry("tokio, asy"), ("tokio, ", "asy"));
+    assert_eq!(last_entry("tok"), ("", "tok"));
+
+    let reference = CompletionRef::parse(&json!({ "type": "ref/resource", "uri": "docs://npm/{package}@{version}" })).unwrap();
+    assert_eq!(reference.ecosystem(), Some(Ecosystem::Npm));
+    let reference = CompletionRef::parse(&json!({ "type": "ref/prompt", "name": "find_api" })).unwrap();
+    assert_eq!(reference, CompletionRef::Prompt("find_api".to_string()));
+    assert_eq!(reference.ecosystem(), None);
+}
+
+#[tokio::test]
+async fn test_complete_arguments() {
+    use mcp_core::protocol::JsonRpcRequest;
+    use tower_service::Service;
+
+    let mut server = mockito::Server::new_async().await;
+    let crates = server
+        .mock("GET", "/api/v1/crates")
+        .match_query(mockito::Matcher::UrlEncoded("q".into(), "ser".into()))
+        .with_status(200)
+        .with_body(r#"{"crates":[{"name":"serde"},{"name":"serde_json"},{"name":"ser-io"}]}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/-/v1/search")
+        .match_query(mockito::Matcher::UrlEncoded("text".into(), "rea".into()))
+        .with_status(200)
+        .with_body(r#"{"objects":[{"package":{"name":"react"}},{"package":{"name":"react-dom"}}]}"#)
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/search/")
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_body(r#"<a class="package-snippet"><span class="package-snippet__name">requests</span></a>"#)
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/search")
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_body(r#"<h2><a href="/net/http" data-gtmc="search result" data-test-id="snippet-title">http</a></h2>"#)
+        .create_async()
+        .await;
+
+    let dir = TempDir::new("complete-test");
+    std::fs::write(dir.join("demo.json"), rustdoc_fixture().to_string()).unwrap();
+    let router = DocRouter::new()
+        .with_registries(mock_registries(&server.url()))
+        .with_rustdoc_sources(vec![dir.to_path_buf()], false);
+
+    let prompt = CompletionRef::Prompt("find_api".to_string());
+    let empty = serde_json::Map::new();
+    let values = |completion: Completion| completion.values;
+
+    assert_eq!(values(router.complete(&prompt, "crate_name", "ser", &empty).await), ["serde", "serde_json", "ser-io"]);
+    assert_eq!(values(router.complete(&prompt, "crate_name", "ser", &empty).await).len(), 3);
+    crates.assert_async().await;
+
+    let npm = json!({ "ecosystem": "npm" });
+    assert_eq!(
+        values(router.complete(&prompt, "packages", "vue, rea", npm.as_object().unwrap()).await),
+        ["vue, react", "vue, react-dom"]
+    );
+    let pypi = CompletionRef::Resource("docs://pypi/{package}".to_string());
+    assert_eq!(values(router.complete(&pypi, "package", "req", &empty).await), ["requests"]);
+    let go = CompletionRef::Resource("docs://go/{module}@{version}".to_string());
+    assert_eq!(values(router.complete(&go, "module", "net/h", &empty).await), ["net/http"]);
+    assert_eq!(values(router.complete(&prompt, "ecosystem", "py", &empty).await), ["pypi"]);
+
+    let item = CompletionRef::Resource("docs://rust/{crate}/{version}/{item_path}".to_string());
+    let context = json!({ "crate": "demo", "version": "0.1.0" });
+    let items = values(router.complete(&item, "item_path", "Wid", context.as_object().unwrap()).await);
+    assert!(items.contains(&"Widget".to_string()));
+    assert!(values(router.complete(&item, "item_path", "Wid", &empty).await).is_empty());
+
+    let mut service = DocService::new(router);
+    let response = service
+        .call(JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(5),
+            method: "completion/complete".to_string(),
+            params: Some(json!({
+                "ref": { "type": "ref/prompt", "name": "compare_packages" },
+                "argument": { "name": "ecosystem", "value": "g" }
+            })),
+        })
+        .await
+        .unwrap();
+    let result = response.result.unwrap();
+    assert_eq!(result["completion"]["values"], json!(["go"]));
+    assert_eq!(result["completion"]["hasMore"], json!(false));
+}
+
+#[tokio::test]
+async fn test_complete_without_index_search() {
+    let mut server = mockito::Server::new_async().await;
+    let npm = server
+        .mock("GET", "/-/v1/search")
+        .match_query(mockito::Matcher::AllOf(vec![
+            mockito::Matcher::UrlEncoded("text".into(), "@types/no&de".into()),
+            mockito::Matcher::UrlEncoded("size".into(), "50".into()),
+        ]))
+        .with_status(200)
+        .with_body(r#"{"objects":[{"package":{"name":"@types/no&de"}}]}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let search = server
+        .mock("GET", "/search/")
+        .match_query(mockito::Matcher::Any)
+        .with_status(404)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let dir = TempDir::new("complete-index-test");
+    std::fs::write(dir.join("requirements.txt"), "requests==2.31.0\nrich==13.7.0\n").unwrap();
+    let router = DocRouter::new()
+        .with_registries(mock_registries(&server.url()))
+        .with_project_root(dir.to_path_buf());
+
+    let npm_ref = CompletionRef::Resource("docs://npm/{package}".to_string());
+    let empty = serde_json::Map::new();
+    assert_eq!(router.complete(&npm_ref, "package", "@types/no&de", &empty).await.values, ["@types/no&de"]);
+    npm.assert_async().await;
+
+    let pypi = CompletionRef::Resource("docs://pypi/{package}".to_string());
+    assert_eq!(router.complete(&pypi, "package", "re", &empty).await.values, ["requests"]);
+    assert_eq!(router.complete(&pypi, "package", "ri", &empty).await.values, ["rich"]);
+    search.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_lookup_reports_progress() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", mockito::Matcher::Any)
+        .with_status(404)
+        .create_async()
+        .await;
+    let router = DocRouter::new()
+        .with_registries(mock_registries(&server.url()))
+        .with_rustdoc_sources(vec![], false);
+
+    let (messages, mut received) = tokio::sync::mpsc::unbounded_channel();
+    let progress = Progress::new(json!(7), messages);
+    let result = progress
+        .scope(router.call_tool("lookup_item", json!({ "crate_name": "demo", "item_path": "Widget" })))
+        .await;
+    assert!(result.is_err());
+
+    let mut steps = Vec::new();
+    while let Ok(message) = received.try_recv() {
+        assert_eq!(message["params"]["progressToken"], 7);
+        steps.push(message["params"]["message"].as_str().unwrap().to_string());
+    }
+    assert_eq!(steps[0], "Trying struct page for `Widget`");
+    assert!(steps.contains(&"Trying macro page for `Widget`".to_string()));
+
+    crate::tools::docs::progress::report("ignored");
+}
+
+#[tokio::test]
+async fn test_metrics_record_tool_calls() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", mockito::Matcher::Any)
+        .with_status(404)
+        .create_async()
+        .await;
+    let router = DocRouter::new()
+        .with_registries(mock_registries(&server.url()))
+        .with_rustdoc_sources(vec![], false);
+
+    assert!(router.call_tool("lookup_item", json!({ "crate_name": "demo", "item_path": "Widget" })).await.is_err());
+    assert!(router.call_tool("no_such_tool", json!({})).await.is_err());
+
+    let mut rendered = String::new();
+    metrics().render(&mut rendered);
+    assert!(rendered.contains("# TYPE devdocs_tool_calls_total counter"));
+    assert!(rendered.contains("devdocs_tool_calls_total{tool=\"lookup_item\",outcome=\"error\"}"));
+    assert!(rendered.contains("devdocs_tool_calls_total{tool=\"unknown\",outcome=\"error\"}"));
+    assert!(!rendered.contains("no_such_tool"));
+    let listed: Vec<String> = router.list_tools().into_iter().map(|tool| tool.name).collect();
+    assert_eq!(listed, crate::tools::docs::docs::TOOL_NAMES);
+    assert!(rendered.contains("devdocs_upstream_request_duration_seconds_bucket{host=\"127.0.0.1\",le=\"+Inf\"}"));
+    assert!(rendered.contains("devdocs_upstream_request_duration_seconds_count{host=\"127.0.0.1\"}"));
+
+    assert_eq!(escape("a\"b\\c\n"), "a\\\"b\\\\c\\n");
+}
@@ -1,4 +1,4 @@
 pub mod docs;
 
-pub use docs::DocRouter;
+pub use docs::{DocRouter, DocService};
 pub use docs::docs::DocCache;
\ No newline at end of file
@@ -0,0 +1,235 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+
+use crate::transport::http_sse_server::App;
+
+/// Request headers MCP clients send, always allowed in preflight requests
+const MCP_HEADERS: &str = "authorization, content-type, last-event-id, mcp-protocol-version, mcp-session-id";
+
+/// Response headers browsers let cross-origin scripts read
+const EXPOSED_HEADERS: &str = "mcp-session-id, www-authenticate";
+
+const ALLOWED_METHODS: &str = "GET, POST, DELETE, OPTIONS";
+
+/// Host names of the loopback interface
+const LOOPBACK_HOSTS: [&str; 3] = ["localhost", "127.0.0.1", "[::1]"];
+
+/// `[http.cors]` section of the config file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsConfig {
+    /// Origins of the web clients allowed to connect, e.g. `https://inspector.example.com`,
+    /// besides those served from the loopback interface; `"*"` allows every origin
+    pub allowed_origins: Vec<String>,
+    /// Request headers allowed besides the ones MCP uses
+    pub allowed_headers: Vec<String>,
+    /// Let browsers send cookies and TLS client certificates along; ignored with `"*"`
+    pub allow_credentials: bool,
+    /// Seconds browsers may cache a preflight response
+    pub max_age_secs: u64,
+    /// Host names requests may be addressed to; once set, or when listening on a loopback
+    /// address, requests naming another host are refused
+    pub allowed_hosts: Vec<String>,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_headers: Vec::new(),
+            allow_credentials: false,
+            max_age_secs: 600,
+            allowed_hosts: Vec::new(),
+        }
+    }
+}
+
+/// Cross-origin policy of the HTTP server
+///
+/// A request carrying an `Origin` the policy does not allow is refused, so a web page
+/// cannot reach the server through a visitor's browser. Pages served from the loopback
+/// interface are always allowed. With [`Cors::loopback_only`], requests naming another
+/// host are refused as well, which stops DNS rebinding attacks on a local server.
+#[derive(Debug, Clone)]
+pub struct Cors {
+    any_origin: bool,
+    /// Allowed origins, lowercased and without a trailing slash
+    origins: Vec<String>,
+    /// Value of `Access-Control-Allow-Headers`
+    allowed_headers: String,
+    allow_credentials: bool,
+    max_age_secs: u64,
+    /// Accepted host names without their port, or `None` to accept any
+    hosts: Option<Vec<String>>,
+}
+
+impl Default for Cors {
+    fn default() -> Self {
+        Self::from_config(&CorsConfig::default())
+    }
+}
+
+impl Cors {
+    /// Build the policy described by `config`
+    pub fn from_config(config: &CorsConfig) -> Self {
+        let mut cors = Self {
+            any_origin: false,
+            origins: Vec::new(),
+            allowed_headers: MCP_HEADERS.to_string(),
+            allow_credentials: config.allow_credentials,
+            max_age_secs: config.max_age_secs,
+            hosts: None,
+        };
+        for origin in &config.allowed_origins {
+            cors = cors.with_origin(origin);
+        }
+        for name in &config.allowed_headers {
+            cors = cors.with_header(name);
+        }
+        for host in &config.allowed_hosts {
+            cors = cors.with_host(host);
+        }
+        cors
+    }
+
+    /// Allow web clients served from `origin`, or from any origin for `"*"`
+    ///
+    /// Allowing any origin turns off credentials, which would otherwise let every site make
+    /// requests on a visitor's behalf.
+    pub fn with_origin(mut self, origin: &str) -> Self {
+        match origin.trim() {
+            "*" => {
+                if self.allow_credentials {
+                    tracing::warn!("ignoring allow_credentials, which cannot be combined with allowing any origin");
+                    self.allow_credentials = false;
+                }
+                self.any_origin = true;
+            }
+            origin => self.origins.push(origin.trim_end_matches('/').to_ascii_lowercase()),
+        }
+        self
+    }
+
+    /// Allow browsers to send the request header `name`
+    ///
+    /// Names that are not valid header names are ignored.
+    pub fn with_header(mut self, name: &str) -> Self {
+        match HeaderName::from_bytes(name.trim().as_bytes()) {
+            Ok(name) => {
+                self.allowed_headers.push_str(", ");
+                self.allowed_headers.push_str(name.as_str());
+            }
+            Err(_) => tracing::warn!(name, "ignoring invalid CORS header name"),
+        }
+        self
+    }
+
+    /// Accept requests whose `Host` header names `host`
+    ///
+    /// Once a host is added, requests naming any other host are refused.
+    pub fn with_host(mut self, host: &str) -> Self {
+        self.hosts
+            .get_or_insert_with(Vec::new)
+            .push(strip_port(host.trim()).to_ascii_lowercase());
+        self
+    }
+
+    /// Refuse requests whose `Host` header names neither the loopback interface nor a host
+    /// added with [`Cors::with_host`]
+    pub fn loopback_only(mut self) -> Self {
+        self.hosts
+            .get_or_insert_with(Vec::new)
+            .extend(LOOPBACK_HOSTS.iter().map(|host| host.to_string()));
+        self
+    }
+
+    /// Whether a page served from `origin` may call the server
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        if self.any_origin {
+            return true;
+        }
+        let origin = origin.trim_end_matches('/').to_ascii_lowercase();
+        if self.origins.contains(&origin) {
+            return true;
+        }
+        origin
+            .strip_prefix("http://")
+            .or_else(|| origin.strip_prefix("https://"))
+            .is_some_and(|authority| LOOPBACK_HOSTS.contains(&strip_port(authority)))
+    }
+
+    /// Whether a request may carry the `Host` header `host`, or none at all
+    pub fn allows_host(&self, host: Option<&str>) -> bool {
+        match (&self.hosts, host) {
+            (None, _) => true,
+            (Some(hosts), Some(host)) => hosts.iter().any(|allowed| allowed.eq_ignore_ascii_case(strip_port(host))),
+            (Some(_), None) => false,
+        }
+    }
+
+    fn add_headers(&self, headers: &mut HeaderMap, origin: HeaderValue) {
+        headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+        if self.allow_credentials {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_CREDENTIALS, HeaderValue::from_static("true"));
+        }
+        headers.append(header::VARY, HeaderValue::from_static("origin"));
+    }
+}
+
+/// `host` without a trailing `:port`, keeping IPv6 literals in brackets whole
+fn strip_port(host: &str) -> &str {
+    match host.rsplit_once(':') {
+        Some((name, port)) if !name.contains(':') || name.ends_with(']') => match port.parse::<u16>() {
+            Ok(_) => name,
+            Err(_) => host,
+        },
+        _ => host,
+    }
+}
+
+/// Middleware enforcing the [`Cors`] policy and answering preflight requests
+///
+/// Runs before authentication, since browsers send preflight requests without credentials.
+///
+/// Requests without an `Origin` pass through, as browsers send one with every
+/// cross-origin request.
+pub(crate) async fn apply_cors(State(app): State<App>, request: Request, next: Next) -> Response {
+    let host = request.headers().get(header::HOST).and_then(|v| v.to_str().ok());
+    if !app.cors.allows_host(host) {
+        tracing::debug!(?host, "refused request for a host that is not allowed");
+        return (StatusCode::FORBIDDEN, "Host not allowed").into_response();
+    }
+
+    let Some(origin) = request.headers().get(header::ORIGIN).cloned() else {
+        return next.run(request).await;
+    };
+    if !origin.to_str().is_ok_and(|origin| app.cors.allows_origin(origin)) {
+        tracing::debug!(?origin, "refused request from an origin that is not allowed");
+        return (StatusCode::FORBIDDEN, "Origin not allowed").into_response();
+    }
+
+    let preflight =
+        request.method() == Method::OPTIONS && request.headers().contains_key(header::ACCESS_CONTROL_REQUEST_METHOD);
+    if preflight {
+        let mut response = StatusCode::NO_CONTENT.into_response();
+        let headers = response.headers_mut();
+        app.cors.add_headers(headers, origin);
+        headers.insert(header::ACCESS_CONTROL_ALLOW_METHODS, HeaderValue::from_static(ALLOWED_METHODS));
+        if let Ok(allowed) = HeaderValue::from_str(&app.cors.allowed_headers) {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_HEADERS, allowed);
+        }
+        headers.insert(header::ACCESS_CONTROL_MAX_AGE, HeaderValue::from(app.cors.max_age_secs));
+        return response;
+    }
+
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    app.cors.add_headers(headers, origin);
+    headers.insert(header::ACCESS_CONTROL_EXPOSE_HEADERS, HeaderValue::from_static(EXPOSED_HEADERS));
+    response
+}
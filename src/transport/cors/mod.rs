@@ -0,0 +1,7 @@
+mod cors;
+
+pub use cors::{Cors, CorsConfig};
+pub(crate) use cors::apply_cors;
+
+#[cfg(test)]
+mod tests;
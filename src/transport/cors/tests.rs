@@ -0,0 +1,147 @@
+use crate::config::Config;
+use crate::test_support::serve;
+use crate::transport::cors::{Cors, CorsConfig};
+use crate::transport::http_auth::HttpAuth;
+use crate::transport::http_sse_server::App;
+use reqwest::{Client, Method, StatusCode};
+use serde_json::json;
+
+#[test]
+fn test_origin_and_host_rules() {
+    let cors = Cors::default();
+    assert!(cors.allows_origin("http://localhost:6274"));
+    assert!(cors.allows_origin("http://127.0.0.1"));
+    assert!(cors.allows_origin("https://[::1]:8443"));
+    assert!(!cors.allows_origin("http://localhost.evil.example"));
+    assert!(!cors.allows_origin("https://evil.example"));
+    assert!(!cors.allows_origin("null"));
+    assert!(cors.allows_host(Some("evil.example")));
+    assert!(cors.allows_host(None));
+
+    let cors = Cors::from_config(&CorsConfig {
+        allowed_origins: vec!["https://App.example.com/".to_string()],
+        allowed_hosts: vec!["devdocs.internal:8080".to_string()],
+        ..CorsConfig::default()
+    });
+    assert!(cors.allows_origin("https://app.example.com"));
+    assert!(!cors.allows_origin("http://app.example.com"));
+    assert!(cors.allows_host(Some("devdocs.internal")));
+    assert!(!cors.allows_host(Some("localhost:8080")));
+
+    let cors = cors.loopback_only();
+    assert!(cors.allows_host(Some("localhost:8080")));
+    assert!(cors.allows_host(Some("[::1]:8080")));
+    assert!(cors.allows_host(Some("DEVDOCS.internal:9000")));
+    assert!(!cors.allows_host(Some("evil.example:8080")));
+    assert!(!cors.allows_host(None));
+
+    assert!(Cors::default().with_origin("*").allows_origin("https://evil.example"));
+}
+
+#[test]
+fn test_cors_config() {
+    let cors = Config::parse("[http.cors]\nallowed_origins = [\"https://app.example.com\"]\nallow_credentials = true")
+        .unwrap()
+        .http
+        .cors();
+    assert!(cors.allows_origin("https://app.example.com"));
+    assert!(!cors.allows_origin("https://evil.example"));
+    assert!(cors.allows_host(Some("evil.example")));
+}
+
+#[tokio::test]
+async fn test_no_credentials_for_any_origin() {
+    let send = |cors: Cors| async move {
+        let base = serve(App::new().with_cors(cors)).await;
+        Client::new()
+            .get(format!("{}/healthz", base))
+            .header("origin", "https://evil.example")
+            .send()
+            .await
+            .unwrap()
+    };
+    let credentials = |origins: &[&str]| CorsConfig {
+        allowed_origins: origins.iter().map(|origin| origin.to_string()).collect(),
+        allow_credentials: true,
+        ..CorsConfig::default()
+    };
+
+    let response = send(Cors::from_config(&credentials(&["*"]))).await;
+    assert_eq!(response.headers()["access-control-allow-origin"], "https://evil.example");
+    assert!(!response.headers().contains_key("access-control-allow-credentials"));
+
+    let response = send(Cors::from_config(&credentials(&["https://evil.example"]))).await;
+    assert_eq!(response.headers()["access-control-allow-credentials"], "true");
+}
+
+#[tokio::test]
+async fn test_cross_origin_requests() {
+    let cors = Cors::default()
+        .with_origin("https://app.example.com")
+        .with_header("x-trace-id")
+        .loopback_only();
+    let app = App::new().with_auth(HttpAuth::default().with_token("secret")).with_cors(cors);
+    let base = serve(app).await;
+    let client = Client::new();
+    let origin = "https://app.example.com";
+
+    let response = client
+        .request(Method::OPTIONS, format!("{}/mcp", base))
+        .header("origin", origin)
+        .header("access-control-request-method", "POST")
+        .header("access-control-request-headers", "authorization, content-type")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let headers = response.headers();
+    assert_eq!(headers["access-control-allow-origin"], origin);
+    assert!(headers["access-control-allow-methods"].to_str().unwrap().contains("POST"));
+    let allowed = headers["access-control-allow-headers"].to_str().unwrap();
+    assert!(allowed.contains("mcp-session-id") && allowed.ends_with("x-trace-id"));
+    assert_eq!(headers["access-control-max-age"], "600");
+    assert!(!headers.contains_key("access-control-allow-credentials"));
+
+    let initialize = json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} });
+    let response = client
+        .post(format!("{}/mcp", base))
+        .header("origin", origin)
+        .bearer_auth("secret")
+        .json(&initialize)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["access-control-allow-origin"], origin);
+    assert!(response.headers()["access-control-expose-headers"]
+        .to_str()
+        .unwrap()
+        .contains("mcp-session-id"));
+
+    let response = client.get(format!("{}/sse", base)).header("origin", origin).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(response.headers()["access-control-allow-origin"], origin);
+
+    let response = client
+        .post(format!("{}/mcp", base))
+        .header("origin", "https://evil.example")
+        .bearer_auth("secret")
+        .json(&initialize)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(!response.headers().contains_key("access-control-allow-origin"));
+
+    let response = client
+        .get(format!("{}/sse", base))
+        .header("host", "evil.example:8080")
+        .bearer_auth("secret")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = client.get(format!("{}/sse", base)).bearer_auth("secret").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
@@ -0,0 +1,227 @@
+use std::{
+    fmt,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::transport::http_sse_server::App;
+
+/// Who opened a connection or session, e.g. `token:1a2b3c4d` or `jwt:alice`
+pub type Identity = Arc<str>;
+
+/// Seconds of clock skew tolerated when checking `exp` and `nbf`
+const CLOCK_SKEW_SECS: u64 = 60;
+
+/// `[http.auth]` section of the config file
+///
+/// Authentication is enabled as soon as a token or a JWT secret is configured.
+/// The `Debug` implementation never prints the secrets themselves.
+#[derive(Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpAuthConfig {
+    /// Static bearer tokens accepted by the server
+    pub tokens: Vec<String>,
+    /// Secret HS256-signed JWTs are verified with; their `sub` claim names the caller
+    pub jwt_secret: Option<String>,
+    /// `iss` claim JWTs must carry
+    pub jwt_issuer: Option<String>,
+    /// `aud` claim JWTs must carry
+    pub jwt_audience: Option<String>,
+}
+
+impl fmt::Debug for HttpAuthConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpAuthConfig")
+            .field("tokens", &format!("<{} redacted>", self.tokens.len()))
+            .field("jwt_secret", &self.jwt_secret.as_ref().map(|_| "<redacted>"))
+            .field("jwt_issuer", &self.jwt_issuer)
+            .field("jwt_audience", &self.jwt_audience)
+            .finish()
+    }
+}
+
+/// Bearer-token check applied to every route of the HTTP server
+///
+/// The default accepts every caller without an identity.
+#[derive(Clone, Default)]
+pub struct HttpAuth {
+    /// SHA-256 digests of the accepted static tokens
+    tokens: Vec<[u8; 32]>,
+    jwt: Option<JwtVerifier>,
+}
+
+#[derive(Clone)]
+struct JwtVerifier {
+    secret: Vec<u8>,
+    issuer: Option<String>,
+    audience: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JwtHeader {
+    alg: String,
+}
+
+#[derive(Deserialize)]
+struct Claims {
+    sub: Option<String>,
+    exp: Option<u64>,
+    nbf: Option<u64>,
+    iss: Option<String>,
+    aud: Option<Value>,
+}
+
+impl HttpAuth {
+    /// Build the check described by `config`
+    ///
+    /// Tokens from the comma-separated `DEVDOCS_HTTP_TOKENS` are accepted alongside the
+    /// configured ones, and `DEVDOCS_HTTP_JWT_SECRET` is used when no secret is configured.
+    pub fn from_config(config: &HttpAuthConfig) -> Self {
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let env_tokens = env("DEVDOCS_HTTP_TOKENS").unwrap_or_default();
+        let tokens = config
+            .tokens
+            .iter()
+            .map(String::as_str)
+            .chain(env_tokens.split(','))
+            .map(str::trim)
+            .filter(|token| !token.is_empty());
+
+        let mut auth = Self::default();
+        for token in tokens {
+            auth = auth.with_token(token);
+        }
+        match config.jwt_secret.clone().filter(|s| !s.is_empty()).or_else(|| env("DEVDOCS_HTTP_JWT_SECRET")) {
+            Some(secret) => auth.with_jwt_secret(secret, config.jwt_issuer.clone(), config.jwt_audience.clone()),
+            None => auth,
+        }
+    }
+
+    /// Accept `token` as a bearer token
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.tokens.push(Sha256::digest(token.as_bytes()).into());
+        self
+    }
+
+    /// Accept JWTs signed with HS256 and `secret`, optionally requiring their `iss` and `aud`
+    pub fn with_jwt_secret(mut self, secret: impl Into<Vec<u8>>, issuer: Option<String>, audience: Option<String>) -> Self {
+        self.jwt = Some(JwtVerifier {
+            secret: secret.into(),
+            issuer,
+            audience,
+        });
+        self
+    }
+
+    /// Whether callers have to present a token at all
+    pub fn is_enabled(&self) -> bool {
+        !self.tokens.is_empty() || self.jwt.is_some()
+    }
+
+    /// Identity of the caller presenting `headers`, or `None` when authentication is disabled
+    ///
+    /// Static tokens are compared by their SHA-256 digests, which have equal length, so
+    /// the comparison reveals nothing about the tokens.
+    pub fn authenticate(&self, headers: &HeaderMap) -> Result<Option<Identity>, String> {
+        if !self.is_enabled() {
+            return Ok(None);
+        }
+        let token = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer ").or_else(|| v.strip_prefix("bearer ")))
+            .map(str::trim)
+            .ok_or_else(|| "Missing bearer token".to_string())?;
+
+        let digest: [u8; 32] = Sha256::digest(token.as_bytes()).into();
+        if self.tokens.contains(&digest) {
+            let id: String = digest[..4].iter().map(|b| format!("{:02x}", b)).collect();
+            return Ok(Some(Arc::from(format!("token:{}", id))));
+        }
+        match &self.jwt {
+            Some(jwt) if token.split('.').count() == 3 => jwt.verify(token).map(Some),
+            _ => Err("Unknown bearer token".to_string()),
+        }
+    }
+}
+
+impl JwtVerifier {
+    fn verify(&self, token: &str) -> Result<Identity, String> {
+        let (signed, signature) = token.rsplit_once('.').ok_or("Malformed JWT")?;
+        let (header, payload) = signed.split_once('.').ok_or("Malformed JWT")?;
+        let decode = |part: &str| URL_SAFE_NO_PAD.decode(part).map_err(|e| format!("Malformed JWT: {}", e));
+
+        let header: JwtHeader =
+            serde_json::from_slice(&decode(header)?).map_err(|e| format!("Malformed JWT header: {}", e))?;
+        if header.alg != "HS256" {
+            return Err(format!("Unsupported JWT algorithm {}", header.alg));
+        }
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret).map_err(|e| e.to_string())?;
+        mac.update(signed.as_bytes());
+        mac.verify_slice(&decode(signature)?)
+            .map_err(|_| "Invalid JWT signature".to_string())?;
+
+        let claims: Claims =
+            serde_json::from_slice(&decode(payload)?).map_err(|e| format!("Malformed JWT claims: {}", e))?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        if claims.exp.is_some_and(|exp| exp.saturating_add(CLOCK_SKEW_SECS) <= now) {
+            return Err("JWT has expired".to_string());
+        }
+        if claims.nbf.is_some_and(|nbf| nbf > now.saturating_add(CLOCK_SKEW_SECS)) {
+            return Err("JWT is not valid yet".to_string());
+        }
+        if let Some(issuer) = &self.issuer {
+            if claims.iss.as_ref() != Some(issuer) {
+                return Err("JWT issuer does not match".to_string());
+            }
+        }
+        if let Some(audience) = &self.audience {
+            let matches = match &claims.aud {
+                Some(Value::String(aud)) => aud == audience,
+                Some(Value::Array(auds)) => auds.iter().any(|aud| aud.as_str() == Some(audience)),
+                _ => false,
+            };
+            if !matches {
+                return Err("JWT audience does not match".to_string());
+            }
+        }
+        match claims.sub.filter(|sub| !sub.is_empty()) {
+            Some(sub) => Ok(Arc::from(format!("jwt:{}", sub))),
+            None => Err("JWT has no subject".to_string()),
+        }
+    }
+}
+
+/// Identity of the caller of the current request, added by [`require_auth`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Caller(pub Option<Identity>);
+
+/// Middleware rejecting requests without a valid bearer token with `401 Unauthorized`
+pub(crate) async fn require_auth(State(app): State<App>, mut request: Request, next: Next) -> Response {
+    match app.auth.authenticate(request.headers()) {
+        Ok(identity) => {
+            request.extensions_mut().insert(Caller(identity));
+            next.run(request).await
+        }
+        Err(reason) => {
+            tracing::debug!(%reason, path = %request.uri().path(), "unauthorized request");
+            let mut response = (StatusCode::UNAUTHORIZED, reason).into_response();
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            response
+        }
+    }
+}
@@ -0,0 +1,7 @@
+mod http_auth;
+
+pub use http_auth::{Caller, HttpAuth, HttpAuthConfig, Identity};
+pub(crate) use http_auth::require_auth;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,148 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::test_support::serve;
+use crate::transport::http_auth::{HttpAuth, HttpAuthConfig};
+use crate::transport::http_sse_server::App;
+use crate::transport::streamable_http::SESSION_HEADER;
+use axum::http::{header, HeaderMap, HeaderValue};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Value};
+use sha2::Sha256;
+
+fn bearer(token: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::AUTHORIZATION, HeaderValue::from_str(&format!("Bearer {}", token)).unwrap());
+    headers
+}
+
+fn jwt(secret: &str, header: Value, claims: Value) -> String {
+    let signed = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(header.to_string()),
+        URL_SAFE_NO_PAD.encode(claims.to_string())
+    );
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(signed.as_bytes());
+    format!("{}.{}", signed, URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes()))
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+#[test]
+fn test_static_tokens() {
+    let disabled = HttpAuth::default();
+    assert!(!disabled.is_enabled());
+    assert_eq!(disabled.authenticate(&HeaderMap::new()), Ok(None));
+
+    let auth = HttpAuth::from_config(&HttpAuthConfig {
+        tokens: vec!["alpha".to_string(), "beta".to_string()],
+        ..HttpAuthConfig::default()
+    });
+    assert!(auth.is_enabled());
+    assert!(auth.authenticate(&HeaderMap::new()).is_err());
+    assert!(auth.authenticate(&bearer("gamma")).is_err());
+
+    let alpha = auth.authenticate(&bearer("alpha")).unwrap().unwrap();
+    let beta = auth.authenticate(&bearer("beta")).unwrap().unwrap();
+    assert!(alpha.starts_with("token:"));
+    assert!(!alpha.contains("alpha"));
+    assert_ne!(alpha, beta);
+    assert_eq!(auth.authenticate(&bearer("alpha")).unwrap().unwrap(), alpha);
+}
+
+#[test]
+fn test_auth_config() {
+    let config = Config::parse("[http.auth]\ntokens = [\"secret-token\"]").unwrap();
+    assert_eq!(config.http.auth.tokens, vec!["secret-token".to_string()]);
+    assert!(!format!("{:?}", config.http.auth).contains("secret-token"));
+    assert!(config.http.auth().is_enabled());
+    assert!(!Config::parse("").unwrap().http.auth().is_enabled());
+}
+
+#[test]
+fn test_jwt_verification() {
+    let auth = HttpAuth::default().with_jwt_secret("secret", Some("issuer".to_string()), Some("devdocs".to_string()));
+    let hs256 = json!({ "alg": "HS256", "typ": "JWT" });
+    let claims = json!({ "sub": "alice", "iss": "issuer", "aud": ["other", "devdocs"], "exp": now() + 600 });
+
+    let token = jwt("secret", hs256.clone(), claims.clone());
+    assert_eq!(auth.authenticate(&bearer(&token)).unwrap().as_deref(), Some("jwt:alice"));
+
+    assert!(auth.authenticate(&bearer(&jwt("other", hs256.clone(), claims.clone()))).is_err());
+    assert!(auth
+        .authenticate(&bearer(&jwt("secret", json!({ "alg": "none" }), claims.clone())))
+        .is_err());
+    let expired = json!({ "sub": "alice", "iss": "issuer", "aud": "devdocs", "exp": now() - 3600 });
+    assert!(auth.authenticate(&bearer(&jwt("secret", hs256.clone(), expired))).is_err());
+    let far_future = json!({ "sub": "alice", "iss": "issuer", "aud": "devdocs", "exp": u64::MAX, "nbf": u64::MAX - 30 });
+    assert!(auth.authenticate(&bearer(&jwt("secret", hs256.clone(), far_future))).is_err());
+    let no_expiry = json!({ "sub": "alice", "iss": "issuer", "aud": "devdocs", "exp": u64::MAX });
+    assert!(auth.authenticate(&bearer(&jwt("secret", hs256.clone(), no_expiry))).is_ok());
+    let other_audience = json!({ "sub": "alice", "iss": "issuer", "aud": "other" });
+    assert!(auth.authenticate(&bearer(&jwt("secret", hs256.clone(), other_audience))).is_err());
+    let no_subject = json!({ "iss": "issuer", "aud": "devdocs" });
+    assert!(auth.authenticate(&bearer(&jwt("secret", hs256.clone(), no_subject))).is_err());
+
+    let mut parts: Vec<String> = token.split('.').map(String::from).collect();
+    parts[1] = URL_SAFE_NO_PAD.encode(json!({ "sub": "mallory", "iss": "issuer", "aud": "devdocs" }).to_string());
+    assert!(auth.authenticate(&bearer(&parts.join("."))).is_err());
+}
+
+#[tokio::test]
+async fn test_sessions_bound_to_identity() {
+    let auth = HttpAuth::default().with_token("alice-token").with_token("bob-token");
+    let base = serve(App::new().with_auth(auth)).await;
+    let client = Client::new();
+    let initialize = json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} });
+    let templates = json!({ "jsonrpc": "2.0", "id": 2, "method": "resources/templates/list" });
+
+    let response = client.post(format!("{}/mcp", base)).json(&initialize).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(response.headers()["www-authenticate"], "Bearer");
+    let response = client.get(format!("{}/sse", base)).bearer_auth("eve-token").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = client
+        .post(format!("{}/mcp", base))
+        .bearer_auth("alice-token")
+        .json(&initialize)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let session = response.headers()[SESSION_HEADER].to_str().unwrap().to_string();
+    let send = |token: &'static str| {
+        client
+            .post(format!("{}/mcp", base))
+            .bearer_auth(token)
+            .header(SESSION_HEADER, &session)
+            .json(&templates)
+            .send()
+    };
+    assert_eq!(send("bob-token").await.unwrap().status(), StatusCode::NOT_FOUND);
+    assert_eq!(send("alice-token").await.unwrap().status(), StatusCode::OK);
+
+    let mut response = client.get(format!("{}/sse", base)).bearer_auth("alice-token").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let endpoint = String::from_utf8(response.chunk().await.unwrap().unwrap().to_vec()).unwrap();
+    let query = endpoint
+        .lines()
+        .find_map(|line| line.strip_prefix("data:"))
+        .unwrap()
+        .trim()
+        .to_string();
+    let post = |token: &'static str| {
+        client
+            .post(format!("{}/sse{}", base, query))
+            .bearer_auth(token)
+            .json(&templates)
+            .send()
+    };
+    assert_eq!(post("bob-token").await.unwrap().status(), StatusCode::NOT_FOUND);
+    assert_eq!(post("alice-token").await.unwrap().status(), StatusCode::ACCEPTED);
+}
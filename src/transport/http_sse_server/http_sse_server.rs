@@ -1,50 +1,285 @@
 use axum::{
     body::Body,
-    extract::{Query, State},
+    extract::{Extension, Query, State},
     http::StatusCode,
-    response::sse::{Event, Sse},
-    routing::get,
+    middleware,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::{get, post},
+    serve::Listener,
     Router,
 };
 use futures::{Stream, StreamExt, TryStreamExt};
-use mcp_server::{ByteTransport, Server};
-use std::collections::HashMap;
-use tokio_util::codec::FramedRead;
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Weak,
+    },
+    time::{Duration, Instant},
+};
+use tokio_util::{codec::FramedRead, sync::CancellationToken};
 
 #[cfg(test)]
 // Tests in ../tests.rs
 
 use anyhow::Result;
-use mcp_server::router::RouterService;
-use crate::{transport::jsonrpc_frame_codec::JsonRpcFrameCodec, tools::DocRouter};
+use crate::{
+    transport::{
+        cors::{self, Cors},
+        http_auth::{self, Caller, HttpAuth, Identity},
+        jsonrpc_frame_codec::JsonRpcFrameCodec,
+        jsonrpc_server::JsonRpcServer,
+        monitoring,
+        streamable_http::{self, Sessions},
+    },
+    tools::{DocRouter, DocService},
+};
 use std::sync::Arc;
 use tokio::{
     io::{self, AsyncWriteExt},
-    sync::Mutex,
+    sync::{Mutex, RwLock},
 };
 
 type C2SWriter = Arc<Mutex<io::WriteHalf<io::SimplexStream>>>;
-type SessionId = Arc<str>;
+type SseSessions = Arc<RwLock<HashMap<SessionId, SseSession>>>;
+pub(crate) type SessionId = Arc<str>;
+
+/// Input of one `/sse` session and the identity that opened it
+#[derive(Clone)]
+pub struct SseSession {
+    pub owner: Option<Identity>,
+    pub(crate) writer: C2SWriter,
+    pub(crate) activity: Arc<Activity>,
+}
+
+impl SseSession {
+    /// End the session's input, which stops its server once the running requests answer
+    /// and with it the event stream
+    ///
+    /// The write half of a simplex stream only signals the end when shut down, not when
+    /// dropped.
+    pub async fn close(&self) {
+        let _ = self.writer.lock().await.shutdown().await;
+    }
+}
+
+/// Limits on the sessions of the HTTP server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionLimits {
+    /// Sessions open at once across `/sse`, `/mcp` and `/ws`; more are refused with `503 Service Unavailable`
+    pub max_sessions: Option<usize>,
+    /// Sessions that receive no request for this long are closed
+    pub idle_timeout: Option<Duration>,
+    /// Interval between keep-alive comments on SSE streams
+    pub keep_alive: Option<Duration>,
+}
+
+impl Default for SessionLimits {
+    fn default() -> Self {
+        Self {
+            max_sessions: Some(256),
+            idle_timeout: Some(Duration::from_secs(30 * 60)),
+            keep_alive: Some(Duration::from_secs(15)),
+        }
+    }
+}
+
+/// When a session last received a request
+pub(crate) struct Activity(std::sync::Mutex<Instant>);
+
+impl Default for Activity {
+    fn default() -> Self {
+        Self(std::sync::Mutex::new(Instant::now()))
+    }
+}
+
+impl Activity {
+    pub(crate) fn touch(&self) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+    }
+
+    fn idle(&self) -> Duration {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).elapsed()
+    }
+}
+
+/// Wait until the session tracked by `activity` has received no request for `timeout`
+///
+/// Returns `false` if the session was dropped first.
+pub(crate) async fn until_idle(activity: Weak<Activity>, timeout: Duration) -> bool {
+    loop {
+        let Some(idle) = activity.upgrade().map(|activity| activity.idle()) else {
+            return false;
+        };
+        if idle >= timeout {
+            return true;
+        }
+        tokio::time::sleep(timeout - idle).await;
+    }
+}
 
 #[derive(Clone, Default)]
 pub struct App {
-    pub txs: Arc<tokio::sync::RwLock<HashMap<SessionId, C2SWriter>>>,
+    pub txs: SseSessions,
+    /// Sessions of the Streamable HTTP endpoint
+    pub sessions: Sessions,
+    /// Documentation router shared by every session, so caches and connection pools are reused
+    pub docs: DocRouter,
+    /// Bearer-token check applied to every route
+    pub auth: HttpAuth,
+    /// Origins and hosts browsers may reach the server from
+    pub cors: Cors,
+    pub limits: SessionLimits,
+    /// Number of open WebSocket connections
+    pub websockets: Arc<AtomicUsize>,
+    /// Cancelled once the server starts shutting down
+    pub shutdown: CancellationToken,
 }
 
 impl App {
     pub fn new() -> Self {
+        Self::with_router(DocRouter::new())
+    }
+
+    /// Create an app whose sessions are all served by `router`
+    pub fn with_router(router: DocRouter) -> Self {
         Self {
             txs: Default::default(),
+            sessions: Default::default(),
+            docs: router,
+            auth: HttpAuth::default(),
+            cors: Cors::default(),
+            limits: SessionLimits::default(),
+            websockets: Default::default(),
+            shutdown: CancellationToken::new(),
         }
     }
+
+    /// Apply `limits` to the sessions of every endpoint
+    pub fn with_limits(mut self, limits: SessionLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Require callers to authenticate with `auth`
+    pub fn with_auth(mut self, auth: HttpAuth) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Apply the cross-origin policy `cors` to browser requests
+    pub fn with_cors(mut self, cors: Cors) -> Self {
+        self.cors = cors;
+        self
+    }
+
+    /// Routes of the server with their middleware
+    ///
+    /// The probes are added after the auth layer so load balancers need no token, and CORS is
+    /// the outermost layer so preflight requests are answered before authentication.
     pub fn router(&self) -> Router {
-        Router::new()
+        let router = Router::new()
             .route("/sse", get(sse_handler).post(post_event_handler))
+            .route(
+                "/mcp",
+                post(streamable_http::post_handler)
+                    .get(streamable_http::get_handler)
+                    .delete(streamable_http::delete_handler),
+            )
+            .route("/metrics", get(monitoring::metrics_handler));
+        #[cfg(feature = "websocket")]
+        let router = router.route("/ws", get(crate::transport::websocket::ws_handler));
+        router
+            .route_layer(middleware::from_fn_with_state(self.clone(), http_auth::require_auth))
+            .route("/healthz", get(monitoring::healthz_handler))
+            .route("/readyz", get(monitoring::readyz_handler))
+            .layer(middleware::from_fn_with_state(self.clone(), cors::apply_cors))
             .with_state(self.clone())
     }
+
+    /// Serve on `listener` until `signal` resolves, then shut down gracefully
+    ///
+    /// Once signalled, the server stops accepting connections and sessions, gives the
+    /// requests already running up to `grace` to answer, and waits for the cache to finish
+    /// writing to disk.
+    pub async fn serve<L>(
+        self,
+        listener: L,
+        signal: impl Future<Output = ()> + Send + 'static,
+        grace: Duration,
+    ) -> io::Result<()>
+    where
+        L: Listener,
+        L::Addr: Debug,
+    {
+        let app = self.clone();
+        let server = axum::serve(listener, self.router()).with_graceful_shutdown(async move {
+            signal.await;
+            app.shut_down().await;
+        });
+        let deadline = async {
+            self.shutdown.cancelled().await;
+            tokio::time::sleep(grace).await;
+        };
+        tokio::select! {
+            result = async { server.await } => result?,
+            _ = deadline => tracing::warn!(?grace, "requests still running after the shutdown deadline were dropped"),
+        }
+        self.docs.cache.flush().await;
+        tracing::info!("server stopped");
+        Ok(())
+    }
+
+    /// Refuse new sessions and end the open ones as soon as their running requests answer
+    ///
+    /// A `/mcp` request still running holds its session and answers on its own response.
+    pub async fn shut_down(&self) {
+        tracing::info!("shutting down, draining sessions");
+        self.shutdown.cancel();
+        let sse: Vec<SseSession> = self.txs.write().await.drain().map(|(_, session)| session).collect();
+        for session in sse {
+            session.close().await;
+        }
+        self.sessions.write().await.clear();
+    }
+
+    /// Number of `/sse`, `/mcp` and `/ws` sessions open
+    pub async fn session_count(&self) -> usize {
+        let sse = self.txs.read().await.len();
+        sse + self.sessions.read().await.len() + self.websockets.load(Ordering::Relaxed)
+    }
+
+    /// `503 Service Unavailable` if the server is shutting down or `open` sessions already reach the limit
+    pub(crate) fn refuse_session(&self, open: usize) -> Option<Response> {
+        if self.shutdown.is_cancelled() {
+            return Some((StatusCode::SERVICE_UNAVAILABLE, "Server is shutting down").into_response());
+        }
+        let max = self.limits.max_sessions.filter(|max| open >= *max)?;
+        tracing::warn!(max, "session limit reached, refusing a new session");
+        Some((StatusCode::SERVICE_UNAVAILABLE, "Too many open sessions, try again later").into_response())
+    }
+
+    /// SSE response for `stream`, with keep-alive comments when configured
+    pub(crate) fn sse<S, E>(&self, stream: S) -> Response
+    where
+        S: Stream<Item = Result<Event, E>> + Send + 'static,
+        E: Into<axum::BoxError>,
+    {
+        match self.limits.keep_alive {
+            Some(interval) => Sse::new(stream)
+                .keep_alive(KeepAlive::new().interval(interval))
+                .into_response(),
+            None => Sse::new(stream).into_response(),
+        }
+    }
 }
 
-fn session_id() -> SessionId {
+pub(crate) fn session_id() -> SessionId {
     let id = format!("{:016x}", rand::random::<u128>());
     Arc::from(id)
 }
@@ -55,17 +290,24 @@ pub struct PostEventQuery {
     pub session_id: String,
 }
 
+/// `POST /sse`: a message for the SSE session named by the `sessionId` query parameter
+///
+/// Another caller's session is reported as missing, so session ids cannot be probed.
 async fn post_event_handler(
     State(app): State<App>,
+    Extension(Caller(caller)): Extension<Caller>,
     Query(PostEventQuery { session_id }): Query<PostEventQuery>,
     body: Body,
 ) -> Result<StatusCode, StatusCode> {
     const BODY_BYTES_LIMIT: usize = 1 << 22;
     let write_stream = {
         let rg = app.txs.read().await;
-        rg.get(session_id.as_str())
-            .ok_or(StatusCode::NOT_FOUND)?
-            .clone()
+        let session = rg
+            .get(session_id.as_str())
+            .filter(|session| session.owner == caller)
+            .ok_or(StatusCode::NOT_FOUND)?;
+        session.activity.touch();
+        session.writer.clone()
     };
     let mut write_stream = write_stream.lock().await;
     let mut body = body.into_data_stream();
@@ -96,32 +338,72 @@ async fn post_event_handler(
     Ok(StatusCode::ACCEPTED)
 }
 
-async fn sse_handler(State(app): State<App>) -> Sse<impl Stream<Item = Result<Event, io::Error>>> {
+/// `GET /sse`: open a session whose responses arrive as server-sent events
+///
+/// The other sessions are counted before locking, so this never holds both locks. The
+/// stream ends once the session's input is closed and its running requests have answered,
+/// with a `close` event telling the client the server ended the session on purpose.
+async fn sse_handler(
+    State(app): State<App>,
+    Extension(Caller(caller)): Extension<Caller>,
+) -> Response {
     // it's 4KB
     const BUFFER_SIZE: usize = 1 << 12;
     let session = session_id();
-    tracing::info!(%session, "sse connection");
     let (c2s_read, c2s_write) = tokio::io::simplex(BUFFER_SIZE);
     let (s2c_read, s2c_write) = tokio::io::simplex(BUFFER_SIZE);
-    app.txs
-        .write()
-        .await
-        .insert(session.clone(), Arc::new(Mutex::new(c2s_write)));
+    let activity = Arc::new(Activity::default());
+    {
+        let others = app.sessions.read().await.len() + app.websockets.load(Ordering::Relaxed);
+        let mut txs = app.txs.write().await;
+        if let Some(response) = app.refuse_session(txs.len() + others) {
+            return response;
+        }
+        txs.insert(
+            session.clone(),
+            SseSession {
+                owner: caller.clone(),
+                writer: Arc::new(Mutex::new(c2s_write)),
+                activity: activity.clone(),
+            },
+        );
+    }
+    tracing::info!(%session, identity = caller.as_deref(), "sse connection");
+
+    if let Some(timeout) = app.limits.idle_timeout {
+        let txs = app.txs.clone();
+        let session = session.clone();
+        let activity = Arc::downgrade(&activity);
+        tokio::spawn(async move {
+            if !until_idle(activity, timeout).await {
+                return;
+            }
+            let removed = txs.write().await.remove(&session);
+            if let Some(closed) = removed {
+                tracing::info!(%session, "closed idle sse session");
+                closed.close().await;
+            }
+        });
+    }
     {
         let app_clone = app.clone();
         let session = session.clone();
         tokio::spawn(async move {
-            let router = RouterService(DocRouter::new());
-            let server = Server::new(router);
-            let bytes_transport = ByteTransport::new(c2s_read, s2c_write);
+            let router = DocService::new(app_clone.docs.clone());
+            let server = JsonRpcServer::new(router);
             let _result = server
-                .run(bytes_transport)
+                .run(c2s_read, s2c_write)
                 .await
                 .inspect_err(|e| tracing::error!(?e, "server run error"));
             app_clone.txs.write().await.remove(&session);
         });
     }
 
+    let shutdown = app.shutdown.clone();
+    let guard = SseStreamGuard {
+        txs: app.txs.clone(),
+        session: session.clone(),
+    };
     let stream = futures::stream::once(futures::future::ok(
         Event::default()
             .event("endpoint")
@@ -134,6 +416,34 @@ async fn sse_handler(State(app): State<App>) -> Sse<impl Stream<Item = Result<Ev
                 Ok(message) => futures::future::ok(Event::default().event("message").data(message)),
                 Err(e) => futures::future::err(io::Error::new(io::ErrorKind::InvalidData, e)),
             }),
-    );
-    Sse::new(stream)
+    )
+    .chain(futures::stream::once(async move {
+        let reason = if shutdown.is_cancelled() { "shutdown" } else { "closed" };
+        Ok(Event::default().event("close").data(reason))
+    }))
+    .map(move |event| {
+        let _ = &guard;
+        event
+    });
+    app.sse(stream)
+}
+
+/// Removes an `/sse` session once its event stream is dropped, i.e. the client went away
+struct SseStreamGuard {
+    txs: SseSessions,
+    session: SessionId,
+}
+
+impl Drop for SseStreamGuard {
+    fn drop(&mut self) {
+        let txs = self.txs.clone();
+        let session = self.session.clone();
+        tokio::spawn(async move {
+            let removed = txs.write().await.remove(&session);
+            if let Some(closed) = removed {
+                tracing::info!(%session, "sse client disconnected");
+                closed.close().await;
+            }
+        });
+    }
 }
\ No newline at end of file
@@ -1,5 +1,9 @@
-use std::sync::Arc;
-use crate::transport::http_sse_server::App;
+use std::{sync::Arc, time::Duration};
+use crate::test_support::serve;
+use crate::tools::docs::registry::RegistryUrls;
+use crate::tools::DocRouter;
+use crate::transport::http_sse_server::{App, SessionLimits, SseSession};
+use serde_json::json;
 
 #[tokio::test]
 async fn test_app_initialization() {
@@ -43,11 +47,158 @@ async fn test_session_management() {
         let (_c2s_read, c2s_write) = tokio::io::simplex(4096);
         let writer = Arc::new(tokio::sync::Mutex::new(c2s_write));
         
-        app.txs.write().await.insert(test_id.clone(), writer);
+        app.txs.write().await.insert(test_id.clone(), SseSession { owner: None, writer, activity: Default::default() });
         
         // Verify session was added
         let txs = app.txs.read().await;
         assert_eq!(txs.len(), 1);
         assert!(txs.contains_key(&test_id));
     }
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_sessions_share_router_cache() {
+    let router = DocRouter::new();
+    let app = App::with_router(router.clone());
+
+    router
+        .cache
+        .set("serde".to_string(), "serde docs".to_string())
+        .await;
+
+    let session_router = app.clone().docs;
+    assert_eq!(
+        session_router.cache.get("serde").await,
+        Some("serde docs".to_string())
+    );
+}
+
+/// Wait until `app` has no `/sse` session left
+async fn wait_for_no_sessions(app: &App) {
+    let closed = async {
+        while !app.txs.read().await.is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    };
+    tokio::time::timeout(Duration::from_secs(5), closed).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_session_limit() {
+    let app = App::new().with_limits(SessionLimits {
+        max_sessions: Some(1),
+        ..SessionLimits::default()
+    });
+    let base = serve(app.clone()).await;
+    let client = reqwest::Client::new();
+
+    let first = client.get(format!("{}/sse", base)).send().await.unwrap();
+    assert_eq!(first.status(), reqwest::StatusCode::OK);
+    assert_eq!(app.session_count().await, 1);
+
+    let second = client.get(format!("{}/sse", base)).send().await.unwrap();
+    assert_eq!(second.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    let initialize = json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} });
+    let streamable = client.post(format!("{}/mcp", base)).json(&initialize).send().await.unwrap();
+    assert_eq!(streamable.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn test_sse_keep_alive_and_disconnect() {
+    let app = App::new().with_limits(SessionLimits {
+        keep_alive: Some(Duration::from_millis(50)),
+        ..SessionLimits::default()
+    });
+    let base = serve(app.clone()).await;
+
+    let mut response = reqwest::get(format!("{}/sse", base)).await.unwrap();
+    let endpoint = response.chunk().await.unwrap().unwrap();
+    assert!(String::from_utf8_lossy(&endpoint).starts_with("event: endpoint"));
+    let keep_alive = response.chunk().await.unwrap().unwrap();
+    assert!(keep_alive.starts_with(b":"));
+    assert_eq!(app.txs.read().await.len(), 1);
+
+    drop(response);
+    wait_for_no_sessions(&app).await;
+}
+
+#[tokio::test]
+async fn test_idle_sessions_closed() {
+    let app = App::new().with_limits(SessionLimits {
+        idle_timeout: Some(Duration::from_millis(200)),
+        keep_alive: None,
+        ..SessionLimits::default()
+    });
+    let base = serve(app.clone()).await;
+
+    let mut response = reqwest::get(format!("{}/sse", base)).await.unwrap();
+    response.chunk().await.unwrap().unwrap();
+    let ended = async { while response.chunk().await.unwrap().is_some() {} };
+    tokio::time::timeout(Duration::from_secs(5), ended).await.unwrap();
+    wait_for_no_sessions(&app).await;
+}
+
+#[tokio::test]
+async fn test_graceful_shutdown() {
+    let mut registry = mockito::Server::new_async().await;
+    registry
+        .mock("GET", mockito::Matcher::Any)
+        .with_status(404)
+        .with_body_from_request(|_| {
+            std::thread::sleep(Duration::from_millis(300));
+            Vec::new()
+        })
+        .create_async()
+        .await;
+    let registries = RegistryUrls {
+        docs_rs: registry.url(),
+        crates_io: registry.url(),
+        crates_index: registry.url(),
+        ..RegistryUrls::default()
+    };
+    let app = App::with_router(DocRouter::new().with_registries(registries));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let (signal, signalled) = tokio::sync::oneshot::channel::<()>();
+    let server = tokio::spawn(app.clone().serve(
+        listener,
+        async move {
+            let _ = signalled.await;
+        },
+        Duration::from_secs(10),
+    ));
+
+    let mut response = reqwest::get(format!("{}/sse", base)).await.unwrap();
+    let endpoint = String::from_utf8(response.chunk().await.unwrap().unwrap().to_vec()).unwrap();
+    let query = endpoint.lines().find_map(|line| line.strip_prefix("data:")).unwrap().trim().to_string();
+    let prompt = json!({
+        "jsonrpc": "2.0",
+        "id": 7,
+        "method": "prompts/get",
+        "params": { "name": "explain_crate", "arguments": { "crate_name": "demo" } }
+    });
+    let posted = reqwest::Client::new()
+        .post(format!("{}/sse{}", base, query))
+        .json(&prompt)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(posted.status(), reqwest::StatusCode::ACCEPTED);
+
+    signal.send(()).unwrap();
+    tokio::time::timeout(Duration::from_secs(5), app.shutdown.cancelled()).await.unwrap();
+    assert!(app.refuse_session(0).is_some());
+
+    let mut rest = String::new();
+    let drained = async {
+        while let Some(chunk) = response.chunk().await.unwrap() {
+            rest.push_str(&String::from_utf8_lossy(&chunk));
+        }
+    };
+    tokio::time::timeout(Duration::from_secs(10), drained).await.unwrap();
+    assert!(rest.contains("\"id\":7"));
+    assert!(rest.trim_end().ends_with("event: close\ndata: shutdown"));
+
+    tokio::time::timeout(Duration::from_secs(5), server).await.unwrap().unwrap().unwrap();
+    assert!(app.txs.read().await.is_empty());
+}
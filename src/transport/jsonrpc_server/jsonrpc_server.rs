@@ -0,0 +1,194 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use futures::StreamExt;
+use mcp_core::protocol::{JsonRpcRequest, JsonRpcResponse};
+use mcp_server::BoxError;
+use serde_json::{json, Value};
+use tokio::{
+    io::{self, AsyncRead, AsyncWrite, AsyncWriteExt},
+    sync::mpsc,
+    task::AbortHandle,
+};
+use tokio_util::codec::FramedRead;
+use tower_service::Service;
+
+use crate::{
+    tools::docs::{
+        progress::Progress,
+        service::{failure, INTERNAL_ERROR, INVALID_REQUEST, PARSE_ERROR},
+    },
+    transport::jsonrpc_frame_codec::JsonRpcFrameCodec,
+};
+
+type InFlight = Arc<Mutex<HashMap<u64, AbortHandle>>>;
+
+/// Runs the JSON-RPC requests of one connection or session, each in its own task
+///
+/// Unlike `mcp_server::Server`, which answers one request at a time and ignores client
+/// notifications, this lets a request carrying a `_meta.progressToken` send
+/// `notifications/progress` while it runs, and lets `notifications/cancelled` abort it,
+/// dropping its in-flight HTTP requests.
+#[derive(Clone)]
+pub struct Dispatcher<S> {
+    service: S,
+    in_flight: InFlight,
+}
+
+impl<S> Dispatcher<S>
+where
+    S: Service<JsonRpcRequest, Response = JsonRpcResponse> + Clone + Send + 'static,
+    S::Error: Into<BoxError>,
+    S::Future: Send,
+{
+    pub fn new(service: S) -> Self {
+        Self {
+            service,
+            in_flight: Default::default(),
+        }
+    }
+
+    /// Handle one message, sending its response and progress notifications to `messages`
+    ///
+    /// Returns whether a response will follow, i.e. whether the message was a request.
+    pub fn dispatch(&self, message: Value, messages: &mpsc::UnboundedSender<Value>) -> bool {
+        match (message.get("method").and_then(Value::as_str), message.get("id")) {
+            (Some("notifications/cancelled"), None) => {
+                let request_id = message.pointer("/params/requestId").and_then(Value::as_u64);
+                let handle = request_id.and_then(|id| self.running().remove(&id));
+                if let Some(handle) = handle {
+                    tracing::debug!(request_id, "request cancelled");
+                    handle.abort();
+                }
+                false
+            }
+            (Some(_), Some(id)) => {
+                let id = id.clone();
+                match serde_json::from_value::<JsonRpcRequest>(message) {
+                    Ok(request) => self.spawn(request, messages),
+                    Err(e) => {
+                        let _ = messages.send(error(id, INVALID_REQUEST, format!("Invalid request: {}", e)));
+                    }
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Abort every running request, e.g. when its session ends
+    pub fn abort_all(&self) {
+        for (_, handle) in self.running().drain() {
+            handle.abort();
+        }
+    }
+
+    fn running(&self) -> std::sync::MutexGuard<'_, HashMap<u64, AbortHandle>> {
+        self.in_flight.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Run `request` in its own task, registered so it can be cancelled
+    ///
+    /// The lock is held until the task is registered, so it cannot finish and deregister first.
+    fn spawn(&self, request: JsonRpcRequest, messages: &mpsc::UnboundedSender<Value>) {
+        let id = request.id;
+        let progress = request
+            .params
+            .as_ref()
+            .and_then(|params| params.pointer("/_meta/progressToken"))
+            .cloned()
+            .map(|token| Progress::new(token, messages.clone()));
+        let mut service = self.service.clone();
+        let messages = messages.clone();
+        let in_flight = self.in_flight.clone();
+
+        let mut running = self.running();
+        let task = tokio::spawn(async move {
+            let call = async move {
+                futures::future::poll_fn(|cx| service.poll_ready(cx)).await?;
+                service.call(request).await
+            };
+            let result = match progress {
+                Some(progress) => progress.scope(call).await,
+                None => call.await,
+            };
+            if let Some(id) = id {
+                in_flight.lock().unwrap_or_else(|e| e.into_inner()).remove(&id);
+            }
+
+            let response = match result {
+                Ok(response) => serde_json::to_value(response).unwrap_or_default(),
+                Err(e) => error(json!(id), INTERNAL_ERROR, e.into().to_string()),
+            };
+            let _ = messages.send(response);
+        });
+        if let Some(id) = id {
+            running.insert(id, task.abort_handle());
+        }
+    }
+}
+
+/// Newline-delimited JSON-RPC server over a byte stream, e.g. stdio
+pub struct JsonRpcServer<S> {
+    service: S,
+}
+
+impl<S> JsonRpcServer<S>
+where
+    S: Service<JsonRpcRequest, Response = JsonRpcResponse> + Clone + Send + 'static,
+    S::Error: Into<BoxError>,
+    S::Future: Send,
+{
+    pub fn new(service: S) -> Self {
+        Self { service }
+    }
+
+    /// Serve messages read from `reader` until it is closed, then wait for the requests still running
+    ///
+    /// Responses and notifications all go through one writer so lines never interleave;
+    /// once every running request has answered, the writer is shut down so whatever reads
+    /// the other end sees the end of the stream.
+    pub async fn run<R, W>(self, reader: R, writer: W) -> io::Result<()>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        let (messages, mut outgoing) = mpsc::unbounded_channel::<Value>();
+        let write_task = tokio::spawn(async move {
+            let mut writer = writer;
+            while let Some(message) = outgoing.recv().await {
+                let mut line = serde_json::to_vec(&message)?;
+                line.push(b'\n');
+                writer.write_all(&line).await?;
+                writer.flush().await?;
+            }
+            writer.shutdown().await
+        });
+
+        let dispatcher = Dispatcher::new(self.service);
+        let mut lines = FramedRead::new(reader, JsonRpcFrameCodec);
+        while let Some(line) = lines.next().await {
+            match serde_json::from_slice(&line?) {
+                Ok(message) => {
+                    dispatcher.dispatch(message, &messages);
+                }
+                Err(e) => {
+                    let _ = messages.send(error(Value::Null, PARSE_ERROR, format!("Parse error: {}", e)));
+                }
+            }
+        }
+
+        drop(messages);
+        write_task.await.map_err(io::Error::other)?
+    }
+}
+
+/// A JSON-RPC error response, as a raw message so it can answer ids that are not a
+/// request's, such as `null` for a message that could not be parsed
+pub(crate) fn error(id: Value, code: i32, message: String) -> Value {
+    let mut response = serde_json::to_value(failure(None, code, message)).unwrap_or_default();
+    response["id"] = id;
+    response
+}
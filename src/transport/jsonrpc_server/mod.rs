@@ -0,0 +1,6 @@
+mod jsonrpc_server;
+pub use jsonrpc_server::{Dispatcher, JsonRpcServer};
+pub(crate) use jsonrpc_server::error;
+
+#[cfg(test)]
+mod tests;
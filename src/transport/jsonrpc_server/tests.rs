@@ -0,0 +1,127 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use crate::tools::docs::progress::report;
+use crate::transport::jsonrpc_server::JsonRpcServer;
+use mcp_core::protocol::{JsonRpcRequest, JsonRpcResponse};
+use mcp_server::BoxError;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader, Lines};
+
+/// Answers `echo` at once; `slow` reports progress and then never finishes
+#[derive(Clone, Default)]
+struct TestService {
+    dropped: Arc<AtomicBool>,
+}
+
+struct DropFlag(Arc<AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+impl tower_service::Service<JsonRpcRequest> for TestService {
+    type Response = JsonRpcResponse;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: JsonRpcRequest) -> Self::Future {
+        let flag = (request.method == "slow").then(|| DropFlag(self.dropped.clone()));
+        Box::pin(async move {
+            let _flag = flag;
+            if request.method == "slow" {
+                report("first step");
+                tokio::time::sleep(Duration::from_secs(600)).await;
+            }
+            Ok(JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                id: request.id,
+                result: Some(json!({ "method": request.method })),
+                error: None,
+            })
+        })
+    }
+}
+
+async fn send(writer: &mut (impl AsyncWrite + Unpin), message: Value) {
+    writer.write_all(format!("{}\n", message).as_bytes()).await.unwrap();
+}
+
+async fn next(lines: &mut Lines<impl AsyncBufRead + Unpin>) -> Value {
+    let line = tokio::time::timeout(Duration::from_secs(5), lines.next_line()).await.unwrap();
+    serde_json::from_str(&line.unwrap().unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_progress_and_cancellation() {
+    let (client, server) = tokio::io::duplex(1 << 16);
+    let (server_read, server_write) = tokio::io::split(server);
+    let (client_read, mut client_write) = tokio::io::split(client);
+    let service = TestService::default();
+    let dropped = service.dropped.clone();
+    let running = tokio::spawn(JsonRpcServer::new(service).run(server_read, server_write));
+
+    let mut lines = BufReader::new(client_read).lines();
+
+    send(&mut client_write, json!({ "jsonrpc": "2.0", "id": 1, "method": "slow", "params": { "_meta": { "progressToken": "lookup" } } })).await;
+    let progress = next(&mut lines).await;
+    assert_eq!(progress["method"], "notifications/progress");
+    assert_eq!(progress["params"], json!({ "progressToken": "lookup", "progress": 1, "message": "first step" }));
+
+    send(&mut client_write, json!({ "jsonrpc": "2.0", "id": 2, "method": "echo" })).await;
+    assert_eq!(next(&mut lines).await["id"], 2);
+
+    send(&mut client_write, json!({ "jsonrpc": "2.0", "method": "notifications/cancelled", "params": { "requestId": 1 } })).await;
+    send(&mut client_write, json!({ "jsonrpc": "2.0", "id": 3, "method": "echo" })).await;
+    assert_eq!(next(&mut lines).await["id"], 3);
+    let aborted = async {
+        while !dropped.load(Ordering::SeqCst) {
+            tokio::task::yield_now().await;
+        }
+    };
+    tokio::time::timeout(Duration::from_secs(5), aborted).await.unwrap();
+
+    drop((client_write, lines));
+    tokio::time::timeout(Duration::from_secs(5), running).await.unwrap().unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn test_invalid_messages() {
+    let (client, server) = tokio::io::duplex(1 << 16);
+    let (server_read, server_write) = tokio::io::split(server);
+    let (client_read, mut client_write) = tokio::io::split(client);
+    tokio::spawn(JsonRpcServer::new(TestService::default()).run(server_read, server_write));
+
+    client_write.write_all(b"not json\n").await.unwrap();
+    client_write
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"method\":\"notifications/initialized\"}\n")
+        .await
+        .unwrap();
+    client_write
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":4,\"method\":\"echo\"}\n")
+        .await
+        .unwrap();
+
+    let mut lines = BufReader::new(client_read).lines();
+    let parse_error: Value = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(parse_error["error"]["code"], -32700);
+    assert_eq!(parse_error["id"], Value::Null);
+
+    let response: Value = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(response["id"], 4);
+    assert_eq!(response["result"]["method"], "echo");
+}
@@ -0,0 +1,86 @@
+use std::{fmt, io, net::SocketAddr, path::PathBuf, str::FromStr};
+
+/// Where the HTTP server listens: a TCP address, or a Unix socket path written `unix:<path>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListenAddress {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+impl FromStr for ListenAddress {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix("unix:") {
+            Some("") => Err(io::Error::new(io::ErrorKind::InvalidInput, "unix: needs a socket path")),
+            Some(path) => Ok(Self::Unix(PathBuf::from(path))),
+            None => s.parse().map(Self::Tcp).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Invalid address {:?} ({}), expected host:port or unix:<path>", s, e),
+                )
+            }),
+        }
+    }
+}
+
+impl fmt::Display for ListenAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "{}", addr),
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// Bind a Unix socket at `path`, giving the socket file the permissions `mode` if set
+///
+/// The socket is bound inside a private directory and only renamed to `path` once its
+/// permissions are set, so no other user can connect in between. Parent directories
+/// writable by every user are refused, since anyone could replace the socket there.
+/// A socket file left behind by a server that is no longer running is replaced; one a
+/// running server still accepts on, or any other kind of file, is not.
+#[cfg(unix)]
+pub fn bind_unix(path: &std::path::Path, mode: Option<u32>) -> io::Result<tokio::net::UnixListener> {
+    use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => std::path::Path::new("."),
+    };
+    if std::fs::metadata(parent)?.permissions().mode() & 0o002 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is writable by every user; put the socket in a private directory", parent.display()),
+        ));
+    }
+
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} exists and is not a socket", path.display()),
+            ));
+        }
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("Another server is listening on {}", path.display()),
+            ));
+        }
+    }
+
+    let private = parent.join(format!(".devdocs-{:016x}", rand::random::<u64>()));
+    std::fs::DirBuilder::new().mode(0o700).create(&private)?;
+    let staged = private.join("socket");
+    let bound = tokio::net::UnixListener::bind(&staged).and_then(|listener| {
+        if let Some(mode) = mode {
+            std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(mode))?;
+        }
+        std::fs::rename(&staged, path)?;
+        Ok(listener)
+    });
+    let _ = std::fs::remove_file(&staged);
+    let _ = std::fs::remove_dir(&private);
+    bound
+}
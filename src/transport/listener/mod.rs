@@ -0,0 +1,8 @@
+mod listener;
+
+pub use listener::ListenAddress;
+#[cfg(unix)]
+pub use listener::bind_unix;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,58 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use crate::transport::listener::ListenAddress;
+
+#[test]
+fn test_parse_listen_address() {
+    let tcp: ListenAddress = "127.0.0.1:8080".parse().unwrap();
+    assert_eq!(tcp, ListenAddress::Tcp("127.0.0.1:8080".parse::<SocketAddr>().unwrap()));
+    assert_eq!(tcp.to_string(), "127.0.0.1:8080");
+
+    let unix: ListenAddress = "unix:/run/devdocs.sock".parse().unwrap();
+    assert_eq!(unix, ListenAddress::Unix(PathBuf::from("/run/devdocs.sock")));
+    assert_eq!(unix.to_string(), "unix:/run/devdocs.sock");
+
+    assert!("unix:".parse::<ListenAddress>().is_err());
+    assert!("localhost".parse::<ListenAddress>().is_err());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_serve_unix_socket() {
+    use crate::test_support::TempDir;
+    use crate::transport::http_sse_server::App;
+    use crate::transport::listener::bind_unix;
+    use std::os::unix::fs::PermissionsExt;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::UnixStream;
+
+    let dir = TempDir::new("socket-test");
+    let path = dir.join("devdocs.sock");
+
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+    let listener = bind_unix(&path, Some(0o600)).unwrap();
+    assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+    tokio::spawn(async move { axum::serve(listener, App::new().router()).await });
+
+    assert_eq!(bind_unix(&path, None).unwrap_err().kind(), std::io::ErrorKind::AddrInUse);
+    std::fs::write(dir.join("file"), "").unwrap();
+    assert!(bind_unix(&dir.join("file"), None).is_err());
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+    let shared = dir.join("shared");
+    std::fs::create_dir(&shared).unwrap();
+    std::fs::set_permissions(&shared, std::fs::Permissions::from_mode(0o777)).unwrap();
+    let refused = bind_unix(&shared.join("devdocs.sock"), None).unwrap_err();
+    assert_eq!(refused.kind(), std::io::ErrorKind::PermissionDenied);
+
+    let mut stream = UnixStream::connect(&path).await.unwrap();
+    stream
+        .write_all(b"GET /healthz HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+    assert!(response.ends_with("\r\n\r\nok"));
+}
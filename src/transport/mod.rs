@@ -1,2 +1,12 @@
+pub mod cors;
+pub mod http_auth;
 pub mod http_sse_server;
-pub mod jsonrpc_frame_codec;
\ No newline at end of file
+pub mod jsonrpc_frame_codec;
+pub mod jsonrpc_server;
+pub mod listener;
+pub mod monitoring;
+pub mod streamable_http;
+#[cfg(feature = "tls")]
+pub mod tls;
+#[cfg(feature = "websocket")]
+pub mod websocket;
\ No newline at end of file
@@ -0,0 +1,6 @@
+mod monitoring;
+
+pub(crate) use monitoring::{healthz_handler, metrics_handler, readyz_handler};
+
+#[cfg(test)]
+mod tests;
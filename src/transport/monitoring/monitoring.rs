@@ -0,0 +1,60 @@
+use std::{fmt::Write, sync::atomic::Ordering};
+
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+
+use crate::{tools::docs::metrics::metrics, transport::http_sse_server::App};
+
+/// `GET /healthz`: the process is up and serving requests
+pub(crate) async fn healthz_handler() -> &'static str {
+    "ok"
+}
+
+/// `GET /readyz`: new sessions would be accepted
+///
+/// Answers `503 Service Unavailable` while the session limit is reached or the server is
+/// shutting down, so a load balancer sends new clients to another instance.
+pub(crate) async fn readyz_handler(State(app): State<App>) -> Response {
+    if app.shutdown.is_cancelled() {
+        return (StatusCode::SERVICE_UNAVAILABLE, "shutting down").into_response();
+    }
+    let open = app.session_count().await;
+    match app.limits.max_sessions {
+        Some(max) if open >= max => (StatusCode::SERVICE_UNAVAILABLE, "session limit reached").into_response(),
+        _ => "ready".into_response(),
+    }
+}
+
+/// `GET /metrics`: sessions, tool calls, upstream latency and cache counters in the
+/// Prometheus text format
+pub(crate) async fn metrics_handler(State(app): State<App>) -> Response {
+    let mut out = String::new();
+
+    let sessions = [
+        ("sse", app.txs.read().await.len()),
+        ("streamable_http", app.sessions.read().await.len()),
+        ("websocket", app.websockets.load(Ordering::Relaxed)),
+    ];
+    out.push_str("# HELP devdocs_active_sessions Open sessions by transport.\n");
+    out.push_str("# TYPE devdocs_active_sessions gauge\n");
+    for (transport, count) in sessions {
+        let _ = writeln!(out, "devdocs_active_sessions{{transport=\"{}\"}} {}", transport, count);
+    }
+
+    let stats = app.docs.cache.stats().await;
+    let counters = [
+        ("devdocs_cache_hits_total", "Documentation cache lookups answered from the cache.", "counter", stats.hits),
+        ("devdocs_cache_misses_total", "Documentation cache lookups that had to be fetched.", "counter", stats.misses),
+        ("devdocs_cache_evictions_total", "Entries evicted from the in-memory cache.", "counter", stats.evictions),
+        ("devdocs_cache_entries", "Entries held in the in-memory cache.", "gauge", stats.entries as u64),
+    ];
+    for (name, help, kind, value) in counters {
+        let _ = writeln!(out, "# HELP {} {}\n# TYPE {} {}\n{} {}", name, help, name, kind, name, value);
+    }
+
+    metrics().render(&mut out);
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")], out).into_response()
+}
@@ -0,0 +1,49 @@
+use crate::test_support::serve;
+use crate::transport::http_auth::HttpAuth;
+use crate::transport::http_sse_server::{App, SessionLimits};
+use reqwest::{Client, StatusCode};
+
+#[tokio::test]
+async fn test_health_and_metrics() {
+    let app = App::new().with_auth(HttpAuth::default().with_token("scraper"));
+    let base = serve(app).await;
+    let client = Client::new();
+
+    let health = client.get(format!("{}/healthz", base)).send().await.unwrap();
+    assert_eq!(health.status(), StatusCode::OK);
+    let ready = client.get(format!("{}/readyz", base)).send().await.unwrap();
+    assert_eq!(ready.status(), StatusCode::OK);
+    let metrics = client.get(format!("{}/metrics", base)).send().await.unwrap();
+    assert_eq!(metrics.status(), StatusCode::UNAUTHORIZED);
+
+    let sse = client.get(format!("{}/sse", base)).bearer_auth("scraper").send().await.unwrap();
+    assert_eq!(sse.status(), StatusCode::OK);
+    let metrics = client
+        .get(format!("{}/metrics", base))
+        .bearer_auth("scraper")
+        .send()
+        .await
+        .unwrap();
+    assert!(metrics.headers()["content-type"].to_str().unwrap().starts_with("text/plain; version=0.0.4"));
+    let body = metrics.text().await.unwrap();
+    assert!(body.contains("# TYPE devdocs_active_sessions gauge\n"));
+    assert!(body.contains("devdocs_active_sessions{transport=\"sse\"} 1\n"));
+    assert!(body.contains("devdocs_active_sessions{transport=\"streamable_http\"} 0\n"));
+    assert!(body.contains("devdocs_cache_hits_total 0\n"));
+    assert!(body.contains("# TYPE devdocs_tool_calls_total counter\n"));
+}
+
+#[tokio::test]
+async fn test_not_ready_at_session_limit() {
+    let app = App::new().with_limits(SessionLimits {
+        max_sessions: Some(1),
+        ..SessionLimits::default()
+    });
+    let base = serve(app).await;
+
+    let _sse = reqwest::get(format!("{}/sse", base)).await.unwrap();
+    let ready = reqwest::get(format!("{}/readyz", base)).await.unwrap();
+    assert_eq!(ready.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let health = reqwest::get(format!("{}/healthz", base)).await.unwrap();
+    assert_eq!(health.status(), StatusCode::OK);
+}
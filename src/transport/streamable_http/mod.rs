@@ -0,0 +1,6 @@
+mod streamable_http;
+pub use streamable_http::{Session, Sessions, SESSION_HEADER};
+pub(crate) use streamable_http::{delete_handler, get_handler, post_handler};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,299 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::Infallible,
+    sync::{atomic::Ordering, Arc, Mutex},
+};
+
+use axum::{
+    body::Bytes,
+    extract::{Extension, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{sse::Event, IntoResponse, Response},
+    Json,
+};
+use futures::Stream;
+use serde_json::Value;
+use tokio::sync::{mpsc, watch, RwLock};
+
+use crate::{
+    tools::{docs::service::PARSE_ERROR, DocRouter, DocService},
+    transport::{
+        http_auth::{Caller, Identity},
+        http_sse_server::{session_id, until_idle, Activity, App, SessionId},
+        jsonrpc_server::{error, Dispatcher},
+    },
+};
+
+/// Header carrying the session id assigned on `initialize`
+pub const SESSION_HEADER: &str = "mcp-session-id";
+
+/// Header a client resuming an SSE stream sends with the id of the last event it received
+const LAST_EVENT_ID: &str = "last-event-id";
+
+/// SSE streams remembered per session for resumption, oldest dropped first
+const STREAMS_KEPT: usize = 16;
+
+pub type Sessions = Arc<RwLock<HashMap<SessionId, Arc<Session>>>>;
+
+/// One Streamable HTTP session, from `initialize` until the client deletes it
+pub struct Session {
+    /// Identity that initialized the session; other callers cannot use it
+    pub owner: Option<Identity>,
+    dispatcher: Dispatcher<DocService>,
+    streams: Mutex<Streams>,
+    activity: Arc<Activity>,
+}
+
+#[derive(Default)]
+struct Streams {
+    next_id: u64,
+    logs: VecDeque<(u64, watch::Sender<StreamLog>)>,
+}
+
+/// Every message sent on one SSE stream so far, kept so a dropped client can resume
+#[derive(Default)]
+struct StreamLog {
+    messages: Vec<Value>,
+    done: bool,
+}
+
+impl Session {
+    pub fn new(router: DocRouter, owner: Option<Identity>) -> Self {
+        Self {
+            owner,
+            dispatcher: Dispatcher::new(DocService::new(router)),
+            streams: Default::default(),
+            activity: Default::default(),
+        }
+    }
+
+    /// Abort the requests still running, e.g. when the session is deleted
+    pub fn close(&self) {
+        self.dispatcher.abort_all();
+    }
+
+    fn open_stream(&self) -> (u64, watch::Sender<StreamLog>) {
+        let mut streams = self.streams.lock().unwrap_or_else(|e| e.into_inner());
+        let id = streams.next_id;
+        streams.next_id += 1;
+        let (log, _) = watch::channel(StreamLog::default());
+        streams.logs.push_back((id, log.clone()));
+        if streams.logs.len() > STREAMS_KEPT {
+            streams.logs.pop_front();
+        }
+        (id, log)
+    }
+
+    fn stream(&self, id: u64) -> Option<watch::Receiver<StreamLog>> {
+        let streams = self.streams.lock().unwrap_or_else(|e| e.into_inner());
+        streams
+            .logs
+            .iter()
+            .find(|(stream, _)| *stream == id)
+            .map(|(_, log)| log.subscribe())
+    }
+}
+
+/// `POST /mcp`: one JSON-RPC message or a batch
+///
+/// Requests are answered with JSON, or with an SSE stream when the client accepts one and
+/// asked for progress, so the notifications arrive before the response.
+///
+/// The other sessions are counted before locking, so this never holds both locks.
+pub(crate) async fn post_handler(
+    State(app): State<App>,
+    Extension(Caller(caller)): Extension<Caller>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let (batch, messages) = match serde_json::from_slice(&body) {
+        Ok(Value::Array(messages)) => (true, messages),
+        Ok(message) => (false, vec![message]),
+        Err(e) => {
+            let parse_error = error(Value::Null, PARSE_ERROR, format!("Parse error: {}", e));
+            return (StatusCode::BAD_REQUEST, Json(parse_error)).into_response();
+        }
+    };
+
+    let initializing = messages
+        .iter()
+        .any(|message| message.get("method").and_then(Value::as_str) == Some("initialize"));
+    let (id, session) = if initializing {
+        let id = session_id();
+        let session = Arc::new(Session::new(app.docs.clone(), caller.clone()));
+        {
+            let others = app.txs.read().await.len() + app.websockets.load(Ordering::Relaxed);
+            let mut sessions = app.sessions.write().await;
+            if let Some(response) = app.refuse_session(sessions.len() + others) {
+                return response;
+            }
+            sessions.insert(id.clone(), session.clone());
+        }
+        tracing::info!(session = %id, identity = caller.as_deref(), "streamable http session");
+        if let Some(timeout) = app.limits.idle_timeout {
+            let sessions = app.sessions.clone();
+            let id = id.clone();
+            let activity = Arc::downgrade(&session.activity);
+            tokio::spawn(async move {
+                if !until_idle(activity, timeout).await {
+                    return;
+                }
+                if let Some(session) = sessions.write().await.remove(&id) {
+                    tracing::info!(session = %id, "closed idle streamable http session");
+                    session.close();
+                }
+            });
+        }
+        (id, session)
+    } else {
+        match find_session(&app, &caller, &headers).await {
+            Ok(found) => found,
+            Err(response) => return response,
+        }
+    };
+
+    let wants_progress = messages
+        .iter()
+        .any(|message| message.pointer("/params/_meta/progressToken").is_some());
+    let (sender, mut outgoing) = mpsc::unbounded_channel();
+    let requests = messages
+        .into_iter()
+        .filter(|message| session.dispatcher.dispatch(message.clone(), &sender))
+        .count();
+    drop(sender);
+
+    let mut response = if requests == 0 {
+        StatusCode::ACCEPTED.into_response()
+    } else if wants_progress && accepts(&headers, "text/event-stream") {
+        let (stream, log) = session.open_stream();
+        let replay_from = log.subscribe();
+        tokio::spawn(async move {
+            while let Some(message) = outgoing.recv().await {
+                log.send_modify(|log| log.messages.push(message));
+            }
+            log.send_modify(|log| log.done = true);
+        });
+        app.sse(replay(stream, replay_from, 0))
+    } else {
+        let mut responses = Vec::new();
+        while let Some(message) = outgoing.recv().await {
+            if message.get("method").is_none() {
+                responses.push(message);
+            }
+        }
+        match (batch, responses.pop()) {
+            (_, None) => StatusCode::ACCEPTED.into_response(),
+            (true, Some(last)) => {
+                responses.push(last);
+                Json(Value::Array(responses)).into_response()
+            }
+            (false, Some(response)) => Json(response).into_response(),
+        }
+    };
+
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(SESSION_HEADER, value);
+    }
+    response
+}
+
+/// `GET /mcp`: resume an SSE stream after the event named by `Last-Event-ID`
+///
+/// The server never starts conversations, so a GET without one is not allowed.
+///
+/// Event ids are `{stream}-{index}`.
+pub(crate) async fn get_handler(
+    State(app): State<App>,
+    Extension(Caller(caller)): Extension<Caller>,
+    headers: HeaderMap,
+) -> Response {
+    let (_, session) = match find_session(&app, &caller, &headers).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+    let Some(last_event) = headers.get(LAST_EVENT_ID).and_then(|v| v.to_str().ok()) else {
+        return StatusCode::METHOD_NOT_ALLOWED.into_response();
+    };
+
+    let resumed = last_event
+        .split_once('-')
+        .and_then(|(stream, index)| Some((stream.parse::<u64>().ok()?, index.parse::<usize>().ok()?)))
+        .and_then(|(stream, index)| Some((stream, session.stream(stream)?, index + 1)));
+    match resumed {
+        Some((stream, log, next)) => app.sse(replay(stream, log, next)),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// `DELETE /mcp`: end the session
+pub(crate) async fn delete_handler(
+    State(app): State<App>,
+    Extension(Caller(caller)): Extension<Caller>,
+    headers: HeaderMap,
+) -> Response {
+    let (id, session) = match find_session(&app, &caller, &headers).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+    app.sessions.write().await.remove(&id);
+    session.close();
+    StatusCode::NO_CONTENT.into_response()
+}
+
+/// The session named by the `Mcp-Session-Id` header, if `caller` opened it
+///
+/// Another caller's session is reported as missing, so session ids cannot be probed.
+async fn find_session(
+    app: &App,
+    caller: &Option<Identity>,
+    headers: &HeaderMap,
+) -> Result<(SessionId, Arc<Session>), Response> {
+    let Some(id) = headers.get(SESSION_HEADER).and_then(|v| v.to_str().ok()) else {
+        return Err((StatusCode::BAD_REQUEST, "Missing Mcp-Session-Id header").into_response());
+    };
+    match app.sessions.read().await.get(id).filter(|session| session.owner == *caller) {
+        Some(session) => {
+            session.activity.touch();
+            Ok((Arc::from(id), session.clone()))
+        }
+        None => Err(StatusCode::NOT_FOUND.into_response()),
+    }
+}
+
+fn accepts(headers: &HeaderMap, content_type: &str) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .any(|accept| accept.contains(content_type))
+}
+
+/// Events of stream `stream` from index `next` on, waiting for new ones until it is done
+fn replay(
+    stream: u64,
+    log: watch::Receiver<StreamLog>,
+    next: usize,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    futures::stream::unfold((log, next), move |(mut log, next)| async move {
+        loop {
+            let message = {
+                let current = log.borrow_and_update();
+                match current.messages.get(next) {
+                    Some(message) => Some(message.clone()),
+                    None if current.done => return None,
+                    None => None,
+                }
+            };
+            if let Some(message) = message {
+                let event = Event::default()
+                    .event("message")
+                    .id(format!("{}-{}", stream, next))
+                    .data(message.to_string());
+                return Some((Ok(event), (log, next + 1)));
+            }
+            if log.changed().await.is_err() {
+                return None;
+            }
+        }
+    })
+}
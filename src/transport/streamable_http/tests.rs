@@ -0,0 +1,177 @@
+use crate::test_support::serve;
+use crate::tools::docs::registry::RegistryUrls;
+use crate::tools::DocRouter;
+use crate::transport::http_sse_server::App;
+use crate::transport::streamable_http::SESSION_HEADER;
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Value};
+
+async fn initialize(client: &Client, url: &str) -> String {
+    let response = client
+        .post(url)
+        .header("Accept", "application/json, text/event-stream")
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let session = response.headers()[SESSION_HEADER].to_str().unwrap().to_string();
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["id"], 1);
+    session
+}
+
+#[tokio::test]
+async fn test_streamable_http_session() {
+    let url = format!("{}/mcp", serve(App::new()).await);
+    let client = Client::new();
+    let templates = json!({ "jsonrpc": "2.0", "id": 2, "method": "resources/templates/list" });
+
+    let response = client.post(&url).json(&templates).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = client.post(&url).header(SESSION_HEADER, "unknown").json(&templates).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let session = initialize(&client, &url).await;
+
+    let response = client
+        .post(&url)
+        .header(SESSION_HEADER, &session)
+        .json(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    let completion = json!({
+        "jsonrpc": "2.0",
+        "id": 3,
+        "method": "completion/complete",
+        "params": { "ref": { "type": "ref/prompt", "name": "find_api" }, "argument": { "name": "ecosystem", "value": "ru" } }
+    });
+    let response = client
+        .post(&url)
+        .header(SESSION_HEADER, &session)
+        .json(&json!([templates, completion]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["content-type"], "application/json");
+    let body: Value = response.json().await.unwrap();
+    let ids: Vec<&Value> = body.as_array().unwrap().iter().map(|r| &r["id"]).collect();
+    assert!(ids.contains(&&json!(2)) && ids.contains(&&json!(3)));
+
+    let response = client.get(&url).header(SESSION_HEADER, &session).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+    let response = client.delete(&url).header(SESSION_HEADER, &session).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = client.post(&url).header(SESSION_HEADER, &session).json(&templates).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_streamable_http_progress_stream() {
+    let server = mockito::Server::new_async().await;
+    let registries = RegistryUrls {
+        docs_rs: server.url(),
+        crates_io: server.url(),
+        crates_index: server.url(),
+        ..RegistryUrls::default()
+    };
+    let url = format!("{}/mcp", serve(App::with_router(DocRouter::new().with_registries(registries))).await);
+    let client = Client::new();
+    let session = initialize(&client, &url).await;
+
+    let response = client
+        .post(&url)
+        .header(SESSION_HEADER, &session)
+        .header("Accept", "application/json, text/event-stream")
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 4,
+            "method": "prompts/get",
+            "params": {
+                "name": "explain_crate",
+                "arguments": { "crate_name": "demo" },
+                "_meta": { "progressToken": "explain" }
+            }
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.headers()["content-type"].to_str().unwrap().starts_with("text/event-stream"));
+    let body = response.text().await.unwrap();
+    let events: Vec<(&str, Value)> = body
+        .split("\n\n")
+        .filter(|event| !event.trim().is_empty())
+        .map(|event| {
+            let field = |name: &str| {
+                event
+                    .lines()
+                    .find_map(|line| line.strip_prefix(name))
+                    .unwrap()
+                    .trim()
+            };
+            (field("id:"), serde_json::from_str(field("data:")).unwrap())
+        })
+        .collect();
+    assert_eq!(events[0].0, "0-0");
+    assert_eq!(events[0].1["method"], "notifications/progress");
+    assert_eq!(events[0].1["params"]["progressToken"], "explain");
+    let (last_id, last) = events.last().unwrap();
+    assert_eq!(last["id"], 4);
+
+    let response = client
+        .get(&url)
+        .header(SESSION_HEADER, &session)
+        .header("Last-Event-ID", "0-0")
+        .send()
+        .await
+        .unwrap();
+    let replayed = response.text().await.unwrap();
+    assert!(!replayed.contains("id: 0-0\n"));
+    assert!(replayed.contains(&format!("id: {}\n", last_id)));
+
+    let response = client
+        .get(&url)
+        .header(SESSION_HEADER, &session)
+        .header("Last-Event-ID", "9-0")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_streamable_http_cancelled_request() {
+    let stalled = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let registries = RegistryUrls {
+        docs_rs: format!("http://{}", stalled.local_addr().unwrap()),
+        ..RegistryUrls::default()
+    };
+    let url = format!("{}/mcp", serve(App::with_router(DocRouter::new().with_registries(registries))).await);
+    let client = Client::new();
+    let session = initialize(&client, &url).await;
+
+    let explain = client
+        .post(&url)
+        .header(SESSION_HEADER, &session)
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 5,
+            "method": "prompts/get",
+            "params": { "name": "explain_crate", "arguments": { "crate_name": "demo" } }
+        }))
+        .send();
+    let explain = tokio::spawn(explain);
+    let (_connection, _) = stalled.accept().await.unwrap();
+
+    let cancel = json!({ "jsonrpc": "2.0", "method": "notifications/cancelled", "params": { "requestId": 5 } });
+    let response = client.post(&url).header(SESSION_HEADER, &session).json(&cancel).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    let response = explain.await.unwrap().unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    assert!(response.text().await.unwrap().is_empty());
+}
@@ -0,0 +1,6 @@
+mod tls;
+
+pub use tls::{TlsCertificate, TlsListener};
+
+#[cfg(test)]
+mod tests;
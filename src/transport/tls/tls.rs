@@ -0,0 +1,186 @@
+use std::{
+    fmt::Debug,
+    fs::File,
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
+};
+
+use axum::serve::Listener;
+use tokio::{sync::mpsc, task::JoinHandle};
+use tokio_rustls::{
+    rustls::{crypto::ring, ServerConfig},
+    server::TlsStream,
+    TlsAcceptor,
+};
+
+/// Time a client gets to complete the TLS handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Completed handshakes waiting for the server to pick them up
+const HANDSHAKES_QUEUED: usize = 64;
+
+/// Certificate and key served by a [`TlsListener`]
+///
+/// Clones share the loaded configuration, so a reload reaches every listener using it.
+#[derive(Clone)]
+pub struct TlsCertificate {
+    cert: PathBuf,
+    key: PathBuf,
+    config: Arc<RwLock<Arc<ServerConfig>>>,
+}
+
+impl TlsCertificate {
+    /// Load the PEM certificate chain at `cert` and the private key at `key`
+    pub fn load(cert: impl Into<PathBuf>, key: impl Into<PathBuf>) -> io::Result<Self> {
+        let (cert, key) = (cert.into(), key.into());
+        let config = server_config(&cert, &key)?;
+        Ok(Self {
+            cert,
+            key,
+            config: Arc::new(RwLock::new(Arc::new(config))),
+        })
+    }
+
+    /// Check the files every `interval` and serve them again once either changes
+    ///
+    /// A renewed pair that fails to load is logged and the previous one kept.
+    ///
+    /// The check stops once every listener using the certificate is gone.
+    pub fn reload_every(self, interval: Duration) -> Self {
+        let (cert, key) = (self.cert.clone(), self.key.clone());
+        let config = Arc::downgrade(&self.config);
+        tokio::spawn(async move {
+            let mut loaded = modified(&cert, &key).await;
+            loop {
+                tokio::time::sleep(interval).await;
+                let Some(config) = config.upgrade() else {
+                    return;
+                };
+                let current = modified(&cert, &key).await;
+                if current == loaded {
+                    continue;
+                }
+                loaded = current;
+                match server_config(&cert, &key) {
+                    Ok(reloaded) => {
+                        *config.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(reloaded);
+                        tracing::info!(cert = %cert.display(), "reloaded TLS certificate");
+                    }
+                    Err(e) => tracing::warn!(?e, cert = %cert.display(), "keeping the previous TLS certificate"),
+                }
+            }
+        });
+        self
+    }
+
+    fn acceptor(&self) -> TlsAcceptor {
+        TlsAcceptor::from(self.config.read().unwrap_or_else(|e| e.into_inner()).clone())
+    }
+}
+
+/// Server config for the pair at `cert` and `key`
+///
+/// Only HTTP/1.1 is offered over ALPN, as axum serves nothing else.
+fn server_config(cert: &Path, key: &Path) -> io::Result<ServerConfig> {
+    let open = |path: &Path| {
+        File::open(path)
+            .map(BufReader::new)
+            .map_err(|e| io::Error::new(e.kind(), format!("Failed to read {}: {}", path.display(), e)))
+    };
+    let certs = rustls_pemfile::certs(&mut open(cert)?).collect::<Result<Vec<_>, _>>()?;
+    if certs.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("No certificate found in {}", cert.display()),
+        ));
+    }
+    let key = rustls_pemfile::private_key(&mut open(key)?)?.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("No private key found in {}", key.display()))
+    })?;
+
+    let mut config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(io::Error::other)?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+async fn modified(cert: &Path, key: &Path) -> Option<(SystemTime, SystemTime)> {
+    let cert = tokio::fs::metadata(cert).await.and_then(|m| m.modified()).ok()?;
+    let key = tokio::fs::metadata(key).await.and_then(|m| m.modified()).ok()?;
+    Some((cert, key))
+}
+
+/// Terminates TLS on the connections accepted by another listener, e.g. a `TcpListener`
+///
+/// Handshakes run concurrently, so a slow or stalled client cannot hold up the others.
+pub struct TlsListener<L: Listener> {
+    incoming: mpsc::Receiver<(TlsStream<L::Io>, L::Addr)>,
+    local_addr: Option<L::Addr>,
+    accepting: JoinHandle<()>,
+}
+
+impl<L> TlsListener<L>
+where
+    L: Listener,
+    L::Addr: Clone + 'static,
+{
+    pub fn new(mut inner: L, certificate: TlsCertificate) -> Self {
+        let local_addr = inner.local_addr().ok();
+        let (handshaken, incoming) = mpsc::channel(HANDSHAKES_QUEUED);
+        let accepting = tokio::spawn(async move {
+            loop {
+                let (io, addr) = inner.accept().await;
+                let acceptor = certificate.acceptor();
+                let handshaken = handshaken.clone();
+                tokio::spawn(async move {
+                    match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(io)).await {
+                        Ok(Ok(stream)) => {
+                            let _ = handshaken.send((stream, addr)).await;
+                        }
+                        Ok(Err(e)) => tracing::debug!(?e, "TLS handshake failed"),
+                        Err(_) => tracing::debug!("TLS handshake timed out"),
+                    }
+                });
+            }
+        });
+        Self {
+            incoming,
+            local_addr,
+            accepting,
+        }
+    }
+}
+
+impl<L: Listener> Drop for TlsListener<L> {
+    fn drop(&mut self) {
+        self.accepting.abort();
+    }
+}
+
+impl<L> Listener for TlsListener<L>
+where
+    L: Listener,
+    L::Addr: Clone + Debug + 'static,
+{
+    type Io = TlsStream<L::Io>;
+    type Addr = L::Addr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.incoming.recv().await {
+            Some(connection) => connection,
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        self.local_addr
+            .clone()
+            .ok_or_else(|| io::Error::other("the underlying listener has no local address"))
+    }
+}
@@ -0,0 +1,6 @@
+mod websocket;
+pub use websocket::serve_websocket;
+pub(crate) use websocket::ws_handler;
+
+#[cfg(test)]
+mod tests;
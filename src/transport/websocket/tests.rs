@@ -0,0 +1,97 @@
+use crate::test_support::listen;
+use crate::transport::http_sse_server::{App, SessionLimits};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Send one masked text frame, as clients must
+async fn send_text(stream: &mut TcpStream, text: &str) {
+    let mask = [0x12, 0x34, 0x56, 0x78];
+    let payload = text.as_bytes();
+    let mut frame = vec![0x81];
+    match payload.len() {
+        len @ 0..=125 => frame.push(0x80 | len as u8),
+        len => {
+            frame.push(0x80 | 126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(&mask);
+    frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+    stream.write_all(&frame).await.unwrap();
+}
+
+/// Read one unmasked text frame sent by the server
+async fn read_text(stream: &mut TcpStream) -> String {
+    let mut header = [0u8; 2];
+    stream.read_exact(&mut header).await.unwrap();
+    assert_eq!(header[0], 0x81, "expected a final text frame");
+    let len = match header[1] & 0x7f {
+        126 => stream.read_u16().await.unwrap() as usize,
+        127 => stream.read_u64().await.unwrap() as usize,
+        len => len as usize,
+    };
+    let mut payload = vec![0u8; len];
+    stream.read_exact(&mut payload).await.unwrap();
+    String::from_utf8(payload).unwrap()
+}
+
+/// Ask for a WebSocket upgrade and return the stream with the response head, read byte by
+/// byte so no frame data is consumed
+async fn connect(addr: SocketAddr) -> (TcpStream, String) {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let handshake = format!(
+        "GET /ws HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n",
+        addr
+    );
+    stream.write_all(handshake.as_bytes()).await.unwrap();
+
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        response.push(stream.read_u8().await.unwrap());
+    }
+    (stream, String::from_utf8(response).unwrap())
+}
+
+#[tokio::test]
+async fn test_websocket_bridge() {
+    let (mut stream, response) = connect(listen(App::new()).await).await;
+    assert!(response.starts_with("HTTP/1.1 101"), "{}", response);
+    assert!(response.contains("s3pPLMBiTxaQ9kYGzzhZRbK+xOo="));
+
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "resources/templates/list" });
+    send_text(&mut stream, &serde_json::to_string_pretty(&request).unwrap()).await;
+    let reply: Value = serde_json::from_str(&read_text(&mut stream).await).unwrap();
+    assert_eq!(reply["id"], 1);
+    assert!(reply["result"]["resourceTemplates"].is_array());
+}
+
+#[tokio::test]
+async fn test_websocket_session_limits() {
+    let app = App::new().with_limits(SessionLimits {
+        max_sessions: Some(1),
+        idle_timeout: Some(Duration::from_millis(200)),
+        ..SessionLimits::default()
+    });
+    let addr = listen(app.clone()).await;
+
+    let (mut first, response) = connect(addr).await;
+    assert!(response.starts_with("HTTP/1.1 101"), "{}", response);
+    assert_eq!(app.session_count().await, 1);
+    let (_, response) = connect(addr).await;
+    assert!(response.starts_with("HTTP/1.1 503"), "{}", response);
+    let sse = reqwest::get(format!("http://{}/sse", addr)).await.unwrap();
+    assert_eq!(sse.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+
+    let closed = tokio::time::timeout(Duration::from_secs(5), first.read_u8()).await.unwrap();
+    assert_eq!(closed.unwrap(), 0x88, "expected a close frame");
+    let released = async {
+        while app.session_count().await > 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    };
+    tokio::time::timeout(Duration::from_secs(5), released).await.unwrap();
+}
@@ -0,0 +1,134 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::Response,
+};
+use futures::{SinkExt, StreamExt};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::io::{self, AsyncWriteExt};
+use tokio_util::{codec::FramedRead, sync::CancellationToken};
+
+use crate::{
+    tools::{DocRouter, DocService},
+    transport::{
+        http_sse_server::{until_idle, Activity, App},
+        jsonrpc_frame_codec::JsonRpcFrameCodec,
+        jsonrpc_server::JsonRpcServer,
+    },
+};
+
+/// `GET /ws`: upgrade to a WebSocket carrying one JSON-RPC message per text frame
+///
+/// WebSocket sessions count towards [`SessionLimits::max_sessions`](crate::transport::http_sse_server::SessionLimits)
+/// like `/sse` and `/mcp` ones, and are closed once idle for as long.
+pub(crate) async fn ws_handler(ws: WebSocketUpgrade, State(app): State<App>) -> Response {
+    let slot = WebSocketSlot::take(&app.websockets);
+    if let Some(response) = app.refuse_session(app.session_count().await - 1) {
+        return response;
+    }
+    ws.on_upgrade(move |socket| async move {
+        let _slot = slot;
+        serve_websocket(socket, app.docs.clone(), app.shutdown.clone(), app.limits.idle_timeout).await;
+    })
+}
+
+/// One open WebSocket in [`App::websockets`], given back when dropped, even if the upgrade fails
+struct WebSocketSlot(Arc<AtomicUsize>);
+
+impl WebSocketSlot {
+    fn take(open: &Arc<AtomicUsize>) -> Self {
+        open.fetch_add(1, Ordering::Relaxed);
+        Self(open.clone())
+    }
+}
+
+impl Drop for WebSocketSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Bridge `socket` to a [`JsonRpcServer`] over the same newline-delimited pipes the SSE
+/// sessions use, until the client closes it, it sends nothing for `idle_timeout`, or
+/// `shutdown` is cancelled
+///
+/// Line breaks outside JSON strings are only whitespace, so they are replaced before a
+/// message is passed on as one line. On shutdown, reading stops but the requests already
+/// running answer before the socket closes.
+pub async fn serve_websocket(
+    socket: WebSocket,
+    router: DocRouter,
+    shutdown: CancellationToken,
+    idle_timeout: Option<Duration>,
+) {
+    // it's 4KB
+    const BUFFER_SIZE: usize = 1 << 12;
+    let (c2s_read, mut c2s_write) = io::simplex(BUFFER_SIZE);
+    let (s2c_read, s2c_write) = io::simplex(BUFFER_SIZE);
+    let server = tokio::spawn(JsonRpcServer::new(DocService::new(router)).run(c2s_read, s2c_write));
+    let (mut sink, mut frames) = socket.split();
+
+    let outgoing = tokio::spawn(async move {
+        let mut lines = FramedRead::new(s2c_read, JsonRpcFrameCodec);
+        while let Some(Ok(line)) = lines.next().await {
+            let Ok(text) = String::from_utf8(line.to_vec()) else {
+                continue;
+            };
+            if sink.send(Message::Text(text.into())).await.is_err() {
+                return;
+            }
+        }
+        let _ = sink.send(Message::Close(None)).await;
+    });
+
+    let activity = Arc::new(Activity::default());
+    loop {
+        let idle = async {
+            match idle_timeout {
+                Some(timeout) => until_idle(Arc::downgrade(&activity), timeout).await,
+                None => std::future::pending().await,
+            }
+        };
+        let frame = tokio::select! {
+            frame = frames.next() => frame,
+            _ = shutdown.cancelled() => break,
+            _ = idle => {
+                tracing::info!("closed idle websocket session");
+                break;
+            }
+        };
+        let Some(Ok(frame)) = frame else {
+            break;
+        };
+        activity.touch();
+        let text = match &frame {
+            Message::Text(text) => text.as_str(),
+            Message::Binary(bytes) => match std::str::from_utf8(bytes) {
+                Ok(text) => text,
+                Err(_) => continue,
+            },
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let line = text.replace(['\r', '\n'], " ") + "\n";
+        if c2s_write.write_all(line.as_bytes()).await.is_err() {
+            break;
+        }
+    }
+
+    let _ = c2s_write.shutdown().await;
+    match server.await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => tracing::error!(?e, "server run error"),
+        Err(e) => tracing::error!(?e, "server task failed"),
+    }
+    let _ = outgoing.await;
+}
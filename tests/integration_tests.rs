@@ -78,8 +78,8 @@ async fn test_unimplemented_apis() {
     let result = router.read_resource("test").await;
     assert!(result.is_err());
     
-    assert!(router.list_prompts().is_empty());
+    assert!(!router.list_prompts().is_empty());
     
     let result = router.get_prompt("test").await;
     assert!(result.is_err());
-}
\ No newline at end of file
+}